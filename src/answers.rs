@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::{fs, io};
+
+use crate::day::Answer;
+
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.txt";
+
+/// Identifies a recorded answer: the day, the part and the input it was computed on, see
+/// [`crate::input::InputSource::answer_key`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AnswerKey {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+}
+
+/// Known-good answers, stored as one `dayNN <part> <input> <answer>` line per entry.
+#[derive(Debug, Default, PartialEq)]
+pub struct AnswerStore {
+    answers: BTreeMap<AnswerKey, String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl AnswerStore {
+    /// Loads the store from `path`; a file that does not exist yet is an empty store.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content).map_err(|line| AnswersError::Malformed {
+                location: path.display().to_string(),
+                line,
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(AnswersError::Io {
+                location: path.display().to_string(),
+                source,
+            }),
+        }
+    }
+
+    /// Parses the store, returning the number of the first malformed line on failure.
+    fn parse(content: &str) -> Result<Self, usize> {
+        let mut answers = BTreeMap::new();
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.splitn(4, ' ');
+            let key = (|| {
+                let day = fields.next()?.strip_prefix("day")?.parse().ok()?;
+                let part = fields.next()?.parse().ok()?;
+                let input = fields.next()?.to_string();
+                Some(AnswerKey { day, part, input })
+            })();
+            match (key, fields.next()) {
+                (Some(key), Some(answer)) => answers.insert(key, answer.to_string()),
+                _ => return Err(idx + 1),
+            };
+        }
+        Ok(AnswerStore { answers })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AnswersError> {
+        fs::write(path, self.to_string()).map_err(|source| AnswersError::Io {
+            location: path.display().to_string(),
+            source,
+        })
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &AnswerKey> {
+        self.answers.keys()
+    }
+
+    pub fn get(&self, key: &AnswerKey) -> Option<&str> {
+        self.answers.get(key).map(String::as_str)
+    }
+
+    /// Stores a solved answer, replacing whatever was recorded before. Unsolved answers are ignored.
+    pub fn record(&mut self, key: AnswerKey, answer: &Answer) {
+        if let Answer::Solved(value) = answer {
+            self.answers.insert(key, value.clone());
+        }
+    }
+
+    pub fn verify(&self, key: &AnswerKey, answer: &Answer) -> Verdict {
+        match (self.get(key), answer) {
+            (None, _) => Verdict::Missing,
+            (Some(expected), Answer::Solved(actual)) if expected == actual => Verdict::Pass,
+            (Some(expected), _) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
+
+impl Display for AnswerStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# day part input answer")?;
+        for (key, answer) in &self.answers {
+            writeln!(f, "day{:02} {} {} {}", key.day, key.part, key.input, answer)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io { location: String, source: io::Error },
+    Malformed { location: String, line: usize },
+}
+
+impl Display for AnswersError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnswersError::Io { location, source } => {
+                write!(f, "Could not access answers in {location}: {source}")
+            }
+            AnswersError::Malformed { location, line } => write!(
+                f,
+                "{location}:{line}: expected `dayNN <part> <input> <answer>`"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::answers::{AnswerKey, AnswerStore, Verdict};
+    use crate::day::Answer;
+
+    fn key(day: u8, part: u8) -> AnswerKey {
+        AnswerKey {
+            day,
+            part,
+            input: String::from("input"),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let content = "# day part input answer\nday05 1 input 35\nday12 1 input 7361\n";
+        let store = AnswerStore::parse(content).expect("should parse");
+        assert_eq!(Some("7361"), store.get(&key(12, 1)));
+        assert_eq!(None, store.get(&key(12, 2)));
+        assert_eq!(content, store.to_string());
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert_eq!(Err(2), AnswerStore::parse("day05 1 input 35\nday05 2\n"));
+        assert_eq!(Err(1), AnswerStore::parse("05 1 input 35\n"));
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut store = AnswerStore::default();
+        store.record(key(5, 1), &Answer::from(35));
+        store.record(key(5, 2), &Answer::Unsolved);
+        assert_eq!(Verdict::Pass, store.verify(&key(5, 1), &Answer::from(35)));
+        assert_eq!(
+            Verdict::Fail {
+                expected: String::from("35")
+            },
+            store.verify(&key(5, 1), &Answer::from(36))
+        );
+        assert_eq!(
+            Verdict::Missing,
+            store.verify(&key(5, 2), &Answer::from(46))
+        );
+    }
+}
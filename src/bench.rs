@@ -0,0 +1,155 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BenchConfig {
+    /// Untimed runs of each phase before measuring, to warm up caches and the allocator.
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warmup: 3,
+            iterations: 10,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Runs `phase` for the configured warm-up and iterations, timing each iteration on its own.
+    pub fn measure<T>(&self, mut phase: impl FnMut() -> T) -> Stats {
+        for _ in 0..self.warmup {
+            black_box(phase());
+        }
+        let samples: Vec<Duration> = (0..self.iterations.max(1))
+            .map(|_| {
+                let now = Instant::now();
+                black_box(phase());
+                now.elapsed()
+            })
+            .collect();
+        Stats::of(samples)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    fn of(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "cannot compute stats without samples");
+        samples.sort();
+        let len = samples.len();
+        let median = if len.is_multiple_of(2) {
+            (samples[len / 2 - 1] + samples[len / 2]) / 2
+        } else {
+            samples[len / 2]
+        };
+        // nearest-rank percentile
+        let p95 = samples[(len * 95).div_ceil(100) - 1];
+
+        let secs: Vec<f64> = samples.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / len as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / len as f64;
+
+        Stats {
+            min: samples[0],
+            median,
+            p95,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DayBench {
+    pub day: u8,
+    pub phases: Vec<(&'static str, Stats)>,
+}
+
+pub const HEADER: &str = "day   phase          min       median          p95       stddev";
+
+impl DayBench {
+    /// One table row per phase, durations in µs so days can be compared directly.
+    pub fn rows(&self) -> Vec<String> {
+        self.phases
+            .iter()
+            .map(|(phase, stats)| {
+                format!(
+                    "day{:02} {:<6} {:>12} {:>12} {:>12} {:>12}",
+                    self.day,
+                    phase,
+                    micros(stats.min),
+                    micros(stats.median),
+                    micros(stats.p95),
+                    micros(stats.stddev)
+                )
+            })
+            .collect()
+    }
+}
+
+fn micros(duration: Duration) -> String {
+    format!("{:.1} µs", duration.as_secs_f64() * 1e6)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::bench::{BenchConfig, DayBench, Stats};
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_micros(v)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::of(micros(&[5, 1, 4, 2, 3]));
+        assert_eq!(Duration::from_micros(1), stats.min);
+        assert_eq!(Duration::from_micros(3), stats.median);
+        assert_eq!(Duration::from_micros(5), stats.p95);
+        assert_eq!(1414, stats.stddev.as_nanos());
+
+        let stats = Stats::of(micros(&[2, 4, 4, 4, 5, 5, 7, 9]));
+        assert_eq!(Duration::from_nanos(4500), stats.median);
+        assert_eq!(Duration::from_micros(2), stats.stddev);
+    }
+
+    #[test]
+    fn test_p95() {
+        let samples: Vec<u64> = (1..=100).collect();
+        assert_eq!(Duration::from_micros(95), Stats::of(micros(&samples)).p95);
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let config = BenchConfig {
+            warmup: 2,
+            iterations: 5,
+        };
+        config.measure(|| calls += 1);
+        assert_eq!(7, calls);
+    }
+
+    #[test]
+    fn test_rows() {
+        let stats = Stats::of(micros(&[12]));
+        let bench = DayBench {
+            day: 5,
+            phases: vec![("parse", stats)],
+        };
+        assert_eq!(
+            vec!["day05 parse       12.0 µs      12.0 µs      12.0 µs       0.0 µs"],
+            bench.rows()
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::{fs, io};
+
+use crate::day::{Answer, Registration};
+use crate::parse::ParseError;
+use crate::report::{DayReport, PartReport};
+
+pub const DEFAULT_CACHE_FILE: &str = ".cache/answers.txt";
+
+/// Identifies a cached answer: the day and part, the solver version that computed it and the hash
+/// of the input it was computed on.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CacheKey {
+    pub day: u8,
+    pub part: u8,
+    pub version: u32,
+    pub input_hash: u64,
+}
+
+/// Answers computed before, stored as one `dayNN <part> <version> <hash> <answer>` line per entry.
+#[derive(Debug, Default, PartialEq)]
+pub struct AnswerCache {
+    answers: BTreeMap<CacheKey, String>,
+}
+
+impl AnswerCache {
+    /// Loads the cache from `path`; a missing file is an empty cache and malformed lines are
+    /// dropped, since every entry can be computed again.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Self::parse(&content)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn parse(content: &str) -> Self {
+        let mut answers = BTreeMap::new();
+        for (idx, line) in content.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.splitn(5, ' ');
+            let entry = (|| {
+                let day = fields.next()?.strip_prefix("day")?.parse().ok()?;
+                let part = fields.next()?.parse().ok()?;
+                let version = fields.next()?.parse().ok()?;
+                let input_hash = u64::from_str_radix(fields.next()?, 16).ok()?;
+                let key = CacheKey {
+                    day,
+                    part,
+                    version,
+                    input_hash,
+                };
+                Some((key, fields.next()?.to_string()))
+            })();
+            match entry {
+                Some((key, answer)) => {
+                    answers.insert(key, answer);
+                }
+                None => crate::warn!("dropping malformed line {} of the answer cache", idx + 1),
+            }
+        }
+        AnswerCache { answers }
+    }
+
+    /// Writes the cache to `path`, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<&str> {
+        self.answers.get(key).map(String::as_str)
+    }
+
+    /// Caches a solved answer and drops the answers older versions of the solver computed for the
+    /// same part. Unsolved and timed out answers are not cached, nor are answers spanning several
+    /// lines.
+    pub fn insert(&mut self, key: CacheKey, answer: &Answer) {
+        if let Answer::Solved(value) = answer {
+            if value.contains('\n') {
+                return;
+            }
+            self.answers.retain(|known, _| {
+                (known.day, known.part) != (key.day, key.part) || known.version == key.version
+            });
+            self.answers.insert(key, value.clone());
+        }
+    }
+}
+
+impl Display for AnswerCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# day part version input-hash answer")?;
+        for (key, answer) in &self.answers {
+            writeln!(
+                f,
+                "day{:02} {} {} {:016x} {}",
+                key.day, key.part, key.version, key.input_hash, answer
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// FNV-1a, which unlike the hasher of the standard library is stable across Rust releases.
+pub fn input_hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Takes the answers of a day from the cache and solves only the parts it has none for, then
+/// caches their answers. The lock is not held while solving, so days can share the cache across
+/// threads. Cached parts take no time, and neither does parsing when every part is cached.
+pub fn run_cached(
+    cache: &Mutex<AnswerCache>,
+    registration: &Registration,
+    input: &str,
+    solve: impl FnOnce(&[u8]) -> Result<DayReport, ParseError>,
+) -> Result<DayReport, ParseError> {
+    let input_hash = input_hash(input);
+    let key = |part| CacheKey {
+        day: registration.day,
+        part,
+        version: registration.version,
+        input_hash,
+    };
+    let cached: Vec<PartReport> = {
+        let cache = cache.lock().expect("no thread panics holding the cache");
+        [1, 2]
+            .into_iter()
+            .filter_map(|part| {
+                let answer = cache.get(&key(part))?;
+                Some(PartReport {
+                    part,
+                    answer: Answer::Solved(answer.to_string()),
+                    solve_time: Duration::ZERO,
+                    cached: true,
+                })
+            })
+            .collect()
+    };
+    let missing: Vec<u8> = [1, 2]
+        .into_iter()
+        .filter(|part| cached.iter().all(|report| report.part != *part))
+        .collect();
+    let mut report = if missing.is_empty() {
+        DayReport {
+            day: registration.day,
+            parse_time: Duration::ZERO,
+            parts: Vec::new(),
+        }
+    } else {
+        solve(&missing)?
+    };
+    {
+        let mut cache = cache.lock().expect("no thread panics holding the cache");
+        for part in &report.parts {
+            cache.insert(key(part.part), &part.answer);
+        }
+    }
+    report.parts.extend(cached);
+    report.parts.sort_by_key(|part| part.part);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::sync::Mutex;
+
+    use crate::cache::{input_hash, run_cached, AnswerCache, CacheKey};
+    use crate::day::Answer;
+
+    fn key(part: u8, version: u32) -> CacheKey {
+        CacheKey {
+            day: 5,
+            part,
+            version,
+            input_hash: 0xabc,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let content = "# day part version input-hash answer\nday05 1 1 0000000000000abc 35\nday12 2 3 ffffffffffffffff 525152\n";
+        let cache = AnswerCache::parse(content);
+        assert_eq!(Some("35"), cache.get(&key(1, 1)));
+        assert_eq!(None, cache.get(&key(1, 2)));
+        assert_eq!(content, cache.to_string());
+        assert_eq!(
+            AnswerCache::parse("day05 1 1 0000000000000abc 35\n"),
+            AnswerCache::parse("day05 1 1 0000000000000abc 35\nday05 1 x 12 7\nday05\n")
+        );
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut cache = AnswerCache::default();
+        cache.insert(key(1, 1), &Answer::from(35));
+        cache.insert(key(2, 1), &Answer::from(46));
+        cache.insert(key(2, 1), &Answer::Unsolved);
+        cache.insert(key(2, 1), &Answer::from("#.\n.#"));
+        assert_eq!(Some("46"), cache.get(&key(2, 1)));
+        cache.insert(key(1, 2), &Answer::from(36));
+        assert_eq!(
+            None,
+            cache.get(&key(1, 1)),
+            "a new version replaces the old answers"
+        );
+        assert_eq!(Some("36"), cache.get(&key(1, 2)));
+        assert_eq!(Some("46"), cache.get(&key(2, 1)));
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(0xcbf2_9ce4_8422_2325, input_hash(""));
+        assert_eq!(0xaf63_dc4c_8601_ec8c, input_hash("a"));
+        assert_ne!(input_hash("seeds: 1"), input_hash("seeds: 2"));
+    }
+
+    #[test]
+    fn test_run_cached() {
+        let registry = crate::registry();
+        let day05 = registry
+            .iter()
+            .find(|reg| reg.day == 5)
+            .expect("day05 is registered");
+        let input = std::fs::read_to_string("examples/day05/example1.txt").expect("fixture exists");
+        let cache = Mutex::new(AnswerCache::default());
+        let solved = RefCell::new(Vec::new());
+        let run = |parts: &[u8]| {
+            solved.borrow_mut().push(parts.to_vec());
+            day05.run_parts(&input, parts)
+        };
+
+        let first = run_cached(&cache, day05, &input, run).expect("should parse");
+        assert!(first.parts.iter().all(|part| !part.cached));
+        let second = run_cached(&cache, day05, &input, run).expect("should parse");
+        assert_eq!(
+            vec![vec![1, 2]],
+            *solved.borrow(),
+            "the second run is cached"
+        );
+        assert!(second.parts.iter().all(|part| part.cached));
+        assert_eq!(
+            vec![Answer::from(35), Answer::from(46)],
+            second
+                .parts
+                .iter()
+                .map(|part| part.answer.clone())
+                .collect::<Vec<_>>()
+        );
+
+        cache.lock().unwrap().answers.retain(|key, _| key.part == 1);
+        let third = run_cached(&cache, day05, &input, run).expect("should parse");
+        assert_eq!(
+            vec![1, 2],
+            third.parts.iter().map(|part| part.part).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![2], solved.borrow()[1]);
+        assert!(third.parts[0].cached && !third.parts[1].cached);
+    }
+}
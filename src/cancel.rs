@@ -0,0 +1,114 @@
+//! Cooperative cancellation of long-running solvers. A runner hands a [`CancellationToken`] to the
+//! part it runs, see [`Day::part1_cancellable`]; solvers that may loop for a long time check it
+//! and stop with [`Cancelled`].
+//!
+//! [`Day::part1_cancellable`]: crate::day::Day::part1_cancellable
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fails with [`Cancelled`] once the token is cancelled, so solvers can stop with `?`.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The error a solver stops with once its token is cancelled.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Cancelled;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TimedOut(pub Duration);
+
+/// Runs `task` on a thread of its own and cancels the token it is handed once `timeout` has
+/// passed. A panic of the task is passed on. The runner does not wait for a cancelled task to
+/// stop: one that does not check its token keeps its thread busy until the process exits.
+pub fn run_with_timeout<T: Send + 'static>(
+    timeout: Duration,
+    task: impl FnOnce(&CancellationToken) -> T + Send + 'static,
+) -> Result<T, TimedOut> {
+    let token = CancellationToken::new();
+    let (sender, receiver) = mpsc::channel::<Result<T, Box<dyn Any + Send>>>();
+    let task_token = token.clone();
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| task(&task_token)));
+        // the runner is gone if it timed out already
+        let _ = sender.send(result);
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(payload)) => panic::resume_unwind(payload),
+        Err(_) => {
+            token.cancel();
+            Err(TimedOut(timeout))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use crate::cancel::{run_with_timeout, CancellationToken, Cancelled, TimedOut};
+    use crate::pool::panic_message;
+
+    #[test]
+    fn test_check() {
+        let token = CancellationToken::new();
+        assert_eq!(Ok(()), token.check());
+        token.clone().cancel();
+        assert!(token.is_cancelled());
+        assert_eq!(Err(Cancelled), token.check());
+    }
+
+    #[test]
+    fn test_run_with_timeout() {
+        assert_eq!(Ok(42), run_with_timeout(Duration::from_secs(10), |_| 42));
+        let timeout = Duration::from_millis(20);
+        let (stopped, has_stopped) = mpsc::channel();
+        let stuck = run_with_timeout(timeout, move |token| -> u32 {
+            while token.check().is_ok() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            stopped.send(()).unwrap();
+            0
+        });
+        assert_eq!(Err(TimedOut(timeout)), stuck);
+        assert_eq!(Ok(()), has_stopped.recv_timeout(Duration::from_secs(10)));
+        let payload = panic::catch_unwind(|| {
+            run_with_timeout(Duration::from_secs(10), |_| -> u32 {
+                panic!("no result found")
+            })
+        })
+        .unwrap_err();
+        assert_eq!("no result found", panic_message(payload.as_ref()));
+    }
+}
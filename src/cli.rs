@@ -0,0 +1,829 @@
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::slice::Iter;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aoc_2023::answers::DEFAULT_ANSWERS_FILE;
+use aoc_2023::bench::BenchConfig;
+use aoc_2023::export::{ImageFormat, DEFAULT_IMAGE_DIR};
+use aoc_2023::fuzz::FuzzConfig;
+use aoc_2023::input::InputSource;
+use aoc_2023::log::{Filter, Level};
+use aoc_2023::pool::SortBy;
+use aoc_2023::report::Format;
+
+pub const USAGE: &str = "usage: aoc_2023 [options] [list | all | today | <day> | <from>..<to>]
+       aoc_2023 [options] verify [<days>] [--record]
+       aoc_2023 [options] bench [<days>] [--iterations <n>] [--warmup <n>]
+       aoc_2023 [options] matrix [<days>]
+       aoc_2023 examples [<days>]
+       aoc_2023 fuzz [<days>] [--runs <n>] [--size <n>] [--seed <n>]
+       aoc_2023 [options] debug <day>
+       aoc_2023 [options] watch <day>
+       aoc_2023 [options] export [<days>] [--out <dir>] [--image-format <format>]
+       aoc_2023 new-day <day>
+  list          show all registered days
+  all           run every registered day
+  today         run the latest solved day (default)
+  <day>         run a single day, e.g. `day05` or `5`
+  <from>..<to>  run an inclusive range of days, e.g. `day03..day07` or `3-7`
+  verify        check the answers of the selected days (default: all) against the answers file
+  matrix        run the selected days (default: today) on every input in <input-dir>/dayNN/*.txt
+  examples      check the selected days (default: all) against the fixtures in examples/
+  bench         time parse, part 1 and part 2 of the selected days (default: today) separately
+  fuzz          compare the selected days (default: all) with slow reference solvers on random inputs
+  debug         step through the simulation of a single day interactively, `help` lists commands
+  watch         re-run a single day whenever its inputs or examples change and show what changed
+  export        write images of the selected days (default: all) that have an exporter
+  new-day       create and register the module of a new day, with an empty input if it has none
+
+options:
+  -v, -vv, -vvv       log info, debug or trace messages of the solvers to stderr (default: warnings)
+  --log <filter>      comma-separated log levels, overall or per day, e.g. `day20=debug,info`
+  --input <path>      read the input of a single day from <path>, `-` reads stdin
+  --input-dir <dir>   read the input of each day from <dir>/dayNN (default: input)
+  --format <format>   print results as `text` (default), `json` or `csv`
+  --timeout <secs>    give up on a part after <secs> seconds and report it as timed out; `verify`
+                      counts it as failed, or as missing if it has no answer (default: none, 10
+                      for `matrix` and `watch`)
+  --no-cache          solve every part again instead of taking known answers from .cache/answers.txt
+  --jobs <n>          run the selected days on <n> threads and print a summary table
+  --sort <key>        order the summary table by `day` (default) or `runtime`
+  --render            draw grid days after solving them, in colour when stdout is a terminal
+  --mem               count the allocations of parse, part 1 and part 2 instead of printing answers
+                      (needs a build with `--features mem`)
+  --dot               print the selected graph days as Graphviz DOT instead of solving them
+  --highlight <nodes> comma-separated nodes whose subgraph `--dot` highlights (default: per day)
+  --answers <path>    answers file used by `verify` (default: answers.txt)
+  --record            let `verify` store the current answers as the known-good ones
+  --iterations <n>    timed runs of each phase in `bench` (default: 10)
+  --warmup <n>        untimed runs of each phase before `bench` measures (default: 3)
+  --runs <n>          random inputs per day in `fuzz` (default: 100)
+  --size <n>          largest size of the random inputs in `fuzz` (default: 10)
+  --seed <n>          seed of the first random input in `fuzz` (default: picked from the clock)
+  --out <dir>         directory `export` writes its images to (default: images)
+  --image-format <f>  write images as `svg` (default) or `ppm`";
+
+#[derive(Debug, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    pub input: InputSource,
+    pub format: Format,
+    pub answers: PathBuf,
+    /// Run days on a thread pool of this size instead of one after another.
+    pub jobs: Option<usize>,
+    pub sort: SortBy,
+    /// Draw the pictures of the days that have a renderer after running them.
+    pub render: bool,
+    /// Print the graphs of the selected days instead of running them.
+    pub dot: Option<Highlight>,
+    /// Report the allocations of each phase instead of the answers.
+    pub mem: bool,
+    pub log: Filter,
+    /// Give up on parts that take longer than this.
+    pub timeout: Option<Duration>,
+    /// Take answers from the answer cache and add new ones to it.
+    pub cache: bool,
+}
+
+/// The nodes a DOT graph highlights.
+#[derive(Debug, PartialEq)]
+pub enum Highlight {
+    /// Whatever path or subgraph the day picks.
+    Default,
+    Nodes(Vec<String>),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    List,
+    Run(Selection),
+    Verify {
+        selection: Selection,
+        record: bool,
+    },
+    Bench {
+        selection: Selection,
+        config: BenchConfig,
+    },
+    /// Runs each selected day on all of its named inputs.
+    Matrix(Selection),
+    Examples(Selection),
+    Fuzz {
+        selection: Selection,
+        config: FuzzConfig,
+    },
+    Debug(u8),
+    Watch(u8),
+    Export {
+        selection: Selection,
+        out: PathBuf,
+        format: ImageFormat,
+    },
+    NewDay(u8),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Selection {
+    All,
+    Today,
+    Days(RangeInclusive<u8>),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UsageError(String);
+
+impl Display for UsageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n\n{}", self.0, USAGE)
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<Args, UsageError> {
+    let mut positional = Vec::new();
+    let mut input = InputSource::default();
+    let mut format = Format::default();
+    let mut answers = PathBuf::from(DEFAULT_ANSWERS_FILE);
+    let mut record = false;
+    let mut iterations = None;
+    let mut warmup = None;
+    let mut jobs = None;
+    let mut sort = None;
+    let mut runs = None;
+    let mut size = None;
+    let mut seed = None;
+    let mut render = false;
+    let mut dot = false;
+    let mut mem = false;
+    let mut verbosity = 0;
+    let mut log_specs = Vec::new();
+    let mut timeout = None;
+    let mut no_cache = false;
+    let mut highlight = None;
+    let mut out = None;
+    let mut image_format = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag, Some(value)),
+            _ => (arg.as_str(), None),
+        };
+        match flag {
+            "--input" => {
+                input = match flag_value(flag, inline_value, &mut args)? {
+                    "-" => InputSource::Stdin,
+                    path => InputSource::File(PathBuf::from(path)),
+                }
+            }
+            "--input-dir" => {
+                input = InputSource::Dir(PathBuf::from(flag_value(flag, inline_value, &mut args)?))
+            }
+            "--format" => {
+                let name = flag_value(flag, inline_value, &mut args)?;
+                format = Format::from_name(name)
+                    .ok_or_else(|| UsageError(format!("unknown format `{name}`")))?
+            }
+            "--jobs" => match parse_count(flag_value(flag, inline_value, &mut args)?)? {
+                0 => return Err(UsageError(String::from("option `--jobs` needs at least 1"))),
+                count => jobs = Some(count),
+            },
+            "--sort" => {
+                let name = flag_value(flag, inline_value, &mut args)?;
+                sort = Some(
+                    SortBy::from_name(name)
+                        .ok_or_else(|| UsageError(format!("unknown sort key `{name}`")))?,
+                )
+            }
+            "--render" => render = true,
+            "--dot" => dot = true,
+            "--mem" => mem = true,
+            "--timeout" => {
+                let value = flag_value(flag, inline_value, &mut args)?;
+                // too large, negative or below a nanosecond does not make a timeout
+                match value.parse().map(Duration::try_from_secs_f64) {
+                    Ok(Ok(duration)) if !duration.is_zero() => timeout = Some(duration),
+                    _ => return Err(UsageError(format!("`{value}` is not a timeout in seconds"))),
+                }
+            }
+            "--no-cache" => no_cache = true,
+            "--log" => log_specs.push(flag_value(flag, inline_value, &mut args)?),
+            flag if flag.len() > 1
+                && flag.starts_with('-')
+                && flag[1..].chars().all(|c| c == 'v') =>
+            {
+                verbosity += flag.len() - 1
+            }
+            "--highlight" => {
+                let nodes = flag_value(flag, inline_value, &mut args)?;
+                highlight = Some(nodes.split(',').map(String::from).collect())
+            }
+            "--answers" => answers = PathBuf::from(flag_value(flag, inline_value, &mut args)?),
+            "--record" => record = true,
+            "--iterations" => {
+                iterations = Some(parse_count(flag_value(flag, inline_value, &mut args)?)?)
+            }
+            "--warmup" => warmup = Some(parse_count(flag_value(flag, inline_value, &mut args)?)?),
+            "--runs" => runs = Some(parse_count(flag_value(flag, inline_value, &mut args)?)?),
+            "--size" => match parse_count(flag_value(flag, inline_value, &mut args)?)? {
+                0 => return Err(UsageError(String::from("option `--size` needs at least 1"))),
+                count => size = Some(count),
+            },
+            "--seed" => {
+                let value = flag_value(flag, inline_value, &mut args)?;
+                seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| UsageError(format!("`{value}` is not a seed")))?,
+                )
+            }
+            "--out" => out = Some(PathBuf::from(flag_value(flag, inline_value, &mut args)?)),
+            "--image-format" => {
+                let name = flag_value(flag, inline_value, &mut args)?;
+                image_format = Some(
+                    ImageFormat::from_name(name)
+                        .ok_or_else(|| UsageError(format!("unknown image format `{name}`")))?,
+                )
+            }
+            flag if flag.starts_with("--") => {
+                return Err(UsageError(format!("unknown option `{flag}`")))
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    let command = match positional[..] {
+        [] => Command::Run(Selection::Today),
+        ["list"] => Command::List,
+        ["verify"] => Command::Verify {
+            selection: Selection::All,
+            record,
+        },
+        ["verify", selection] => Command::Verify {
+            selection: parse_selection(selection)?,
+            record,
+        },
+        ["matrix"] => Command::Matrix(Selection::Today),
+        ["matrix", selection] => Command::Matrix(parse_selection(selection)?),
+        ["examples"] => Command::Examples(Selection::All),
+        ["examples", selection] => Command::Examples(parse_selection(selection)?),
+        ["bench", ..] => {
+            let default = BenchConfig::default();
+            let config = BenchConfig {
+                warmup: warmup.unwrap_or(default.warmup),
+                iterations: iterations.unwrap_or(default.iterations),
+            };
+            let selection = match positional[1..] {
+                [] => Selection::Today,
+                [selection] => parse_selection(selection)?,
+                [_, extra, ..] => return Err(UsageError(format!("unexpected argument `{extra}`"))),
+            };
+            Command::Bench { selection, config }
+        }
+        ["fuzz", ..] => {
+            let default = FuzzConfig::default();
+            let config = FuzzConfig {
+                runs: runs.unwrap_or(default.runs),
+                size: size.unwrap_or(default.size),
+                seed: seed.unwrap_or_else(clock_seed),
+            };
+            let selection = match positional[1..] {
+                [] => Selection::All,
+                [selection] => parse_selection(selection)?,
+                [_, extra, ..] => return Err(UsageError(format!("unexpected argument `{extra}`"))),
+            };
+            Command::Fuzz { selection, config }
+        }
+        ["debug", day] => Command::Debug(parse_day(day)?),
+        ["watch", day] => Command::Watch(parse_day(day)?),
+        ["new-day", day] => Command::NewDay(parse_day(day)?),
+        ["export", ..] => {
+            let selection = match positional[1..] {
+                [] => Selection::All,
+                [selection] => parse_selection(selection)?,
+                [_, extra, ..] => return Err(UsageError(format!("unexpected argument `{extra}`"))),
+            };
+            Command::Export {
+                selection,
+                out: out
+                    .take()
+                    .unwrap_or_else(|| PathBuf::from(DEFAULT_IMAGE_DIR)),
+                format: image_format.unwrap_or_default(),
+            }
+        }
+        [single] => Command::Run(parse_selection(single)?),
+        [_, extra, ..] => return Err(UsageError(format!("unexpected argument `{extra}`"))),
+    };
+    if record && !matches!(command, Command::Verify { .. }) {
+        return Err(UsageError(String::from(
+            "option `--record` can only be used with `verify`",
+        )));
+    }
+    if (iterations.is_some() || warmup.is_some()) && !matches!(command, Command::Bench { .. }) {
+        return Err(UsageError(String::from(
+            "options `--iterations` and `--warmup` can only be used with `bench`",
+        )));
+    }
+    if (runs.is_some() || size.is_some() || seed.is_some())
+        && !matches!(command, Command::Fuzz { .. })
+    {
+        return Err(UsageError(String::from(
+            "options `--runs`, `--size` and `--seed` can only be used with `fuzz`",
+        )));
+    }
+    if (out.is_some() || image_format.is_some()) && !matches!(command, Command::Export { .. }) {
+        return Err(UsageError(String::from(
+            "options `--out` and `--image-format` can only be used with `export`",
+        )));
+    }
+    if jobs.is_some() && !matches!(command, Command::Run(_)) {
+        return Err(UsageError(String::from(
+            "option `--jobs` can only be used when running days",
+        )));
+    }
+    if render && (!matches!(command, Command::Run(_)) || jobs.is_some()) {
+        return Err(UsageError(String::from(
+            "option `--render` can only be used when running days one after another",
+        )));
+    }
+    if render && format != Format::Text {
+        return Err(UsageError(String::from(
+            "option `--render` can only be used with the `text` format",
+        )));
+    }
+    if dot && (!matches!(command, Command::Run(_)) || jobs.is_some() || render) {
+        return Err(UsageError(String::from(
+            "option `--dot` can only be used when running days one after another, without `--render`",
+        )));
+    }
+    if dot && format != Format::Text {
+        return Err(UsageError(String::from(
+            "option `--dot` can only be used with the `text` format",
+        )));
+    }
+    if mem && (!matches!(command, Command::Run(_)) || jobs.is_some() || render || dot) {
+        return Err(UsageError(String::from(
+            "option `--mem` can only be used when running days one after another, without `--render` or `--dot`",
+        )));
+    }
+    if mem && format != Format::Text {
+        return Err(UsageError(String::from(
+            "option `--mem` can only be used with the `text` format",
+        )));
+    }
+    let timed = matches!(
+        command,
+        Command::Run(_) | Command::Verify { .. } | Command::Matrix(_) | Command::Watch(_)
+    );
+    if timeout.is_some() && (!timed || dot || mem) {
+        return Err(UsageError(String::from(
+            "option `--timeout` can only be used when running, verifying or watching days, without `--dot` or `--mem`",
+        )));
+    }
+    if no_cache && (!matches!(command, Command::Run(_)) || dot || mem) {
+        return Err(UsageError(String::from(
+            "option `--no-cache` can only be used when running days, without `--dot` or `--mem`",
+        )));
+    }
+    if highlight.is_some() && !dot {
+        return Err(UsageError(String::from(
+            "option `--highlight` can only be used with `--dot`",
+        )));
+    }
+    if input == InputSource::Stdin && matches!(command, Command::Watch(_)) {
+        return Err(UsageError(String::from(
+            "option `--input -` cannot be used with `watch`, stdin has no changes to watch",
+        )));
+    }
+    if sort.is_some() && jobs.is_none() {
+        return Err(UsageError(String::from(
+            "option `--sort` can only be used with `--jobs`",
+        )));
+    }
+    let mut log = Filter::new(Level::Warn.raised(verbosity));
+    for spec in log_specs {
+        log.apply(spec)
+            .map_err(|directive| UsageError(format!("unknown log filter `{directive}`")))?;
+    }
+    Ok(Args {
+        command,
+        input,
+        format,
+        answers,
+        jobs,
+        sort: sort.unwrap_or_default(),
+        render,
+        dot: dot.then(|| highlight.map_or(Highlight::Default, Highlight::Nodes)),
+        mem,
+        log,
+        timeout,
+        cache: !no_cache,
+    })
+}
+
+fn flag_value<'a>(
+    flag: &str,
+    inline_value: Option<&'a str>,
+    args: &mut Iter<'a, String>,
+) -> Result<&'a str, UsageError> {
+    inline_value
+        .or_else(|| args.next().map(String::as_str))
+        .ok_or_else(|| UsageError(format!("option `{flag}` needs a value")))
+}
+
+fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+fn parse_count(arg: &str) -> Result<usize, UsageError> {
+    arg.parse()
+        .map_err(|_| UsageError(format!("`{arg}` is not a number")))
+}
+
+fn parse_selection(arg: &str) -> Result<Selection, UsageError> {
+    match arg {
+        "all" => Ok(Selection::All),
+        "today" => Ok(Selection::Today),
+        range => parse_range(range).map(Selection::Days),
+    }
+}
+
+fn parse_range(arg: &str) -> Result<RangeInclusive<u8>, UsageError> {
+    let bounds = arg.split_once("..").or_else(|| arg.split_once('-'));
+    let (from, to) = match bounds {
+        Some((from, to)) => (parse_day(from)?, parse_day(to)?),
+        None => {
+            let day = parse_day(arg)?;
+            (day, day)
+        }
+    };
+    if from > to {
+        return Err(UsageError(format!("empty day range `{arg}`")));
+    }
+    Ok(from..=to)
+}
+
+fn parse_day(arg: &str) -> Result<u8, UsageError> {
+    let number = arg.strip_prefix("day").unwrap_or(arg);
+    match number.parse::<u8>() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => Err(UsageError(format!("`{arg}` is not a day between 1 and 25"))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use crate::cli::{parse_args, Args, Command, Highlight, Selection};
+    use aoc_2023::bench::BenchConfig;
+    use aoc_2023::export::ImageFormat;
+    use aoc_2023::fuzz::FuzzConfig;
+    use aoc_2023::input::InputSource;
+    use aoc_2023::log::{Filter, Level};
+    use aoc_2023::pool::SortBy;
+    use aoc_2023::report::Format;
+
+    fn parse_full(args: &[&str]) -> Args {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_args(&args).expect("should parse")
+    }
+
+    fn parse(args: &[&str]) -> Command {
+        parse_full(args).command
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(Command::Run(Selection::Today), parse(&[]));
+        assert_eq!(Command::List, parse(&["list"]));
+        assert_eq!(Command::Run(Selection::All), parse(&["all"]));
+        assert_eq!(Command::Run(Selection::Days(5..=5)), parse(&["day05"]));
+        assert_eq!(Command::Run(Selection::Days(5..=5)), parse(&["5"]));
+        assert_eq!(
+            Command::Run(Selection::Days(3..=7)),
+            parse(&["day03..day07"])
+        );
+        assert_eq!(Command::Run(Selection::Days(3..=7)), parse(&["3-7"]));
+    }
+
+    #[test]
+    fn test_verify() {
+        let args = parse_full(&["verify"]);
+        assert_eq!(
+            Command::Verify {
+                selection: Selection::All,
+                record: false
+            },
+            args.command
+        );
+        assert_eq!(PathBuf::from("answers.txt"), args.answers);
+        let args = parse_full(&["verify", "day18", "--record", "--answers=other.txt"]);
+        assert_eq!(
+            Command::Verify {
+                selection: Selection::Days(18..=18),
+                record: true
+            },
+            args.command
+        );
+        assert_eq!(PathBuf::from("other.txt"), args.answers);
+    }
+
+    #[test]
+    fn test_input_options() {
+        assert_eq!(InputSource::default(), parse_full(&["day05"]).input);
+        assert_eq!(
+            InputSource::File(PathBuf::from("example.txt")),
+            parse_full(&["day05", "--input", "example.txt"]).input
+        );
+        assert_eq!(
+            InputSource::Stdin,
+            parse_full(&["--input=-", "day05"]).input
+        );
+        let args = parse_full(&["--input-dir", "other", "all"]);
+        assert_eq!(InputSource::Dir(PathBuf::from("other")), args.input);
+        assert_eq!(Command::Run(Selection::All), args.command);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Format::Text, parse_full(&["all"]).format);
+        assert_eq!(
+            Format::Json,
+            parse_full(&["all", "--format", "json"]).format
+        );
+        assert_eq!(Format::Csv, parse_full(&["--format=csv", "all"]).format);
+    }
+
+    #[test]
+    fn test_matrix() {
+        assert_eq!(Command::Matrix(Selection::Today), parse(&["matrix"]));
+        let args = parse_full(&["matrix", "day05", "--input-dir", "inputs"]);
+        assert_eq!(Command::Matrix(Selection::Days(5..=5)), args.command);
+        assert_eq!(InputSource::Dir(PathBuf::from("inputs")), args.input);
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(Command::Examples(Selection::All), parse(&["examples"]));
+        assert_eq!(
+            Command::Examples(Selection::Days(4..=4)),
+            parse(&["examples", "day04"])
+        );
+    }
+
+    #[test]
+    fn test_bench() {
+        assert_eq!(
+            Command::Bench {
+                selection: Selection::Today,
+                config: BenchConfig::default()
+            },
+            parse(&["bench"])
+        );
+        assert_eq!(
+            Command::Bench {
+                selection: Selection::Days(16..=16),
+                config: BenchConfig {
+                    warmup: 0,
+                    iterations: 50
+                }
+            },
+            parse(&["bench", "day16", "--iterations", "50", "--warmup=0"])
+        );
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let Command::Fuzz { selection, config } = parse(&["fuzz"]) else {
+            panic!("should be a fuzz command");
+        };
+        assert_eq!(Selection::All, selection);
+        assert_eq!(
+            (100, 10),
+            (config.runs, config.size),
+            "defaults apply, the seed comes from the clock"
+        );
+        assert_eq!(
+            Command::Fuzz {
+                selection: Selection::Days(5..=5),
+                config: FuzzConfig {
+                    runs: 500,
+                    size: 3,
+                    seed: 42
+                }
+            },
+            parse(&["fuzz", "day05", "--runs", "500", "--size=3", "--seed", "42"])
+        );
+    }
+
+    #[test]
+    fn test_jobs() {
+        let args = parse_full(&["all"]);
+        assert_eq!((None, SortBy::Day), (args.jobs, args.sort));
+        let args = parse_full(&["all", "--jobs", "4", "--sort=runtime"]);
+        assert_eq!((Some(4), SortBy::Runtime), (args.jobs, args.sort));
+    }
+
+    #[test]
+    fn test_render() {
+        assert!(!parse_full(&["day16"]).render);
+        let args = parse_full(&["day16", "--render"]);
+        assert_eq!(
+            (Command::Run(Selection::Days(16..=16)), true),
+            (args.command, args.render)
+        );
+    }
+
+    #[test]
+    fn test_log() {
+        assert_eq!(Filter::default(), parse_full(&["day20"]).log);
+        assert_eq!(Level::Info, parse_full(&["-v", "day20"]).log.default);
+        assert_eq!(
+            Level::Trace,
+            parse_full(&["day20", "-vv", "-v"]).log.default
+        );
+        let log = parse_full(&["day20", "--log", "day20=debug", "--log=day12=trace,error"]).log;
+        assert_eq!(
+            (Level::Debug, Level::Trace, Level::Error),
+            (log.level("day20"), log.level("day12"), log.level("day18"))
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        assert_eq!(None, parse_full(&["day20"]).timeout);
+        assert_eq!(
+            Some(Duration::from_secs(5)),
+            parse_full(&["day20", "--timeout", "5"]).timeout
+        );
+        assert_eq!(
+            Some(Duration::from_millis(500)),
+            parse_full(&["all", "--jobs=4", "--timeout=0.5"]).timeout
+        );
+        assert_eq!(
+            Some(Duration::from_secs(5)),
+            parse_full(&["verify", "day12", "--timeout", "5"]).timeout
+        );
+        assert_eq!(
+            Some(Duration::from_secs(2)),
+            parse_full(&["matrix", "day20", "--timeout", "2"]).timeout
+        );
+        assert_eq!(
+            Some(Duration::from_secs(2)),
+            parse_full(&["watch", "day20", "--timeout", "2"]).timeout
+        );
+        // zero, not a number, negative, too large and below a nanosecond
+        for value in ["0", "soon", "-1", "1e30", "1e-12", "inf"] {
+            let args = ["day20", "--timeout", value].map(String::from);
+            assert!(parse_args(&args).is_err(), "{value} should not parse");
+        }
+    }
+
+    #[test]
+    fn test_no_cache() {
+        assert!(parse_full(&["day17"]).cache);
+        assert!(!parse_full(&["day17", "--no-cache"]).cache);
+        assert!(!parse_full(&["all", "--jobs=4", "--no-cache"]).cache);
+    }
+
+    #[test]
+    fn test_mem() {
+        assert!(!parse_full(&["day17"]).mem);
+        let args = parse_full(&["day12..day17", "--mem"]);
+        assert_eq!(
+            (Command::Run(Selection::Days(12..=17)), true),
+            (args.command, args.mem)
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(Command::Debug(20), parse(&["debug", "day20"]));
+        assert_eq!(
+            InputSource::File(PathBuf::from("example.txt")),
+            parse_full(&["debug", "14", "--input", "example.txt"]).input
+        );
+    }
+
+    #[test]
+    fn test_watch() {
+        assert_eq!(Command::Watch(5), parse(&["watch", "day05"]));
+        assert_eq!(
+            InputSource::File(PathBuf::from("edge.txt")),
+            parse_full(&["watch", "5", "--input", "edge.txt"]).input
+        );
+    }
+
+    #[test]
+    fn test_new_day() {
+        assert_eq!(Command::NewDay(21), parse(&["new-day", "21"]));
+        assert_eq!(Command::NewDay(23), parse(&["new-day", "day23"]));
+    }
+
+    #[test]
+    fn test_dot() {
+        assert_eq!(None, parse_full(&["day20"]).dot);
+        assert_eq!(
+            Some(Highlight::Default),
+            parse_full(&["day20", "--dot"]).dot
+        );
+        let args = parse_full(&["--dot", "day08", "--highlight", "AAA,BBB"]);
+        assert_eq!(
+            Some(Highlight::Nodes(vec![
+                String::from("AAA"),
+                String::from("BBB")
+            ])),
+            args.dot
+        );
+        assert_eq!(Command::Run(Selection::Days(8..=8)), args.command);
+    }
+
+    #[test]
+    fn test_export() {
+        assert_eq!(
+            Command::Export {
+                selection: Selection::All,
+                out: PathBuf::from("images"),
+                format: ImageFormat::Svg
+            },
+            parse(&["export"])
+        );
+        assert_eq!(
+            Command::Export {
+                selection: Selection::Days(18..=18),
+                out: PathBuf::from("pictures"),
+                format: ImageFormat::Ppm
+            },
+            parse(&["export", "day18", "--out", "pictures", "--image-format=ppm"])
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        for args in [
+            &["day26"][..],
+            &["7..3"],
+            &["tomorrow"],
+            &["all", "list"],
+            &["--input"],
+            &["--verbose"],
+            &["--format", "xml"],
+            &["day05", "--record"],
+            &["verify", "all", "day05"],
+            &["matrix", "day05", "day06"],
+            &["new-day"],
+            &["watch"],
+            &["-x", "day20"],
+            &["day20", "--log", "day20=loud"],
+            &["day20", "--log"],
+            &["bench", "--timeout", "5"],
+            &["day20", "--mem", "--timeout", "5"],
+            &["verify", "--no-cache"],
+            &["day17", "--mem", "--no-cache"],
+            &["day16", "--dot", "--no-cache"],
+            &["watch", "all"],
+            &["watch", "day05", "--input", "-"],
+            &["verify", "--mem"],
+            &["all", "--mem", "--jobs", "2"],
+            &["day16", "--mem", "--render"],
+            &["day20", "--mem", "--dot"],
+            &["day17", "--mem", "--format", "json"],
+            &["new-day", "26"],
+            &["new-day", "21", "--render"],
+            &["matrix", "--jobs", "2"],
+            &["bench", "--iterations", "many"],
+            &["day05", "--warmup", "1"],
+            &["bench", "all", "day05"],
+            &["all", "--jobs", "0"],
+            &["all", "--sort", "runtime"],
+            &["verify", "--jobs", "2"],
+            &["all", "--jobs", "2", "--sort", "name"],
+            &["day05", "--seed", "1"],
+            &["fuzz", "--size", "0"],
+            &["fuzz", "--seed", "-1"],
+            &["verify", "--render"],
+            &["all", "--jobs", "2", "--render"],
+            &["day16", "--render", "--format", "json"],
+            &["day18", "--out", "pictures"],
+            &["verify", "--image-format", "svg"],
+            &["export", "--image-format", "png"],
+            &["export", "day16", "day17"],
+            &["debug"],
+            &["debug", "day14..day16"],
+            &["debug", "day14", "--jobs", "2"],
+            &["verify", "--dot"],
+            &["day20", "--dot", "--render"],
+            &["all", "--jobs", "2", "--dot"],
+            &["day20", "--dot", "--format", "csv"],
+            &["day20", "--highlight", "rx"],
+        ] {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            assert!(parse_args(&args).is_err(), "{:?} should not parse", args);
+        }
+    }
+}
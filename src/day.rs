@@ -0,0 +1,305 @@
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::bench::{BenchConfig, DayBench};
+use crate::cancel::{self, CancellationToken};
+use crate::debugger::{Debugger, Session, Simulate};
+use crate::dot::{Dot, Graph};
+use crate::export::{Export, Image};
+use crate::fuzz::{Fuzz, Target};
+use crate::mem::{self, DayMemory};
+use crate::parse::ParseError;
+use crate::render::{Picture, Render};
+use crate::report::{DayReport, PartReport};
+
+/// A single puzzle day. The input is parsed once and both parts are solved on the parsed model.
+pub trait Day {
+    const DAY: u8;
+    /// Keys the answer cache together with the input. Bump it in any change that can change an
+    /// answer for some input, a fix of a wrong answer as well, or the cache keeps the old answer.
+    const VERSION: u32 = 1;
+    /// False for a day whose parts are still stubs, so `today` skips it.
+    const SOLVED: bool = true;
+    /// Shared between the threads the parts run on when they are run with a timeout.
+    type Input: Send + Sync + 'static;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(input: &Self::Input) -> Answer;
+
+    /// Like [`Day::part1`], for runs with a timeout. A solver that can run for long overrides it
+    /// to stop once `token` is cancelled; the answer of a cancelled part is not used.
+    fn part1_cancellable(input: &Self::Input, _token: &CancellationToken) -> Answer {
+        Self::part1(input)
+    }
+
+    /// Like [`Day::part1_cancellable`], for part 2.
+    fn part2_cancellable(input: &Self::Input, _token: &CancellationToken) -> Answer {
+        Self::part2(input)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Solved(String),
+    Unsolved,
+    /// The part was given up on after this long.
+    TimedOut(Duration),
+}
+
+impl Answer {
+    /// Whether the part was solved, left unsolved or timed out, as the reports name it.
+    pub fn status(&self) -> &'static str {
+        match self {
+            Answer::Solved(_) => "solved",
+            Answer::Unsolved => "unsolved",
+            Answer::TimedOut(_) => "timed out",
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Solved(value) => write!(f, "{value}"),
+            Answer::Unsolved => write!(f, "unsolved"),
+            Answer::TimedOut(timeout) => write!(f, "timed out after {}s", timeout.as_secs_f64()),
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($($typ:ty),*) => {
+        $(impl From<$typ> for Answer {
+            fn from(value: $typ) -> Self {
+                Answer::Solved(value.to_string())
+            }
+        })*
+    };
+}
+
+answer_from!(u32, u64, usize, i32, i64, String, &str);
+
+/// One of the tools of a day: parses an input and hands the parsed model to the tool.
+pub type Tool<T> = fn(&str) -> Result<T, ParseError>;
+
+/// Type-erased entry of the day registry, so days with different inputs can be run side by side.
+#[derive(Copy, Clone)]
+pub struct Registration {
+    pub day: u8,
+    pub version: u32,
+    pub solved: bool,
+    run: fn(&str, &[u8]) -> Result<DayReport, ParseError>,
+    run_timed: fn(&str, &[u8], Duration) -> Result<DayReport, ParseError>,
+    bench: fn(&str, &BenchConfig) -> Result<DayBench, ParseError>,
+    profile: fn(&str) -> Result<DayMemory, ParseError>,
+    // the tools a day supports besides solving, which `days!` adds with the `with_` methods
+    /// Set for a [`Fuzz`] day.
+    pub fuzz: Option<Target>,
+    /// Parses the input and draws it, for a [`Render`] day.
+    pub render: Option<Tool<Vec<Picture>>>,
+    /// Parses the input and turns it into images, for an [`Export`] day.
+    pub export: Option<Tool<Vec<Image>>>,
+    /// Parses the input and turns it into a Graphviz graph, for a [`Dot`] day.
+    pub dot: Option<Tool<Graph>>,
+    /// Parses the input and starts a debugger on its simulation, for a [`Simulate`] day.
+    pub simulate: Option<Tool<Box<dyn Session>>>,
+}
+
+impl Registration {
+    pub fn of<D: Day>() -> Self {
+        Registration {
+            day: D::DAY,
+            version: D::VERSION,
+            solved: D::SOLVED,
+            run: run::<D>,
+            run_timed: run_timed::<D>,
+            bench: bench::<D>,
+            profile: profile::<D>,
+            fuzz: None,
+            render: None,
+            export: None,
+            dot: None,
+            simulate: None,
+        }
+    }
+
+    /// Adds the fuzz target of `D`, which has to be the registered day.
+    pub fn with_fuzz<D: Fuzz>(self) -> Self {
+        Registration {
+            fuzz: Some(Target::of::<D>()),
+            ..self
+        }
+    }
+
+    /// Adds the pictures of `D`, which has to be the registered day.
+    pub fn with_render<D: Render>(self) -> Self {
+        Registration {
+            render: Some(|input| D::parse(input).map(|parsed| D::render(&parsed))),
+            ..self
+        }
+    }
+
+    /// Adds the images of `D`, which has to be the registered day.
+    pub fn with_export<D: Export>(self) -> Self {
+        Registration {
+            export: Some(|input| D::parse(input).map(|parsed| D::export(&parsed))),
+            ..self
+        }
+    }
+
+    /// Adds the graph of `D`, which has to be the registered day.
+    pub fn with_dot<D: Dot>(self) -> Self {
+        Registration {
+            dot: Some(|input| D::parse(input).map(|parsed| D::graph(&parsed))),
+            ..self
+        }
+    }
+
+    /// Adds the simulation of `D`, which has to be the registered day.
+    pub fn with_simulate<D: Simulate>(self) -> Self {
+        Registration {
+            simulate: Some(|input| {
+                let parsed = D::parse(input)?;
+                Ok(Box::new(Debugger::new(D::simulation(&parsed))))
+            }),
+            ..self
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!("day{:02}", self.day)
+    }
+
+    /// Parses the input and solves both parts, timing each phase separately.
+    pub fn run(&self, input: &str) -> Result<DayReport, ParseError> {
+        (self.run)(input, &[1, 2])
+    }
+
+    /// Like [`Registration::run`], but only solves the given parts.
+    pub fn run_parts(&self, input: &str, parts: &[u8]) -> Result<DayReport, ParseError> {
+        (self.run)(input, parts)
+    }
+
+    /// Like [`Registration::run`], but each part runs on a thread of its own, sharing the parsed
+    /// input, and is cancelled after `timeout`, see [`cancel`]. Parsing is not timed out.
+    pub fn run_with_timeout(
+        &self,
+        input: &str,
+        timeout: Duration,
+    ) -> Result<DayReport, ParseError> {
+        (self.run_timed)(input, &[1, 2], timeout)
+    }
+
+    /// Like [`Registration::run_with_timeout`], but only solves the given parts.
+    pub fn run_parts_with_timeout(
+        &self,
+        input: &str,
+        parts: &[u8],
+        timeout: Duration,
+    ) -> Result<DayReport, ParseError> {
+        (self.run_timed)(input, parts, timeout)
+    }
+
+    /// Benchmarks the parse, part 1 and part 2 phases separately.
+    pub fn bench(&self, input: &str, config: &BenchConfig) -> Result<DayBench, ParseError> {
+        (self.bench)(input, config)
+    }
+
+    /// Counts the allocations of the parse, part 1 and part 2 phases separately; see [`mem`].
+    pub fn profile(&self, input: &str) -> Result<DayMemory, ParseError> {
+        (self.profile)(input)
+    }
+}
+
+fn run<D: Day>(input: &str, wanted: &[u8]) -> Result<DayReport, ParseError> {
+    let now = Instant::now();
+    let parsed = D::parse(input)?;
+    let parse_time = now.elapsed();
+
+    let solvers: [fn(&D::Input) -> Answer; 2] = [D::part1, D::part2];
+    let parts = (1..)
+        .zip(solvers)
+        .filter(|(part, _)| wanted.contains(part))
+        .map(|(part, solve)| {
+            let now = Instant::now();
+            let answer = solve(&parsed);
+            PartReport {
+                part,
+                answer,
+                solve_time: now.elapsed(),
+                cached: false,
+            }
+        })
+        .collect();
+
+    Ok(DayReport {
+        day: D::DAY,
+        parse_time,
+        parts,
+    })
+}
+
+fn run_timed<D: Day>(
+    input: &str,
+    wanted: &[u8],
+    timeout: Duration,
+) -> Result<DayReport, ParseError> {
+    let now = Instant::now();
+    let parsed = Arc::new(D::parse(input)?);
+    let parse_time = now.elapsed();
+
+    let solvers: [fn(&D::Input, &CancellationToken) -> Answer; 2] =
+        [D::part1_cancellable, D::part2_cancellable];
+    let parts = (1..)
+        .zip(solvers)
+        .filter(|(part, _)| wanted.contains(part))
+        .map(|(part, solve)| {
+            let parsed = Arc::clone(&parsed);
+            let now = Instant::now();
+            match cancel::run_with_timeout(timeout, move |token| solve(&parsed, token)) {
+                Ok(answer) => PartReport {
+                    part,
+                    answer,
+                    solve_time: now.elapsed(),
+                    cached: false,
+                },
+                Err(_) => PartReport {
+                    part,
+                    answer: Answer::TimedOut(timeout),
+                    solve_time: timeout,
+                    cached: false,
+                },
+            }
+        })
+        .collect();
+
+    Ok(DayReport {
+        day: D::DAY,
+        parse_time,
+        parts,
+    })
+}
+
+fn bench<D: Day>(input: &str, config: &BenchConfig) -> Result<DayBench, ParseError> {
+    let parsed = D::parse(input)?;
+    let parse = config.measure(|| D::parse(input));
+    let part1 = config.measure(|| D::part1(&parsed));
+    let part2 = config.measure(|| D::part2(&parsed));
+    Ok(DayBench {
+        day: D::DAY,
+        phases: vec![("parse", parse), ("part1", part1), ("part2", part2)],
+    })
+}
+
+fn profile<D: Day>(input: &str) -> Result<DayMemory, ParseError> {
+    let (parsed, parse) = mem::measure(|| D::parse(input));
+    let parsed = parsed?;
+    let (_, part1) = mem::measure(|| D::part1(&parsed));
+    let (_, part2) = mem::measure(|| D::part2(&parsed));
+    Ok(DayMemory {
+        day: D::DAY,
+        phases: vec![("parse", parse), ("part1", part1), ("part2", part2)],
+    })
+}
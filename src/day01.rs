@@ -1,65 +1,82 @@
-use std::fs;
+use crate::day::{Answer, Day};
+use crate::parse::ParseError;
 
+pub struct Solution;
 
-pub fn run() {
-    run_p1();
-    run_p2();
-}
+impl Day for Solution {
+    const DAY: u8 = 1;
+    type Input = Vec<Calibration>;
 
-pub fn run_p1() {
-    let res: u32 = fs::read_to_string("input/day01")
-        .expect("Could not open file.")
-        .lines()
-        .map(|line| line_to_number(line))
-        .sum();
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input
+            .lines()
+            .map(|line| {
+                let spelled =
+                    line_to_number_p2(line).ok_or_else(|| ParseError::at(input, line, "a digit"))?;
+                Ok(Calibration {
+                    digits: line_to_number(line),
+                    spelled,
+                })
+            })
+            .collect()
+    }
 
-    println!("Day 01 Solution Part 1: {}", res);
-}
+    /// Unsolved if a line only has spelled-out digits, like the lines of the part 2 example.
+    fn part1(input: &Self::Input) -> Answer {
+        input
+            .iter()
+            .map(|calibration| calibration.digits)
+            .sum::<Option<u32>>()
+            .map_or(Answer::Unsolved, Answer::from)
+    }
 
-pub fn run_p2() {
-    let res: u32 = fs::read_to_string("input/day01")
-        .expect("Could not open file.")
-        .lines()
-        .map(|line| line_to_number_p2(line))
-        .sum();
+    fn part2(input: &Self::Input) -> Answer {
+        input
+            .iter()
+            .map(|calibration| calibration.spelled)
+            .sum::<u32>()
+            .into()
+    }
+}
 
-    println!("Day 01 Solution Part 2: {}", res);
+/// The calibration values of a line, read without and with spelled-out digits.
+pub struct Calibration {
+    digits: Option<u32>,
+    spelled: u32,
 }
 
-fn line_to_number(line: &str) -> u32 {
+/// The calibration value of a line: its first and last digit. None without any digit.
+pub fn line_to_number(line: &str) -> Option<u32> {
     let mut numbers = line
         .char_indices()
         .filter(|(_idx, val)| *val >= '0' && *val <= '9');
-    let first = numbers
-        .nth(0)
-        .expect(&format!("No first number in line {}", line))
-        .1 as u32
-        - '0' as u32;
+    let first = numbers.next()?.1 as u32 - '0' as u32;
     let last = numbers.nth_back(0).map(|(_, val)| val as u32 - '0' as u32);
 
     if let Some(last_val) = last {
-        first * 10 + last_val
+        Some(first * 10 + last_val)
     } else {
-        first * 11
+        Some(first * 11)
     }
 }
 
-fn line_to_number_p2(line: &str) -> u32 {
+/// Like [`line_to_number`], but digits may also be spelled out, like `two1nine`.
+pub fn line_to_number_p2(line: &str) -> Option<u32> {
     let mut first = None;
     let mut last = None;
     let mut remaining = line;
-    while remaining.len() > 0 {
-        let next_char = remaining.chars().nth(0).expect("no more line");
+    while !remaining.is_empty() {
+        let next_char = remaining.chars().next().expect("no more line");
 
-        if next_char >= '0' && next_char <= '9' {
-            if first == None {
+        if next_char.is_ascii_digit() {
+            if first.is_none() {
                 first = Some(next_char as u32 - '0' as u32);
             }
             last = Some(next_char as u32 - '0' as u32);
         } else {
             let mut check = |str_val: &str, int_val: u32| {
                 if remaining.starts_with(str_val) {
-                    if first == None {
+                    if first.is_none() {
                         first = Some(int_val);
                     }
                     last = Some(int_val);
@@ -79,27 +96,41 @@ fn line_to_number_p2(line: &str) -> u32 {
                 || check("eight", 8)
                 || check("nine", 9);
         }
-        remaining = &remaining[1..];
+        remaining = &remaining[next_char.len_utf8()..];
     }
 
-    first.expect(&format!("No first number in line {}", line)) * 10
-        + last.expect(&format!("No second number in line {}", line))
+    Some(first? * 10 + last?)
 }
 
 #[cfg(test)]
 mod day01_test {
+    use crate::day::{Answer, Day};
     use crate::day01;
 
     #[test]
     pub fn test_p1() {
-        assert_eq!(day01::line_to_number(&"1abc2"), 12);
-        assert_eq!(day01::line_to_number(&"pqr3stu8vwx"), 38);
-        assert_eq!(day01::line_to_number(&"a1b2c3d4e5f"), 15);
+        assert_eq!(day01::line_to_number("1abc2"), Some(12));
+        assert_eq!(day01::line_to_number("pqr3stu8vwx"), Some(38));
+        assert_eq!(day01::line_to_number("a1b2c3d4e5f"), Some(15));
+        assert_eq!(day01::line_to_number("eightwothree"), None);
     }
 
     #[test]
     pub fn test_p2() {
-        assert_eq!(day01::line_to_number_p2(&"two1nine"), 29);
-        assert_eq!(day01::line_to_number_p2(&"eightwothree"), 83);
+        assert_eq!(day01::line_to_number_p2("two1nine"), Some(29));
+        assert_eq!(day01::line_to_number_p2("é1xnineé"), Some(19));
+        assert_eq!(day01::line_to_number_p2("eightwothree"), Some(83));
+    }
+
+    #[test]
+    pub fn test_parse_error() {
+        let err = day01::Solution::parse("1abc2\ntreb7uchet\nabcxyz").err();
+        assert_eq!(
+            Some(String::from("3:1: expected a digit")),
+            err.map(|err| err.to_string())
+        );
+        let spelled = day01::Solution::parse("two1nine\neightwothree").expect("should parse");
+        assert_eq!(Answer::Unsolved, day01::Solution::part1(&spelled));
+        assert_eq!(Answer::from(112), day01::Solution::part2(&spelled));
     }
 }
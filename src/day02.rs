@@ -1,19 +1,20 @@
-use std::fs;
-use std::str::Lines;
-
 use parse_display::*;
 
+use crate::day::{Answer, Day};
+use crate::parse::{self, ParseError};
+
 #[derive(Display, FromStr, PartialEq, Debug)]
 #[display("Game {val}")]
 pub struct GameNumber {
-    val: u32,
+    pub val: u32,
 }
 
+/// The cubes of a single draw, or the fewest cubes a game needs.
 #[derive(Debug, Copy, Clone)]
 pub struct Draw {
-    red: u32,
-    green: u32,
-    blue: u32,
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
 }
 
 impl Draw {
@@ -25,13 +26,15 @@ impl Draw {
         }
     }
 
+    #[cfg(test)]
     fn fold(&mut self, other: &Draw) {
         self.red += other.red;
         self.green += other.green;
         self.blue += other.blue;
     }
 
-    fn minimize(&self, other: &Draw) -> Draw {
+    /// The fewest cubes of each colour that allow both draws.
+    pub fn minimize(&self, other: &Draw) -> Draw {
         let red = u32::max(self.red, other.red);
         let green = u32::max(self.green, other.green);
         let blue = u32::max(self.blue, other.blue);
@@ -61,55 +64,62 @@ impl DrawVariant {
     }
 }
 
-pub fn run() {
-    let res = solve(
-        fs::read_to_string("input/day02")
-            .expect("Could not open file.")
-            .lines(),
-    );
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 2;
+    type Input = Vec<(GameNumber, Vec<Draw>)>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_games(input)
+    }
 
-    println!("Day 02 Solution Part 1: {}", res.0);
-    println!("Day 02 Solution Part 2: {}", res.1);
+    fn part1(games: &Self::Input) -> Answer {
+        games
+            .iter()
+            .map(|(number, draws)| {
+                let valid = determine_valid_p1(draws);
+
+                (number, valid)
+            })
+            .filter(|(_n, success)| *success)
+            .map(|(number, _s)| number.val)
+            .sum::<u32>()
+            .into()
+    }
+
+    fn part2(games: &Self::Input) -> Answer {
+        games
+            .iter()
+            .map(|(_n, draws)| draws)
+            .map(|draws| {
+                draws
+                    .iter()
+                    .fold(Draw::new(), |accu, draw| accu.minimize(draw))
+            })
+            .map(|min_draw| min_draw.red * min_draw.green * min_draw.blue)
+            .sum::<u32>()
+            .into()
+    }
 }
 
-pub fn solve(val: Lines<'_>) -> (u32, u32) {
-    let games: Vec<(GameNumber, Vec<Draw>)> = val
+/// Parses every game with its draws.
+pub fn parse_games(input: &str) -> Result<Vec<(GameNumber, Vec<Draw>)>, ParseError> {
+    input
+        .lines()
         .map(|line| {
-            let (game, draws) = line.trim().split_once(':').unwrap();
+            let (game, draws) = parse::split_once(input, line.trim(), ":")?;
             let number = game
                 .parse::<GameNumber>()
-                .expect(&format!("Could not be parse number {}", game));
-            let draws = parse_draws(draws);
-            (number, draws)
+                .map_err(|_| ParseError::at(input, game, "`Game <number>`"))?;
+            let draws = parse_draws(input, draws)?;
+            Ok((number, draws))
         })
-        .collect();
-
-    let res_p1: u32 = games
-        .iter()
-        .map(|(number, draws)| {
-            let valid = determine_valid_p1(draws);
-
-            (number, valid)
-        })
-        .filter(|(_n, success)| *success)
-        .map(|(number, _s)| number.val)
-        .sum();
-
-    let res_p2: u32 = games
-        .iter()
-        .map(|(_n, draws)| draws)
-        .map(|draws| {
-            draws
-                .iter()
-                .fold(Draw::new(), |accu, draw| accu.minimize(draw))
-        })
-        .map(|min_draw| min_draw.red * min_draw.green * min_draw.blue)
-        .sum();
-
-    (res_p1, res_p2)
+        .collect()
 }
 
-fn determine_valid_p1(draws: &[Draw]) -> bool {
+/// Whether the draws are possible with 12 red, 13 green and 14 blue cubes.
+pub fn determine_valid_p1(draws: &[Draw]) -> bool {
     for total in draws {
         let valid = total.red <= 12 && total.green <= 13 && total.blue <= 14;
         if !valid {
@@ -119,22 +129,28 @@ fn determine_valid_p1(draws: &[Draw]) -> bool {
     true
 }
 
-pub fn parse_draws(draws: &str) -> Vec<Draw> {
+/// Parses the draws of a game; `draws` has to be a slice of `input` to locate errors.
+pub fn parse_draws(input: &str, draws: &str) -> Result<Vec<Draw>, ParseError> {
     draws
         .split(';')
         .map(|split_semi| {
             split_semi
                 .split(',')
                 .map(|split| split.trim())
-                .map(|val| val.parse::<DrawVariant>().expect("could not get variant"))
-                .fold(Draw::new(), |accu, val| val.increment(accu))
+                .try_fold(Draw::new(), |accu, val| {
+                    let variant = val.parse::<DrawVariant>().map_err(|_| {
+                        ParseError::at(input, val, "`<count> red`, `green` or `blue`")
+                    })?;
+                    Ok(variant.increment(accu))
+                })
         })
         .collect()
 }
 
 #[cfg(test)]
 mod day02_test {
-    use crate::day02::{parse_draws, solve, Draw};
+    use crate::day::{Answer, Day};
+    use crate::day02::{parse_draws, parse_games, Draw, Solution};
 
     #[test]
     pub fn test_p1() {
@@ -142,14 +158,16 @@ mod day02_test {
             Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
             Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
             Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-            Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#
-            .lines();
-        solve(lines);
+            Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
+        let games = parse_games(lines).expect("should parse");
+        assert_eq!(Answer::from(8), Solution::part1(&games));
+        assert_eq!(Answer::from(2286), Solution::part2(&games));
     }
 
     #[test]
     pub fn test_parse() {
-        let res = parse_draws(&"8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red");
+        let draws = "8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
+        let res = parse_draws(draws, draws).expect("should parse");
         println!("{:?}", res);
         let total = res.iter().fold(Draw::new(), |mut accu, other| {
             accu.fold(other);
@@ -157,4 +175,10 @@ mod day02_test {
         });
         println!("{:?}", total);
     }
+
+    #[test]
+    pub fn test_parse_error() {
+        let err = parse_games("Game 1: 3 blue\nGame 2: 1 blue, 2 purple").unwrap_err();
+        assert_eq!("2:17: expected `<count> red`, `green` or `blue`", err.to_string());
+    }
 }
@@ -1,72 +1,87 @@
 use std::collections::HashMap;
-use std::fs;
 use std::iter::zip;
-use std::str::Lines;
 
-pub fn run() {
-    let res = solve(
-        fs::read_to_string("input/day03")
-            .expect("Could not open file.")
-            .lines(),
-    );
-    println!("Day 03 Solution Part 1: {}", res.0);
-    println!("Day 03 Solution Part 2: {}", res.1);
+use crate::day::{Answer, Day};
+use crate::parse::{self, ParseError};
+
+pub struct Solution;
+
+/// The engine schematic, with symbols and numbers keyed by their (first) `(row, column)`.
+pub struct Schematic {
+    pub special_chars: HashMap<(i32, i32), char>,
+    /// The value and the length of each number.
+    pub numbers: HashMap<(i32, i32), (u32, i32)>,
+}
+
+impl Day for Solution {
+    const DAY: u8 = 3;
+    type Input = Schematic;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_schematic(input)
+    }
+
+    fn part1(schematic: &Self::Input) -> Answer {
+        part_numbers(schematic)
+            .map(|(parsed, _)| parsed)
+            .sum::<u32>()
+            .into()
+    }
+
+    fn part2(schematic: &Self::Input) -> Answer {
+        gear_ratios(schematic).into_iter().sum::<u32>().into()
+    }
+}
+
+/// The product of the two part numbers of every `*` that touches exactly two of them.
+pub fn gear_ratios(schematic: &Schematic) -> Vec<u32> {
+    let mut gear_candidates = HashMap::new();
+    for (parsed, (special, row, col)) in part_numbers(schematic) {
+        if special == '*' {
+            let entry = gear_candidates.entry((row, col)).or_insert(Vec::new());
+            entry.push(parsed);
+        }
+    }
+    gear_candidates
+        .values()
+        .filter(|val| val.len() == 2)
+        .map(|val| val[0] * val[1])
+        .collect()
 }
 
-fn solve(lines: Lines<'_>) -> (u32, u32) {
+fn parse_schematic(input: &str) -> Result<Schematic, ParseError> {
     let mut special_chars = HashMap::new();
     let mut numbers = HashMap::new();
-    for (row, line) in zip(0.., lines) {
-        let mut number = Vec::new();
+    for (row, line) in zip(0.., input.lines()) {
         let mut first_found = None;
         for (col, char) in line.char_indices() {
             match char {
-                '.' => {
-                    complete_number(&number, row, first_found, &mut numbers);
-                    number = Vec::new();
-                    first_found = None;
+                '0'..='9' => {
+                    first_found.get_or_insert(col);
                 }
-                num @ '0'..='9' => {
-                    // special_chars.insert((row as i32, col as i32), num);
-                    number.push(num);
-                    if first_found == None {
-                        first_found = Some(i32::try_from(col).expect("col too large?"));
-                    }
+                '.' => {
+                    complete_number(input, line, row, first_found.take(), col, &mut numbers)?;
                 }
                 special => {
-                    complete_number(&number, row, first_found, &mut numbers);
-                    number = Vec::new();
-                    first_found = None;
-                    special_chars.insert((row as i32, col as i32), special);
+                    complete_number(input, line, row, first_found.take(), col, &mut numbers)?;
+                    special_chars.insert((row, col as i32), special);
                 }
             }
         }
-        complete_number(&number, row, first_found, &mut numbers);
+        complete_number(input, line, row, first_found, line.len(), &mut numbers)?;
     }
+    Ok(Schematic {
+        special_chars,
+        numbers,
+    })
+}
 
-    let mut res_p1 = 0;
-    let mut gear_candidates = HashMap::new();
-    for ((row, col), number) in numbers {
-        if let Some((special, row, col)) =
-            scan_neighborhood_hit(row, col, number.len() as i32, &special_chars)
-        {
-            // println!("found completed number {} @ ({},{}) matching {}", number, row, col, _special);
-            let parsed = number.parse::<u32>().expect("could not parse number");
-
-            res_p1 += parsed;
-
-            if special == '*' {
-                let entry = gear_candidates.entry((row, col)).or_insert(Vec::new());
-                entry.push(parsed);
-            }
-        }
-    }
-    let res_p2 = gear_candidates
-        .values()
-        .filter(|val| val.len() == 2)
-        .map(|val| val[0] * val[1])
-        .sum();
-    (res_p1, res_p2)
+/// Yields every number adjacent to a symbol together with the symbol and its position.
+pub fn part_numbers(schematic: &Schematic) -> impl Iterator<Item = (u32, (char, i32, i32))> + '_ {
+    schematic.numbers.iter().filter_map(|((row, col), (number, len))| {
+        scan_neighborhood_hit(*row, *col, *len, &schematic.special_chars)
+            .map(|special| (*number, special))
+    })
 }
 
 fn scan_neighborhood_hit(
@@ -96,26 +111,29 @@ fn scan_neighborhood_hit(
             return Some((*value, row + 1, idx));
         }
     }
-    return None;
+    None
 }
 
+/// Adds the number of `line` from `start` up to `end`, if a number started.
 fn complete_number(
-    collected: &[char],
+    input: &str,
+    line: &str,
     row: i32,
-    column: Option<i32>,
-    numbers: &mut HashMap<(i32, i32), String>,
-) {
-    if collected.len() == 0 {
-        return;
+    start: Option<usize>,
+    end: usize,
+    numbers: &mut HashMap<(i32, i32), (u32, i32)>,
+) -> Result<(), ParseError> {
+    if let Some(start) = start {
+        let value = parse::number(input, &line[start..end])?;
+        numbers.insert((row, start as i32), (value, (end - start) as i32));
     }
-    let column = column.expect("Got characters but no start??");
-
-    numbers.insert((row, column), collected.iter().collect());
+    Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day03;
+    use crate::day::{Answer, Day};
+    use crate::day03::Solution;
 
     #[test]
     pub fn test_01() {
@@ -130,8 +148,17 @@ mod test {
 ......755.
 ...$.*....
 .664.598.."#;
-        let res = day03::solve(test_input.lines());
-        assert_eq!(res.0, 4361);
-        assert_eq!(res.1, 467835);
+        let schematic = Solution::parse(test_input).expect("should parse");
+        assert_eq!(Solution::part1(&schematic), Answer::from(4361));
+        assert_eq!(Solution::part2(&schematic), Answer::from(467835));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let err = Solution::parse("..*\n.99999999999").err().expect("too large for u32");
+        assert_eq!(
+            "2:2: expected a number, found `99999999999`",
+            err.to_string()
+        );
     }
 }
@@ -1,20 +1,24 @@
 use std::collections::HashSet;
-use std::fs;
 use std::iter::zip;
-use std::str::Lines;
 
-struct Card {
-    line_no: u32,
-    winning: HashSet<u32>,
-    yours: HashSet<u32>,
+use crate::day::{Answer, Day};
+use crate::parse::{self, ParseError};
+
+pub struct Card {
+    /// The card number, starting at 1.
+    pub line_no: u32,
+    pub winning: HashSet<u32>,
+    pub yours: HashSet<u32>,
 }
 
 impl Card {
-    fn count_winning(&self) -> usize {
+    /// How many of your numbers are winning numbers.
+    pub fn count_winning(&self) -> usize {
         self.yours.intersection(&self.winning).count()
     }
 
-    fn count_winning_points(&self) -> u32 {
+    /// One point for the first winning number, doubled for every further one.
+    pub fn count_winning_points(&self) -> u32 {
         let res_count = self.count_winning();
         if res_count > 0 {
             u32::pow(2, (res_count - 1) as u32)
@@ -24,60 +28,66 @@ impl Card {
     }
 }
 
-pub fn run() {
-    let res = crate::day04::solve(
-        fs::read_to_string("input/day04")
-            .expect("Could not open file.")
-            .lines(),
-    );
-    println!("Day 04 Solution Part 1: {}", res.0);
-    println!("Day 04 Solution Part 2: {}", res.1);
-}
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 4;
+    type Input = Vec<Card>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        zip(1.., input.lines())
+            .map(|(line_no, line)| parse_card(input, line, line_no))
+            .collect()
+    }
 
-fn solve(lines: Lines<'_>) -> (u32, u32) {
-    let cards = zip(1.., lines.clone()).map(|(line_no, line)| parse_card(line, line_no));
+    fn part1(cards: &Self::Input) -> Answer {
+        cards
+            .iter()
+            .map(|card| card.count_winning_points())
+            .sum::<u32>()
+            .into()
+    }
 
-    let cards: Vec<Card> = cards.collect();
-    let res_p1 = cards.iter().map(|card| card.count_winning_points()).sum();
+    fn part2(cards: &Self::Input) -> Answer {
+        count_scratchcards(cards).into()
+    }
+}
 
+/// The number of cards you end up with when winning copies of the following cards. Copies past
+/// the end of the table, which a truncated table would win, are not counted.
+pub fn count_scratchcards(cards: &[Card]) -> u32 {
     let mut won = vec![1; cards.len()];
 
     for card in cards {
         let winning = card.count_winning() as u32;
+        let last = (card.line_no + winning).min(cards.len() as u32);
 
-        for card_won in (card.line_no + 1)..(card.line_no + 1 + winning) {
+        for card_won in (card.line_no + 1)..=last {
             let multi = won[to_idx(card.line_no)];
             won[to_idx(card_won)] += multi;
         }
     }
 
-    (res_p1, won.iter().sum())
+    won.iter().sum()
 }
 
 fn to_idx(line_no: u32) -> usize {
     (line_no - 1) as usize
 }
-fn parse_card(line: &str, line_no: u32) -> Card {
-    let mut split_results = line.split(':').nth(1).expect("wrong parsing").split("|");
-    let winning = parse_numbers(split_results.nth(0).expect("should be a value"));
-    let yours = parse_numbers(split_results.nth(0).expect("should be a value"));
+fn parse_card(input: &str, line: &str, line_no: u32) -> Result<Card, ParseError> {
+    let (_, numbers) = parse::split_once(input, line, ":")?;
+    let (winning, yours) = parse::split_once(input, numbers, "|")?;
 
-    Card {
+    Ok(Card {
         line_no,
-        winning,
-        yours,
-    }
+        winning: parse_numbers(input, winning)?,
+        yours: parse_numbers(input, yours)?,
+    })
 }
 
-fn parse_numbers(input: &str) -> HashSet<u32> {
-    let mut parsed_set = HashSet::new();
-    for hopeful_number in input.split_whitespace() {
-        let hopeful_number = hopeful_number.trim();
-        parsed_set.insert(
-            hopeful_number
-                .parse::<u32>()
-                .expect("Not a number after all"),
-        );
-    }
-    parsed_set
+fn parse_numbers(input: &str, numbers: &str) -> Result<HashSet<u32>, ParseError> {
+    numbers
+        .split_whitespace()
+        .map(|hopeful_number| parse::number(input, hopeful_number))
+        .collect()
 }
@@ -1,39 +1,43 @@
-use std::collections::VecDeque;
-use std::fs;
 use std::str::Lines;
 
 use itertools::Itertools;
 
-use crate::day05::State::{Before, Beyond, Within};
+use crate::day::{Answer, Day};
+use crate::fuzz::{Fuzz, Rng};
+use crate::interval::{Interval, IntervalSet};
+use crate::parse::{self, ParseError};
 
+/// One line of a map: `range_length` ids starting at `source_range_start` map to the ids
+/// starting at `destination_range_start`.
 #[derive(Copy, Clone, Debug)]
-struct Range {
-    destination_range_start: u64,
-    source_range_start: u64,
-    range_length: u64,
+pub struct Range {
+    pub destination_range_start: u64,
+    pub source_range_start: u64,
+    pub range_length: u64,
 }
 
-#[derive(Debug)]
-enum State {
-    Before,
-    Within,
-    Beyond,
+impl Range {
+    /// The ids this line maps.
+    pub fn source(&self) -> Interval<u64> {
+        Interval::with_len(self.source_range_start, self.range_length)
+    }
 }
 
-struct Almanac {
-    seeds: Vec<u64>,
-    seeds_to_soil: Vec<Range>,
-    soil_to_fertilizer: Vec<Range>,
-    fertilizer_to_water: Vec<Range>,
-    water_to_light: Vec<Range>,
-    light_to_temperature: Vec<Range>,
-    temperature_to_humidity: Vec<Range>,
-    humidity_to_location: Vec<Range>,
+/// The seeds and the seven maps, each sorted by source id.
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    pub seeds_to_soil: Vec<Range>,
+    pub soil_to_fertilizer: Vec<Range>,
+    pub fertilizer_to_water: Vec<Range>,
+    pub water_to_light: Vec<Range>,
+    pub light_to_temperature: Vec<Range>,
+    pub temperature_to_humidity: Vec<Range>,
+    pub humidity_to_location: Vec<Range>,
 }
 
 impl Almanac {
+    /// Sorts the maps by source id; the seeds are left in input order, part 2 pairs them up.
     fn sort_by_source(&mut self) {
-        self.seeds.sort();
         sort_by_source_id(&mut self.seeds_to_soil);
         sort_by_source_id(&mut self.soil_to_fertilizer);
         sort_by_source_id(&mut self.fertilizer_to_water);
@@ -42,26 +46,100 @@ impl Almanac {
         sort_by_source_id(&mut self.temperature_to_humidity);
         sort_by_source_id(&mut self.humidity_to_location);
     }
+
+    /// All maps, from seed-to-soil to humidity-to-location.
+    pub fn maps(&self) -> [&[Range]; 7] {
+        [
+            &self.seeds_to_soil,
+            &self.soil_to_fertilizer,
+            &self.fertilizer_to_water,
+            &self.water_to_light,
+            &self.light_to_temperature,
+            &self.temperature_to_humidity,
+            &self.humidity_to_location,
+        ]
+    }
 }
 
-pub fn run() {
-    let mut almanac = crate::day05::parse(
-        fs::read_to_string("input/day05")
-            .expect("Could not open file.")
-            .lines(),
-    );
-    let orig_seeds = almanac.seeds.clone();
-    almanac.sort_by_source();
-    let res_p1 = solve_p1(&almanac);
-    let res_p2 = solve_p2(&almanac, orig_seeds);
-
-    let res = (res_p1, res_p2);
-    println!("Day 05 Solution Part 1: {}", res.0);
-    println!("Day 05 Solution Part 2: {}", res.1);
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 5;
+    type Input = Almanac;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut almanac = parse(input)?;
+        almanac.sort_by_source();
+        Ok(almanac)
+    }
+
+    fn part1(almanac: &Self::Input) -> Answer {
+        let mut seeds = almanac.seeds.clone();
+        seeds.sort();
+        solve_p1(&seeds, almanac).into()
+    }
+
+    fn part2(almanac: &Self::Input) -> Answer {
+        solve_p2(almanac, almanac.seeds.clone()).into()
+    }
 }
 
-fn solve_p1(almanac: &Almanac) -> u64 {
-    let mut soils = get_destinations(&almanac.seeds, &almanac.seeds_to_soil);
+impl Fuzz for Solution {
+    const PART: u8 = 2;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let limit = 10 * size as u64 + 10;
+        let seeds = (0..rng.range(1..size as u64 / 2 + 2))
+            .map(|_| format!("{} {}", rng.range(0..limit), rng.range(1..limit / 4 + 2)))
+            .join(" ");
+        let mut input = format!("seeds: {seeds}\n");
+        for header in MAP_HEADERS {
+            input.push_str(&format!("\n{header}\n"));
+            // disjoint source ranges, like the puzzle guarantees
+            let mut source = rng.range(0..limit / 2);
+            for _ in 0..rng.range(1..size as u64 + 1) {
+                let length = rng.range(1..limit / 4 + 2);
+                let destination = rng.range(0..limit);
+                input.push_str(&format!("{destination} {source} {length}\n"));
+                source += length + rng.range(0..3);
+            }
+        }
+        input
+    }
+
+    /// Maps every single seed through all maps.
+    fn reference(almanac: &Almanac) -> Option<Answer> {
+        let maps = almanac.maps();
+        let valid_map = |ranges: &[Range]| {
+            ranges.iter().all(|range| range.range_length > 0)
+                && ranges.iter().tuple_windows().all(|(lower, upper)| {
+                    lower.source_range_start + lower.range_length <= upper.source_range_start
+                })
+        };
+        if almanac.seeds.len() % 2 == 1 || !maps.iter().all(|ranges| valid_map(ranges)) {
+            return None;
+        }
+        almanac
+            .seeds
+            .iter()
+            .tuples()
+            .flat_map(|(start, length)| *start..start + length)
+            .map(|seed| {
+                maps.iter().fold(seed, |id, ranges| {
+                    ranges
+                        .iter()
+                        .find(|range| (0..range.range_length).contains(&id.wrapping_sub(range.source_range_start)))
+                        .map_or(id, |range| range.destination_range_start + (id - range.source_range_start))
+                })
+            })
+            .min()
+            .map(Answer::from)
+    }
+}
+
+/// The lowest location of the given seeds, which have to be sorted.
+pub fn solve_p1(seeds: &[u64], almanac: &Almanac) -> u64 {
+    let mut soils = get_destinations(seeds, &almanac.seeds_to_soil);
     soils.sort();
     let mut fertilizer = get_destinations(&soils, &almanac.soil_to_fertilizer);
     fertilizer.sort();
@@ -79,226 +157,129 @@ fn solve_p1(almanac: &Almanac) -> u64 {
     location[0]
 }
 
-fn solve_p2(almanac: &Almanac, original_seeds: Vec<u64>) -> u64 {
-    let mut ranges: VecDeque<_> = original_seeds
+/// The lowest location of the seed ranges given as `start length` pairs.
+pub fn solve_p2(almanac: &Almanac, original_seeds: Vec<u64>) -> u64 {
+    let seeds: IntervalSet<u64> = original_seeds
         .iter()
-        .tuples::<(_, _)>()
-        .map(|(start, range)| (*start as u64, start + range))
+        .tuples()
+        .map(|(start, length)| Interval::with_len(*start, *length))
         .collect();
-    ranges.make_contiguous().sort_by(|t1, t2| t1.0.cmp(&t2.0));
-    let ranges = extend_ranges(ranges, &almanac.seeds_to_soil);
-    let ranges = extend_ranges(ranges, &almanac.soil_to_fertilizer);
-    let ranges = extend_ranges(ranges, &almanac.fertilizer_to_water);
-    let light = extend_ranges(ranges, &almanac.water_to_light);
-    let temperature = extend_ranges(light, &almanac.light_to_temperature);
-    let ranges = extend_ranges(temperature, &almanac.temperature_to_humidity);
-    let ranges = extend_ranges(ranges, &almanac.humidity_to_location);
-
-    ranges[0].0
+    almanac
+        .maps()
+        .iter()
+        .fold(seeds, |ids, ranges| extend_ranges(&ids, ranges))
+        .min()
+        .expect("there should be seeds")
 }
 
-fn extend_ranges(mut sources: VecDeque<(u64, u64)>, ranges: &[Range]) -> VecDeque<(u64, u64)> {
-    let mut dest_ranges = VecDeque::new();
-
-    'outer: while let Some((source_start, source_end)) = sources.pop_front() {
-        for range in ranges {
-            let range_start = range.source_range_start;
-            let range_end = range_start + range.range_length - 1;
-
-            let start = point_in_relation(source_start, range_start, range_end);
-            let end = point_in_relation(source_end, range_start, range_end);
-
-            use State::*;
-            match (start, end) {
-                (Before, Before) => (),
-                (Before, Within) => {
-                    dest_ranges.push_back((source_start, range_start - 1));
-                    let diff = source_end - range_start;
-                    dest_ranges.push_back((
-                        range.destination_range_start,
-                        range.destination_range_start + diff,
-                    ));
-                    continue 'outer;
-                }
-                (Before, Beyond) => {
-                    dest_ranges.push_back((source_start, range_start - 1));
-                    dest_ranges.push_back((
-                        range.destination_range_start,
-                        range.destination_range_start + range.range_length,
-                    ));
-                    sources.push_front((range_end + 1, source_end));
-                    continue 'outer;
-                }
-                (Within, Before) => unreachable!(),
-                (Within, Within) => {
-                    // source starts above, end within
-                    let diff_start = source_start - range_start;
-                    let diff_end = source_end - range_start;
-                    dest_ranges.push_back((
-                        range.destination_range_start + diff_start,
-                        range.destination_range_start + diff_end,
-                    ));
-                    continue 'outer;
-                }
-                (Within, Beyond) => {
-                    let diff = source_start - range_start;
-                    dest_ranges.push_back((
-                        range.destination_range_start + diff,
-                        range.destination_range_start + range.range_length,
-                    ));
-                    sources.push_front((range_end + 1, source_end));
-                    continue 'outer;
-                }
-                (Beyond, Before) => unreachable!(),
-                (Beyond, Within) => unreachable!(),
-                (Beyond, Beyond) => (),
-            }
+/// Maps a set of ids through one map; ids outside every range keep their number.
+pub fn extend_ranges(sources: &IntervalSet<u64>, ranges: &[Range]) -> IntervalSet<u64> {
+    let mut unmapped = sources.clone();
+    let mut mapped = IntervalSet::new();
+    for range in ranges {
+        let covered = sources.intersection(&range.source().into());
+        for ids in covered.intervals() {
+            mapped.insert(ids.translate(range.source_range_start, range.destination_range_start));
         }
-        dest_ranges.push_back((source_start, source_end));
-    }
-
-    dest_ranges
-        .make_contiguous()
-        .sort_by(|(s1, _), (s2, _)| s1.cmp(&s2));
-
-    let mut consolidated = VecDeque::new();
-
-    let mut curr = dest_ranges.pop_back().unwrap();
-    while let Some(range) = dest_ranges.pop_back() {
-        let higher_start = curr.0;
-        let higher_end = curr.1;
-
-        let lower_start = range.0;
-        let lower_end = range.1;
-
-        if lower_end >= higher_start {
-            curr = (lower_start, higher_end.max(lower_end))
-        } else {
-            consolidated.push_front(curr);
-            curr = range;
-        }
-    }
-    consolidated.push_front(curr);
-
-    consolidated
-}
-
-fn point_in_relation(point: u64, start_idx: u64, end_idx: u64) -> State {
-    if start_idx > point {
-        Before
-    } else if start_idx <= point && end_idx >= point {
-        Within
-    } else if end_idx < point {
-        Beyond
-    } else {
-        panic!("this should not be viable!");
+        unmapped = unmapped.difference(&covered);
     }
+    mapped.union(&unmapped)
 }
 
-fn get_destinations(sources: &[u64], ranges: &[Range]) -> Vec<u64> {
+/// Maps sorted ids through one map; ids outside every range keep their number.
+pub fn get_destinations(sources: &[u64], ranges: &[Range]) -> Vec<u64> {
     let mut lower_idx = 0;
     let mut destinations = Vec::new();
-    'outer: for &source in sources {
-        'inner: for curr in lower_idx..=ranges.len() {
-            if curr < ranges.len() && ranges[curr].source_range_start < source {
-                continue 'inner;
-            } else {
-                lower_idx = curr;
-                if curr > 0 {
-                    let possible_range = ranges[curr - 1];
-                    let diff = source - possible_range.source_range_start;
-                    if diff <= possible_range.range_length {
-                        destinations.push(possible_range.destination_range_start + diff);
-                    }
-                }
-                continue 'outer;
-            }
-        }
-        destinations.push(source);
+    for &source in sources {
+        let curr = (lower_idx..=ranges.len())
+            .find(|&curr| curr == ranges.len() || ranges[curr].source_range_start > source)
+            .expect("the end of the ranges always matches");
+        lower_idx = curr;
+        // sources outside of every range keep their number
+        let destination = curr
+            .checked_sub(1)
+            .map(|idx| ranges[idx])
+            .filter(|range| source - range.source_range_start < range.range_length)
+            .map_or(source, |range| {
+                range.destination_range_start + (source - range.source_range_start)
+            });
+        destinations.push(destination);
     }
     destinations
 }
 
-fn parse(mut lines: Lines<'_>) -> Almanac {
-    // seeds
-    let seeds = parse_seeds(lines.next().unwrap());
+const MAP_HEADERS: [&str; 7] = [
+    "seed-to-soil map:",
+    "soil-to-fertilizer map:",
+    "fertilizer-to-water map:",
+    "water-to-light map:",
+    "light-to-temperature map:",
+    "temperature-to-humidity map:",
+    "humidity-to-location map:",
+];
+
+fn parse(input: &str) -> Result<Almanac, ParseError> {
+    let mut lines = input.lines();
+    let seeds = parse_seeds(input, lines.next())?;
     let _ = lines.next();
-    // seeds2soil
-    assert_eq!("seed-to-soil map:", lines.next().unwrap());
-    let seeds_to_soil = parse_block(&mut lines);
-
-    // soil2fertilizer
-    assert_eq!("soil-to-fertilizer map:", lines.next().unwrap());
-    let soil_to_fertilizer = parse_block(&mut lines);
-
-    // fertilizer2water
-    assert_eq!("fertilizer-to-water map:", lines.next().unwrap());
-    let fertilizer_to_water = parse_block(&mut lines);
-
-    // water2light
-    assert_eq!("water-to-light map:", lines.next().unwrap());
-    let water_to_light = parse_block(&mut lines);
-
-    // light2temperature
-    assert_eq!("light-to-temperature map:", lines.next().unwrap());
-    let light_to_temperature = parse_block(&mut lines);
 
-    // temperature2humidity
-    assert_eq!("temperature-to-humidity map:", lines.next().unwrap());
-    let temperature_to_humidity = parse_block(&mut lines);
-
-    // humidity2location
-    assert_eq!("humidity-to-location map:", lines.next().unwrap());
-    let humidity_to_location = parse_block(&mut lines);
-
-    Almanac {
+    Ok(Almanac {
         seeds,
-        seeds_to_soil,
-        soil_to_fertilizer,
-        fertilizer_to_water,
-        water_to_light,
-        light_to_temperature,
-        temperature_to_humidity,
-        humidity_to_location,
-    }
+        seeds_to_soil: parse_block(input, &mut lines, MAP_HEADERS[0])?,
+        soil_to_fertilizer: parse_block(input, &mut lines, MAP_HEADERS[1])?,
+        fertilizer_to_water: parse_block(input, &mut lines, MAP_HEADERS[2])?,
+        water_to_light: parse_block(input, &mut lines, MAP_HEADERS[3])?,
+        light_to_temperature: parse_block(input, &mut lines, MAP_HEADERS[4])?,
+        temperature_to_humidity: parse_block(input, &mut lines, MAP_HEADERS[5])?,
+        humidity_to_location: parse_block(input, &mut lines, MAP_HEADERS[6])?,
+    })
 }
 
-fn parse_seeds(seeds: &str) -> Vec<u64> {
+fn parse_seeds(input: &str, line: Option<&str>) -> Result<Vec<u64>, ParseError> {
+    let seeds = line
+        .and_then(|line| line.strip_prefix("seeds:"))
+        .ok_or_else(|| ParseError::at(input, line.unwrap_or(input), "`seeds: <numbers>`"))?;
     seeds
         .split_whitespace()
-        .skip(1)
-        .map(|val| val.parse::<u64>().unwrap())
+        .map(|val| parse::number(input, val))
         .collect()
 }
 
-fn parse_block(lines: &mut Lines<'_>) -> Vec<Range> {
+fn parse_block(input: &str, lines: &mut Lines<'_>, header: &str) -> Result<Vec<Range>, ParseError> {
+    match lines.next() {
+        Some(line) if line.trim() == header => (),
+        Some(line) => return Err(ParseError::at(input, line, format!("`{header}`"))),
+        None => return Err(ParseError::at_end(input, format!("`{header}`"))),
+    }
     lines
         .take_while(|line| !line.trim().is_empty())
-        .map(|line| parse_range(line))
+        .map(|line| parse_range(input, line))
         .collect()
 }
 
-fn parse_range(range: &str) -> Range {
-    let split: Vec<u64> = range
+fn parse_range(input: &str, range: &str) -> Result<Range, ParseError> {
+    let split = range
         .split_whitespace()
-        .map(|val| val.parse::<u64>().unwrap())
-        .collect();
-    Range {
-        destination_range_start: split[0],
-        source_range_start: split[1],
-        range_length: split[2],
-    }
+        .map(|val| parse::number(input, val))
+        .collect::<Result<Vec<u64>, _>>()?;
+    let [destination_range_start, source_range_start, range_length] = split[..] else {
+        return Err(ParseError::at(input, range, "three numbers"));
+    };
+    Ok(Range {
+        destination_range_start,
+        source_range_start,
+        range_length,
+    })
 }
 
-fn sort_by_source_id(rangy_vec: &mut Vec<Range>) {
-    rangy_vec.sort_by(|a, b| a.source_range_start.cmp(&b.source_range_start));
+fn sort_by_source_id(rangy_vec: &mut [Range]) {
+    rangy_vec.sort_by_key(|a| a.source_range_start);
 }
 
 #[cfg(test)]
 pub mod test05 {
-    use std::collections::VecDeque;
-
-    use crate::day05::{extend_ranges, parse, solve_p2, Almanac, Range};
+    use crate::day05::{extend_ranges, get_destinations, parse, solve_p2, Almanac, Range};
+    use crate::interval::Interval;
 
     #[test]
     pub fn test_p2() {
@@ -334,73 +315,104 @@ temperature-to-humidity map:
 
 humidity-to-location map:
 60 56 37
-56 93 4"#
-            .lines();
-        let mut almanac = parse(input);
+56 93 4"#;
+        let mut almanac = parse(input).expect("should parse");
         almanac.sort_by_source();
-        // let res_p2 = solve_p2(&almanac, orig_seeds);
-        // assert_eq!(res_p2, 46);
+        assert_eq!(46, solve_p2(&almanac, almanac.seeds.clone()));
 
-        let res_test_val = solve_p2(&almanac, vec![82, 0]);
+        let res_test_val = solve_p2(&almanac, vec![82, 1]);
         assert_eq!(res_test_val, 46);
     }
 
     #[test]
     fn test_extend() {
-        let mut sources = VecDeque::new();
-        sources.push_front((5, 23));
-        let mut ranges = Vec::new();
-        let range1 = Range {
-            source_range_start: 10,
-            destination_range_start: 110,
-            range_length: 5,
-        };
-        let range2 = Range {
-            source_range_start: 16,
-            destination_range_start: 216,
-            range_length: 5,
-        };
-        ranges.push(range1);
-        ranges.push(range2);
-        let res = extend_ranges(sources, &ranges);
-        println!("{:?}", res);
+        let ranges = [
+            Range {
+                source_range_start: 10,
+                destination_range_start: 110,
+                range_length: 5,
+            },
+            Range {
+                source_range_start: 16,
+                destination_range_start: 216,
+                range_length: 5,
+            },
+        ];
+        let res = extend_ranges(&Interval::new(5, 24).into(), &ranges);
+        let expected = [(5, 10), (15, 16), (21, 24), (110, 115), (216, 221)];
+        assert_eq!(intervals(&expected), res.intervals());
     }
 
     #[test]
     fn test_extend_2() {
-        let mut sources = VecDeque::new();
-        sources.push_front((9, 90));
-        let mut ranges = Vec::new();
-        let range1 = Range {
-            source_range_start: 10,
-            destination_range_start: 105,
-            range_length: 10,
-        };
-        let range2 = Range {
-            source_range_start: 50,
-            destination_range_start: 100,
-            range_length: 40,
-        };
-        ranges.push(range1);
-        ranges.push(range2);
-        let res = extend_ranges(sources, &ranges);
-        println!("{:?}", res);
+        let ranges = [
+            Range {
+                source_range_start: 10,
+                destination_range_start: 105,
+                range_length: 10,
+            },
+            Range {
+                source_range_start: 50,
+                destination_range_start: 100,
+                range_length: 40,
+            },
+        ];
+        let res = extend_ranges(&Interval::new(9, 91).into(), &ranges);
+        // 50..90 lands on 100..140 and swallows 10..20, which lands on 105..115
+        let expected = [(9, 10), (20, 50), (90, 91), (100, 140)];
+        assert_eq!(intervals(&expected), res.intervals());
     }
 
     #[test]
     fn test_failing() {
         let mut almanac = provide_test_almanac();
-        println!("ltt {:?}", &almanac.light_to_temperature);
         almanac.sort_by_source();
-        let mut test_source = VecDeque::new();
-        test_source.push_front((77, 77));
+        let res = extend_ranges(&Interval::new(77, 78).into(), &almanac.light_to_temperature);
+        assert_eq!(Some(45), res.min());
+        assert_eq!(1, res.len());
+    }
 
-        println!("ltt {:?}", &almanac.light_to_temperature);
-        println!("res {:?}", test_source);
-        let mut res = extend_ranges(test_source, &almanac.light_to_temperature);
+    /// A seed range of length 1 right below a map range stays where it is, the map must not
+    /// catch the id after its end.
+    #[test]
+    fn test_range_end() {
+        let ranges = [Range {
+            destination_range_start: 13,
+            source_range_start: 11,
+            range_length: 5,
+        }];
+        let res = extend_ranges(&Interval::with_len(15, 1).into(), &ranges);
+        assert_eq!(Some(17), res.min());
+        let res = extend_ranges(&Interval::with_len(16, 1).into(), &ranges);
+        assert_eq!(Some(16), res.min());
+    }
+
+    #[test]
+    fn test_destinations() {
+        let ranges = [
+            Range {
+                destination_range_start: 52,
+                source_range_start: 50,
+                range_length: 48,
+            },
+            Range {
+                destination_range_start: 50,
+                source_range_start: 98,
+                range_length: 2,
+            },
+        ];
+        // range starts belong to their own range, range ends and unmapped ids keep their number
+        assert_eq!(
+            vec![49, 52, 99, 50, 51, 100],
+            get_destinations(&[49, 50, 97, 98, 99, 100], &ranges)
+        );
+    }
 
-        println!("res {:?}", res);
-        assert_eq!(res.pop_front(), Some((45, 45)))
+    fn intervals(bounds: &[(u64, u64)]) -> Vec<Interval<u64>> {
+        bounds
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
     }
 
     fn provide_test_almanac() -> Almanac {
@@ -436,8 +448,19 @@ temperature-to-humidity map:
 
 humidity-to-location map:
 60 56 37
-56 93 4"#
-            .lines();
-        parse(input)
+56 93 4"#;
+        parse(input).expect("should parse")
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n\nsoil-to-water map:\n0 15 37\n";
+        let err = parse(input).err().expect("wrong header");
+        assert_eq!(
+            "6:1: expected `soil-to-fertilizer map:`",
+            err.to_string()
+        );
+        let err = parse("seeds: 79 1x4\n").err().expect("bad seed");
+        assert_eq!((1, 11), (err.line, err.column));
     }
 }
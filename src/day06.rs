@@ -1,60 +1,71 @@
-use std::fs;
 use std::iter::zip;
 
-pub fn run() {
-    let file = fs::read_to_string("input/day06").expect("Could not open file.");
-    let mut lines = file.lines();
+use crate::day::{Answer, Day};
+use crate::parse::{self, ParseError};
 
-    let times: Vec<u64> = lines
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .skip(1)
-        .map(|val| val.parse::<u64>().unwrap())
-        .collect();
-    let distances: Vec<u64> = lines
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .skip(1)
-        .map(|val| val.parse::<u64>().unwrap())
-        .collect();
+pub struct Solution;
 
-    let res_1 = zip(times, distances)
-        .map(|(time, distance)| calc_winning_amount(time, distance))
-        .fold(1, |a, b| a * b);
+/// The time and record distance of each race, in input order.
+pub struct Races {
+    pub times: Vec<u64>,
+    pub distances: Vec<u64>,
+}
 
-    let mut lines_2 = file.lines();
-    let times_p2 = lines_2
-        .next()
-        .unwrap()
-        .split(':')
-        .skip(1)
-        .map(|line| line.replace(' ', ""))
-        .map(|no_wsp| no_wsp.parse::<u64>().unwrap())
-        .next()
-        .unwrap();
-    let distances_p2 = lines_2
-        .next()
-        .unwrap()
-        .split(':')
-        .skip(1)
-        .map(|line| line.replace(' ', ""))
-        .map(|no_wsp| no_wsp.parse::<u64>().unwrap())
-        .next()
-        .unwrap();
+impl Day for Solution {
+    const DAY: u8 = 6;
+    type Input = Races;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut lines = input.lines();
+        let times = parse_numbers(input, lines.next(), "Time:")?;
+        let distances = parse_numbers(input, lines.next(), "Distance:")?;
+        Ok(Races { times, distances })
+    }
 
-    let res_2 = calc_winning_amount(times_p2, distances_p2);
-    let res = (res_1, res_2);
-    println!("Day 06 Solution Part 1: {}", res.0);
-    println!("Day 06 Solution Part 2: {}", res.1);
+    fn part1(races: &Self::Input) -> Answer {
+        zip(&races.times, &races.distances)
+            .map(|(time, distance)| calc_winning_amount(*time, *distance))
+            .product::<u64>()
+            .into()
+    }
+
+    fn part2(races: &Self::Input) -> Answer {
+        let time = join_digits(&races.times);
+        let distance = join_digits(&races.distances);
+        calc_winning_amount(time, distance).into()
+    }
+}
+
+fn parse_numbers(input: &str, line: Option<&str>, label: &str) -> Result<Vec<u64>, ParseError> {
+    let Some(numbers) = line.and_then(|line| line.strip_prefix(label)) else {
+        let expected = format!("`{label} <numbers>`");
+        return Err(match line {
+            Some(line) => ParseError::at(input, line, expected),
+            None => ParseError::at_end(input, expected),
+        });
+    };
+    numbers
+        .split_whitespace()
+        .map(|val| parse::number(input, val))
+        .collect()
+}
+
+/// The kerning fix of part 2: all numbers of a line are actually one number.
+pub fn join_digits(numbers: &[u64]) -> u64 {
+    numbers
+        .iter()
+        .map(|number| number.to_string())
+        .collect::<String>()
+        .parse::<u64>()
+        .unwrap()
 }
 
-fn calc_winning_amount(time: u64, record_dist: u64) -> u64 {
+/// The number of ways to beat `record_dist` in a race of `time` milliseconds.
+pub fn calc_winning_amount(time: u64, record_dist: u64) -> u64 {
     let mut counter = 0;
     for charging in 1..time {
         // exclude first and last on purpose
-        if charging as u64 * ((time - charging) as u64) > record_dist as u64 {
+        if charging * (time - charging) > record_dist {
             counter += 1;
         }
     }
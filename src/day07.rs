@@ -1,10 +1,13 @@
 use std::cmp::Ordering;
-use std::fs;
 
 use itertools::Itertools;
 
+use crate::day::{Answer, Day};
+use crate::parse::{self, ParseError};
+
+/// A bid whose hand can be ranked against others.
 #[derive(Clone, Copy)]
-struct CamelBid {
+pub struct CamelBid {
     hand: Hand,
     bid: u32,
 }
@@ -18,10 +21,10 @@ struct Hand {
 impl Hand {
     fn from_cards(cards: [char; 5]) -> Hand {
         use HandType::*;
-        let mut ccards = cards.clone();
+        let mut ccards = cards;
         ccards.sort();
         let hand_type = match ccards
-            .group_by(|a, b| *a == *b)
+            .chunk_by(|a, b| *a == *b)
             .map(|grp| grp.len())
             .sorted()
             .pad_using(5, |_| 0)
@@ -46,7 +49,7 @@ impl Hand {
         let binding = cards.into_iter().filter(|card| *card != '!').sorted();
         let ccards: &[char] = binding.as_ref();
         let hand_type = match ccards
-            .group_by(|a, b| *a == *b)
+            .chunk_by(|a, b| *a == *b)
             .map(|grp| grp.len())
             .sorted()
             .pad_using(5, |_| 0)
@@ -78,78 +81,79 @@ enum HandType {
     HighCard = 0,
 }
 
-pub fn run() {
-    let res_1: u32 = fs::read_to_string("input/day07")
-        .expect("Could not open file.")
-        .lines()
-        .map(|line| parse_hand(line))
-        .sorted_by(camel_comparator)
-        .zip(1..)
-        .map(|(bid, rank)| rank * bid.bid)
-        .sum();
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 7;
+    type Input = Vec<RawBid>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.lines().map(|line| parse_bid(input, line)).collect()
+    }
+
+    fn part1(bids: &Self::Input) -> Answer {
+        total_winnings(bids.iter().map(camel_bid)).into()
+    }
+
+    fn part2(bids: &Self::Input) -> Answer {
+        total_winnings(bids.iter().map(camel_bid_extra_rule)).into()
+    }
+}
 
-    let res_2: u32 = fs::read_to_string("input/day07")
-        .expect("Could not open file.")
-        .lines()
-        .map(|line| parse_hand_extra_rule(line))
-        .sorted_by(camel_comparator)
+/// Ranks the bids from weakest to strongest hand and sums up bid times rank.
+pub fn total_winnings(bids: impl Iterator<Item = CamelBid>) -> u32 {
+    bids.sorted_by(camel_comparator)
         .zip(1..)
         .map(|(bid, rank)| rank * bid.bid)
-        .sum();
-    let res = (res_1, res_2);
-    println!("Day 07 Solution Part 1: {}", res.0);
-    println!("Day 07 Solution Part 2: {}", res.1);
+        .sum()
 }
 
-fn parse_hand(line: &str) -> CamelBid {
-    let (hand, bid) = line
-        .split_whitespace()
-        .tuples()
-        .next()
-        .expect("parsing bleh");
+/// A hand as written in the input, before the cards are mapped to sortable symbols.
+pub struct RawBid {
+    pub cards: [char; 5],
+    pub bid: u32,
+}
 
-    let cards: [char; 5] = hand
+fn parse_bid(input: &str, line: &str) -> Result<RawBid, ParseError> {
+    let Some((hand, bid)) = line.split_whitespace().collect_tuple() else {
+        return Err(ParseError::at(input, line, "`<hand> <bid>`"));
+    };
+    let cards = hand
         .chars()
-        .take(5)
-        .map(|card| match card {
-            'A' => 'Z',
-            'K' => 'X',
-            'T' => 'B',
-            rest => rest,
-        })
         .collect::<Vec<char>>()
         .try_into()
-        .expect("no clue what this is doing");
+        .ok()
+        .filter(|cards: &[char; 5]| cards.iter().all(|card| "AKQJT98765432".contains(*card)))
+        .ok_or_else(|| ParseError::at(input, hand, "five cards out of `AKQJT98765432`"))?;
+    let bid = parse::number(input, bid)?;
+    Ok(RawBid { cards, bid })
+}
+
+/// Ranks the hand by the rules of part 1.
+pub fn camel_bid(raw: &RawBid) -> CamelBid {
+    let cards = raw.cards.map(|card| match card {
+        'A' => 'Z',
+        'K' => 'X',
+        'T' => 'B',
+        rest => rest,
+    });
 
     let hand = Hand::from_cards(cards);
-    let bid = bid.parse::<u32>().expect("bid you not");
-    CamelBid { hand, bid }
+    CamelBid { hand, bid: raw.bid }
 }
 
-fn parse_hand_extra_rule(line: &str) -> CamelBid {
-    let (hand, bid) = line
-        .split_whitespace()
-        .tuples()
-        .next()
-        .expect("parsing bleh");
-
-    let cards: [char; 5] = hand
-        .chars()
-        .take(5)
-        .map(|card| match card {
-            'A' => 'Z',
-            'K' => 'X',
-            'T' => 'B',
-            'J' => '!',
-            rest => rest,
-        })
-        .collect::<Vec<char>>()
-        .try_into()
-        .expect("no clue what this is doing");
+/// Ranks the hand by the rules of part 2, where `J` is a joker.
+pub fn camel_bid_extra_rule(raw: &RawBid) -> CamelBid {
+    let cards = raw.cards.map(|card| match card {
+        'A' => 'Z',
+        'K' => 'X',
+        'T' => 'B',
+        'J' => '!',
+        rest => rest,
+    });
 
     let hand = Hand::from_cards_extra_rule(cards);
-    let bid = bid.parse::<u32>().expect("bid you not");
-    CamelBid { hand, bid }
+    CamelBid { hand, bid: raw.bid }
 }
 
 fn camel_comparator(a: &CamelBid, b: &CamelBid) -> Ordering {
@@ -1,23 +1,35 @@
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 use std::str::{FromStr, Lines};
 
 use itertools::Itertools;
 use num::integer::lcm;
 use parse_display::FromStr;
 
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::day::{Answer, Day};
+use crate::dot::{Dot, Graph, NodeStyle};
+use crate::parse::ParseError;
+
+/// A three letter node name, like `AAA`.
 #[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone, Debug)]
-struct Node(char, char, char);
+pub struct Node(pub char, pub char, pub char);
 
 impl FromStr for Node {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (a, b, c) = s.chars().next_tuple().expect("meh");
+        let (a, b, c) = s.chars().collect_tuple().ok_or(())?;
         Ok(Node(a, b, c))
     }
 }
 
+impl Display for Node {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.0, self.1, self.2)
+    }
+}
+
 #[derive(FromStr)]
 #[display("{nav_start} = ({nav_l}, {nav_r})")]
 struct NavLine {
@@ -26,68 +38,159 @@ struct NavLine {
     nav_r: Node,
 }
 
-pub fn run() {
-    let file = fs::read_to_string("input/day08").expect("Could not open file.");
-    let mut lines = file.lines();
-    let path = lines.next().unwrap();
+pub struct Solution;
+
+/// The left/right instructions and where each node leads to on `L` and on `R`.
+pub struct Network {
+    pub path: String,
+    pub l_map: HashMap<Node, Node>,
+    pub r_map: HashMap<Node, Node>,
+}
+
+impl Day for Solution {
+    const DAY: u8 = 8;
+    type Input = Network;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut lines = input.lines();
+        let path = match lines.next() {
+            Some(path) if !path.is_empty() && path.chars().all(|instr| "LR".contains(instr)) => {
+                path.to_string()
+            }
+            Some(path) => return Err(ParseError::at(input, path, "a path of `L` and `R`")),
+            None => return Err(ParseError::at_end(input, "a path of `L` and `R`")),
+        };
+        let (l_map, r_map) = parse_map(input, lines)?;
+        Ok(Network { path, l_map, r_map })
+    }
 
-    let (l_map, r_map) = parse_map(lines);
+    fn part1(network: &Self::Input) -> Answer {
+        Self::part1_cancellable(network, &CancellationToken::new())
+    }
 
-    let res_1 = count_steps(path, &l_map, &r_map);
-    let res_2 = count_steps_simultaneously(path, &l_map, &r_map);
+    fn part2(network: &Self::Input) -> Answer {
+        Self::part2_cancellable(network, &CancellationToken::new())
+    }
+
+    fn part1_cancellable(network: &Self::Input, token: &CancellationToken) -> Answer {
+        // the networks of part 2 start at other nodes
+        if !network.l_map.contains_key(&Node('A', 'A', 'A')) {
+            return Answer::Unsolved;
+        }
+        count_steps(&network.path, &network.l_map, &network.r_map, token)
+            .map_or(Answer::Unsolved, Answer::from)
+    }
+
+    fn part2_cancellable(network: &Self::Input, token: &CancellationToken) -> Answer {
+        count_steps_simultaneously(&network.path, &network.l_map, &network.r_map, token)
+            .map_or(Answer::Unsolved, Answer::from)
+    }
+}
+
+impl Dot for Solution {
+    /// Start nodes (`..A`) in green and end nodes (`..Z`) in red, with an edge per instruction.
+    /// The walk of part 1 from `AAA` is highlighted.
+    fn graph(network: &Self::Input) -> Graph {
+        let mut graph = Graph::new("day08-network");
+        for node in network.l_map.keys().sorted() {
+            let style = match node {
+                Node(_, _, 'A') => NodeStyle::color("green"),
+                Node(_, _, 'Z') => NodeStyle::color("red"),
+                _ => NodeStyle::PLAIN,
+            };
+            graph.node(node.to_string(), style);
+        }
+        for (node, left) in network.l_map.iter().sorted() {
+            graph.edge(node.to_string(), left.to_string(), Some(String::from("L")));
+            if let Some(right) = network.r_map.get(node) {
+                graph.edge(node.to_string(), right.to_string(), Some(String::from("R")));
+            }
+        }
+        graph.highlighted = walk(network).iter().map(Node::to_string).collect();
+        graph
+    }
+}
 
-    let res = (res_1, res_2);
-    println!("Day 08 Solution Part 1: {}", res.0);
-    println!("Day 08 Solution Part 2: {}", res.1);
+/// The nodes visited from `AAA` until `ZZZ`, or until the walk repeats itself without reaching it.
+fn walk(network: &Network) -> BTreeSet<Node> {
+    let mut curr = Node('A', 'A', 'A');
+    if !network.l_map.contains_key(&curr) {
+        return BTreeSet::new();
+    }
+    let mut visited = BTreeSet::from([curr]);
+    let mut seen = HashSet::new();
+    for (idx, instr) in network.path.chars().enumerate().cycle() {
+        if compare_end(&curr) || !seen.insert((idx, curr)) {
+            break;
+        }
+        let map_to_use = if instr == 'L' {
+            &network.l_map
+        } else {
+            &network.r_map
+        };
+        let Some(next) = map_to_use.get(&curr) else {
+            break;
+        };
+        curr = *next;
+        visited.insert(curr);
+    }
+    visited
 }
 
-fn count_steps_simultaneously(
+/// Steps until every node ending in `A` stands on a node ending in `Z` at the same time.
+pub fn count_steps_simultaneously(
     path: &str,
     l_map: &HashMap<Node, Node>,
     r_map: &HashMap<Node, Node>,
-) -> u64 {
-    l_map
+    token: &CancellationToken,
+) -> Result<u64, Cancelled> {
+    let steps = l_map
         .keys()
-        .filter(|key| match key {
-            Node(_, _, 'A') => true,
-            _ => false,
-        })
-        .map(|node| count_steps_starting(node, path, l_map, r_map, &compare_any_end))
-        .map(|val| val as u64)
+        .filter(|key| matches!(key, Node(_, _, 'A')))
+        .map(|node| count_steps_starting(node, path, l_map, r_map, &compare_any_end, token))
+        .map_ok(|val| val as u64)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(steps
+        .into_iter()
         .reduce(lcm)
-        .expect("should be fine")
+        .expect("parse checks that there is a start node"))
 }
 
-fn count_steps(path: &str, l_map: &HashMap<Node, Node>, r_map: &HashMap<Node, Node>) -> u32 {
+/// Steps from `AAA` to `ZZZ`.
+pub fn count_steps(
+    path: &str,
+    l_map: &HashMap<Node, Node>,
+    r_map: &HashMap<Node, Node>,
+    token: &CancellationToken,
+) -> Result<u32, Cancelled> {
     let start_point = Node('A', 'A', 'A');
-    count_steps_starting(&start_point, path, l_map, r_map, &compare_end)
+    count_steps_starting(&start_point, path, l_map, r_map, &compare_end, token)
 }
 
 fn compare_end(node: &Node) -> bool {
-    match node {
-        Node('Z', 'Z', 'Z') => true,
-        _ => false,
-    }
+    matches!(node, Node('Z', 'Z', 'Z'))
 }
 
 fn compare_any_end(node: &Node) -> bool {
-    match node {
-        Node(_, _, 'Z') => true,
-        _ => false,
-    }
+    matches!(node, Node(_, _, 'Z'))
 }
 
-fn count_steps_starting(
+/// Steps from `start_point` until `com_fn` accepts a node, repeating `path` as often as needed
+/// or until `token` is cancelled.
+pub fn count_steps_starting(
     start_point: &Node,
     path: &str,
     l_map: &HashMap<Node, Node>,
     r_map: &HashMap<Node, Node>,
     com_fn: &dyn Fn(&Node) -> bool,
-) -> u32 {
+    token: &CancellationToken,
+) -> Result<u32, Cancelled> {
     let mut curr = start_point;
     let mut steps = 0;
 
     loop {
+        // an end that cannot be reached would keep this walking forever
+        token.check()?;
         for instr in path.chars() {
             steps += 1;
 
@@ -98,26 +201,96 @@ fn count_steps_starting(
             };
 
             let next = map_to_use
-                .get(&curr)
-                .expect("Map does not contain curr node!");
+                .get(curr)
+                .expect("parse checks that every node leads to a defined one");
 
             if com_fn(next) {
-                return steps;
+                return Ok(steps);
             }
             curr = next;
         }
     }
 }
 
-fn parse_map(lines: Lines) -> (HashMap<Node, Node>, HashMap<Node, Node>) {
+type NodeMaps = (HashMap<Node, Node>, HashMap<Node, Node>);
+
+/// The nodes below the path, which every node they lead to has to be one of.
+fn parse_map(input: &str, mut lines: Lines<'_>) -> Result<NodeMaps, ParseError> {
+    match lines.next() {
+        Some("") => (),
+        Some(line) => return Err(ParseError::at(input, line, "an empty line after the path")),
+        None => return Err(ParseError::at_end(input, "an empty line after the path")),
+    }
+
     let mut l_map = HashMap::new();
     let mut r_map = HashMap::new();
-
-    for line in lines.skip(1) {
-        let nav_line = line.parse::<NavLine>().expect("could not parse line");
+    let mut targets = Vec::new();
+    for line in lines {
+        let nav_line = line
+            .parse::<NavLine>()
+            .map_err(|_| ParseError::at(input, line, "`AAA = (BBB, CCC)`"))?;
         l_map.insert(nav_line.nav_start, nav_line.nav_l);
         r_map.insert(nav_line.nav_start, nav_line.nav_r);
+        targets.push((line, [nav_line.nav_l, nav_line.nav_r]));
+    }
+
+    if !l_map.keys().any(|node| matches!(node, Node(_, _, 'A'))) {
+        return Err(ParseError::at_end(input, "a start node like `AAA`"));
+    }
+    for (line, nodes) in targets {
+        let (_, destinations) = line.split_once('(').expect("nav lines have targets");
+        if let Some(node) = nodes.iter().find(|node| !l_map.contains_key(node)) {
+            let name = node.to_string();
+            let fragment = destinations
+                .find(&name)
+                .map_or(line, |idx| &destinations[idx..idx + name.len()]);
+            return Err(ParseError::at(
+                input,
+                fragment,
+                format!("a defined node, found `{name}`"),
+            ));
+        }
     }
 
-    (l_map, r_map)
+    Ok((l_map, r_map))
+}
+
+#[test]
+fn test_graph() {
+    let input = std::fs::read_to_string("examples/day08/example1.txt").expect("fixture exists");
+    let graph = Solution::graph(&Solution::parse(&input).expect("should parse"));
+    assert_eq!("day08-network", graph.name);
+    // `RL` leads from AAA right to CCC and left to ZZZ
+    assert_eq!(
+        BTreeSet::from(["AAA", "CCC", "ZZZ"].map(String::from)),
+        graph.highlighted
+    );
+    assert_eq!(Some(&NodeStyle::color("green")), graph.nodes.get("AAA"));
+    assert_eq!(Some(&NodeStyle::color("red")), graph.nodes.get("ZZZ"));
+    assert_eq!(14, graph.edges.len());
+
+    let input = std::fs::read_to_string("examples/day08/example2.txt").expect("fixture exists");
+    let graph = Solution::graph(&Solution::parse(&input).expect("should parse"));
+    assert_eq!(
+        BTreeSet::from(["AAA", "BBB", "ZZZ"].map(String::from)),
+        graph.highlighted
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let error = |input: &str| Solution::parse(input).map(|_| ()).unwrap_err().to_string();
+    assert_eq!(
+        "2:1: expected an empty line after the path",
+        error("L\nAAA = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)")
+    );
+    assert_eq!("3:1: expected a start node like `AAA`", error("L\n\n"));
+    assert_eq!(
+        "3:13: expected a defined node, found `ZZZ`",
+        error("L\n\nAAA = (AAA, ZZZ)")
+    );
+    // part 2 alone has no `AAA` to start part 1 from
+    let network = Solution::parse("L\n\n11A = (11Z, 11Z)\n11Z = (11A, 11A)").expect("should parse");
+    assert_eq!(Answer::Unsolved, Solution::part1(&network));
+    assert_eq!(Answer::from(1), Solution::part2(&network));
 }
@@ -1,23 +1,48 @@
-use std::fs;
-
-pub fn run() {
-    let file = fs::read_to_string("input/day09").expect("Could not open file.");
-    let lines = file.lines();
-    let (sol_prev, sol_next): (i32, i32) = lines
-        .map(|line| calc_prev_next_val(line))
-        .reduce(|(a_p, a_n), (b_p, b_n)| (a_p + b_p, a_n + b_n))
-        .unwrap();
-    println!("Day 09 Solution Part 1: {}", sol_next);
-    println!("Day 09 Solution Part 2: {}", sol_prev);
+use crate::day::{Answer, Day};
+use crate::parse::{self, ParseError};
+
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 9;
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input
+            .lines()
+            .map(|line| parse_values(input, line))
+            .collect()
+    }
+
+    fn part1(histories: &Self::Input) -> Answer {
+        histories
+            .iter()
+            .map(|values| calc_prev_next_val(values).1)
+            .sum::<i32>()
+            .into()
+    }
+
+    fn part2(histories: &Self::Input) -> Answer {
+        histories
+            .iter()
+            .map(|values| calc_prev_next_val(values).0)
+            .sum::<i32>()
+            .into()
+    }
 }
 
-fn calc_prev_next_val(line: &str) -> (i32, i32) {
-    let values: Vec<i32> = line
-        .split_whitespace()
-        .map(|val| val.parse::<i32>().expect("could not parse"))
-        .collect();
+fn parse_values(input: &str, line: &str) -> Result<Vec<i32>, ParseError> {
+    if line.trim().is_empty() {
+        return Err(ParseError::at(input, line, "at least one value"));
+    }
+    line.split_whitespace()
+        .map(|val| parse::number(input, val))
+        .collect()
+}
 
-    let (res_prev_derived, res_next_derived) = rec_derive_return_last(&values);
+/// Extrapolates the history one value backwards and one forwards.
+pub fn calc_prev_next_val(values: &[i32]) -> (i32, i32) {
+    let (res_prev_derived, res_next_derived) = rec_derive_return_last(values);
     (
         values.first().expect("nop...") - res_prev_derived,
         values.last().expect("meh...") + res_next_derived,
@@ -29,7 +54,7 @@ fn rec_derive_return_last(values: &[i32]) -> (i32, i32) {
         .windows(2)
         .map(|window| window[1] - window[0])
         .collect();
-    if derived.iter().filter(|val| **val != 0).count() == 0 {
+    if derived.iter().all(|val| *val == 0) {
         (0, 0)
     } else {
         let (res_prev_derived, res_next_derived) = rec_derive_return_last(&derived);
@@ -42,6 +67,7 @@ fn rec_derive_return_last(values: &[i32]) -> (i32, i32) {
 
 #[test]
 fn test_values() {
-    let res = calc_prev_next_val("10 13 16 21 30 45");
+    let line = "10 13 16 21 30 45";
+    let res = calc_prev_next_val(&parse_values(line, line).expect("should parse"));
     assert_eq!((5, 68), res);
 }
@@ -1,48 +1,77 @@
-use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::str::Lines;
+use std::collections::HashSet;
 
 use itertools::Itertools;
 
+use crate::day::{Answer, Day};
+use crate::geometry::Direction;
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::render::{Cell, Picture, Render, Style};
+
 use crate::day10::Pipe::{
     Ground, Horizontal, NorthToEast, NorthToWest, SouthToEast, SouthToWest, Start, Vertical,
 };
 
-pub fn run() {
-    let file = fs::read_to_string("input/day10").expect("Could not open file.");
-    let map = parse_map(file.lines());
-    let (res_p1, res_p2) = solve(map);
-    println!("Day 10 Solution Part 1: {}", res_p1);
-    println!("Day 10 Solution Part 2: {}", res_p2);
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 10;
+    type Input = Grid<Pipe>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_map(input)
+    }
+
+    fn part1(pipe_map: &Self::Input) -> Answer {
+        let (path, _) = find_loop(pipe_map);
+        (path.len() / 2).into()
+    }
+
+    fn part2(pipe_map: &Self::Input) -> Answer {
+        solve_p2(pipe_map).into()
+    }
 }
 
-fn parse_map(lines: Lines) -> HashMap<(i32, i32), Pipe> {
-    let mut pipe_map = HashMap::new();
-    for (y_val, line) in lines.enumerate() {
-        for (x_val, char) in line.trim().char_indices() {
-            pipe_map.insert((x_val as i32, y_val as i32), Pipe::from_char(&char));
-        }
+fn parse_map(input: &str) -> Result<Grid<Pipe>, ParseError> {
+    let pipe_map = Grid::parse(input, "a pipe out of `|-LJ7F.S`", |char| Pipe::from_char(&char))?;
+    if pipe_map.find(|pipe| *pipe == Start).is_none() {
+        return Err(ParseError::at_end(input, "a start tile `S`"));
     }
+    if let Err((x, y)) = trace_loop(&pipe_map) {
+        // the tiles are single bytes, so the column is the byte offset in the line
+        let line = input.lines().nth(y).unwrap_or(input);
+        return Err(ParseError::at(
+            input,
+            line.get(x..).unwrap_or(line),
+            "a pipe that continues the loop from `S`",
+        ));
+    }
+    Ok(pipe_map)
+}
+
+/// The tiles of the loop (start last) and the pipe hidden below the start.
+type Loop = (Vec<(usize, usize)>, Pipe);
 
-    pipe_map
+/// Follows the loop from the start tile.
+pub fn find_loop(pipe_map: &Grid<Pipe>) -> Loop {
+    trace_loop(pipe_map).expect("parse checks that the loop is closed")
 }
 
-fn solve(mut pipe_map: HashMap<(i32, i32), Pipe>) -> (usize, usize) {
-    let (start_pos, _) = pipe_map
-        .iter()
-        .filter(|(_, pipe)| **pipe == Start)
-        .next()
-        .expect("Could not find start");
+/// Like [`find_loop`], but fails with the tile where the loop breaks.
+fn trace_loop(pipe_map: &Grid<Pipe>) -> Result<Loop, (usize, usize)> {
+    let start_pos = pipe_map
+        .find(|pipe| *pipe == Start)
+        .expect("parse checks for a start");
 
-    let starting_directions = determine_start_directions(start_pos, &pipe_map);
+    let starting_directions = determine_start_directions(&start_pos, pipe_map).ok_or(start_pos)?;
 
-    let mut curr_pos = *start_pos;
+    let mut curr_pos = start_pos;
     let mut curr_dir = starting_directions.0;
     let mut path = Vec::new();
 
     loop {
-        let (next_pos, next_direction) = perform_step(&curr_pos, &curr_dir, &pipe_map);
-        if next_pos == *start_pos {
+        let (next_pos, next_direction) = perform_step(&curr_pos, &curr_dir, pipe_map)?;
+        if next_pos == start_pos {
             break;
         }
 
@@ -51,15 +80,25 @@ fn solve(mut pipe_map: HashMap<(i32, i32), Pipe>) -> (usize, usize) {
         curr_dir = next_direction;
     }
 
-    let res_1 = (path.len() + 1) / 2;
+    path.push(start_pos);
+    let start_pipe = Pipe::determine_start_pipe(&starting_directions.0, &starting_directions.1);
+
+    Ok((path, start_pipe))
+}
+
+/// The number of tiles enclosed by the loop.
+pub fn solve_p2(pipe_map: &Grid<Pipe>) -> usize {
+    enclosed_tiles(pipe_map).len()
+}
+
+/// The tiles enclosed by the loop, row after row.
+pub fn enclosed_tiles(pipe_map: &Grid<Pipe>) -> Vec<(usize, usize)> {
+    let (path, start_pipe) = find_loop(pipe_map);
+    let mut pipe_map = pipe_map.clone();
+    pipe_map[*path.last().expect("loop is never empty")] = start_pipe;
 
-    path.push(*start_pos);
-    pipe_map.insert(
-        *start_pos,
-        Pipe::determine_start_pipe(&starting_directions.0, &starting_directions.1),
-    );
     let (min_x, min_y, max_x, max_y) = path.iter().fold(
-        (i32::MAX, i32::MAX, i32::MIN, i32::MIN),
+        (usize::MAX, usize::MAX, usize::MIN, usize::MIN),
         |(min_x, min_y, max_x, max_y), (path_x, path_y)| {
             (
                 min_x.min(*path_x),
@@ -70,41 +109,41 @@ fn solve(mut pipe_map: HashMap<(i32, i32), Pipe>) -> (usize, usize) {
         },
     );
 
-    let path_lookup: HashSet<&(i32, i32)> = path.iter().collect();
+    let path_lookup: HashSet<&(usize, usize)> = path.iter().collect();
 
-    let candidates: Vec<&(i32, i32)> = pipe_map
-        .iter()
-        .filter_map(|(val, _)| (path_lookup.get(val) == None).then(|| val))
+    let candidates: Vec<(usize, usize)> = pipe_map
+        .positions()
+        .filter(|val| !path_lookup.contains(val))
         .filter(|(elem_x, elem_y)| {
             *elem_x >= min_x && *elem_y >= min_y && *elem_x <= max_x && *elem_y <= max_y
         })
         .collect();
 
-    let mut res_2 = 0;
+    let mut res_2 = Vec::new();
     for (can_x, can_y) in candidates {
         let left = path
             .iter()
-            .filter(|(path_x, path_y)| path_x < can_x && path_y == can_y)
+            .filter(|(path_x, path_y)| *path_x < can_x && *path_y == can_y)
             .sorted_by(|first, second| second.0.cmp(&first.0))
-            .collect::<Vec<&(i32, i32)>>();
+            .collect::<Vec<&(usize, usize)>>();
 
         if determine_inside(&left, &pipe_map) {
-            res_2 += 1;
+            res_2.push((can_x, can_y));
         }
     }
 
-    (res_1, res_2)
+    res_2
 }
 
 fn determine_inside(
-    left_nodes_sorted: &[&(i32, i32)],
-    pipe_map: &HashMap<(i32, i32), Pipe>,
+    left_nodes_sorted: &[&(usize, usize)],
+    pipe_map: &Grid<Pipe>,
 ) -> bool {
     let mut inside = false;
     let mut last_found = None;
 
     for node in left_nodes_sorted {
-        let pipe = pipe_map.get(node).expect("wat");
+        let pipe = pipe_map.get(**node).expect("wat");
         use Pipe::*;
         match (pipe, &last_found) {
             (Vertical, None) => {
@@ -132,46 +171,78 @@ fn determine_inside(
             }
             (Horizontal, Some(_)) => (),
 
-            (other, val) => {
-                println!("{:?} , {:?}", other, val);
-                unreachable!("uh?");
-            }
+            (other, val) => unreachable!("uh? {other:?} , {val:?}"),
         }
     }
     inside
 }
 
+/// The next tile and the direction to leave it in, or the tile the loop breaks at: the current
+/// one if it leads off the map, the next one if it cannot be entered from this side.
 fn perform_step(
-    curr_pos: &(i32, i32),
+    curr_pos: &(usize, usize),
     direction: &Direction,
-    pipe_map: &HashMap<(i32, i32), Pipe>,
-) -> ((i32, i32), Direction) {
-    let next_pos = direction.move_dir(curr_pos);
-    let next_pipe = pipe_map.get(&next_pos).expect("cannot move there");
-    let next_dir = next_pipe
-        .change_dir_safe(direction)
-        .expect("Cannot enter that pipe from that side");
-
-    (next_pos, next_dir)
+    pipe_map: &Grid<Pipe>,
+) -> Result<((usize, usize), Direction), (usize, usize)> {
+    let next_pos = pipe_map
+        .offset(*curr_pos, direction.delta())
+        .ok_or(*curr_pos)?;
+    let next_pipe = &pipe_map[next_pos];
+    let next_dir = next_pipe.change_dir_safe(direction).ok_or(next_pos)?;
+
+    Ok((next_pos, next_dir))
 }
 
+/// The first two sides of the start that a pipe connects to, if there are two.
 fn determine_start_directions(
-    start_pos: &(i32, i32),
-    pipe_map: &HashMap<(i32, i32), Pipe>,
-) -> (Direction, Direction) {
-    use Direction::*;
-    [North, East, South, West]
+    start_pos: &(usize, usize),
+    pipe_map: &Grid<Pipe>,
+) -> Option<(Direction, Direction)> {
+    Direction::CARDINAL
         .iter()
-        .map(|direction| (direction, direction.move_dir(start_pos)))
-        .filter_map(|(dir, n_pos)| pipe_map.get(&n_pos).map(|val| (dir, val)))
+        .filter_map(|direction| {
+            let n_pos = pipe_map.offset(*start_pos, direction.delta())?;
+            Some((direction, &pipe_map[n_pos]))
+        })
         .filter_map(|(dir, pipe)| pipe.change_dir_safe(dir).map(|_| *dir))
         .tuples()
         .next()
-        .expect("could not find start dir")
 }
 
-#[derive(PartialEq, Debug)]
-enum Pipe {
+impl Render for Solution {
+    /// The loop in box drawing characters, with the tiles it encloses marked `I`.
+    fn render(pipe_map: &Self::Input) -> Vec<Picture> {
+        let (path, _) = find_loop(pipe_map);
+        let on_loop: HashSet<&(usize, usize)> = path.iter().collect();
+        let enclosed: HashSet<(usize, usize)> = enclosed_tiles(pipe_map).into_iter().collect();
+        let cells = pipe_map
+            .iter()
+            .map(|(position, pipe)| {
+                if *pipe == Start {
+                    Cell::new('S', Style::Red)
+                } else if on_loop.contains(&position) {
+                    Cell::new(pipe.box_glyph(), Style::Yellow).or_plain(pipe.to_char())
+                } else if enclosed.contains(&position) {
+                    Cell::new('I', Style::Green)
+                } else {
+                    Cell::new('.', Style::Dim)
+                }
+            })
+            .collect();
+        vec![Picture {
+            title: format!(
+                "day10: loop of {} tiles enclosing {}",
+                path.len(),
+                enclosed.len()
+            ),
+            cells: Grid::from_cells(pipe_map.width(), pipe_map.height(), cells),
+        }]
+    }
+}
+
+/// A tile of the pipe map.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Pipe {
     Horizontal,
     Vertical,
     NorthToEast,
@@ -182,29 +253,9 @@ enum Pipe {
     Start,
 }
 
-#[derive(Debug, Copy, Clone)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Direction {
-    fn move_dir(&self, (start_x, start_y): &(i32, i32)) -> (i32, i32) {
-        use Direction::*;
-        match self {
-            North => (*start_x, start_y - 1),
-            East => (start_x + 1, *start_y),
-            South => (*start_x, start_y + 1),
-            West => (start_x - 1, *start_y),
-        }
-    }
-}
-
 impl Pipe {
-    fn from_char(character: &char) -> Self {
-        match character {
+    fn from_char(character: &char) -> Option<Self> {
+        let pipe = match character {
             '|' => Vertical,
             '-' => Horizontal,
             'L' => NorthToEast,
@@ -213,10 +264,33 @@ impl Pipe {
             'F' => SouthToEast,
             '.' => Ground,
             'S' => Start,
-            val => {
-                println!("val: {}", val);
-                unreachable!("eh?")
-            }
+            _ => return None,
+        };
+        Some(pipe)
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Vertical => '|',
+            Horizontal => '-',
+            NorthToEast => 'L',
+            NorthToWest => 'J',
+            SouthToWest => '7',
+            SouthToEast => 'F',
+            Ground => '.',
+            Start => 'S',
+        }
+    }
+
+    fn box_glyph(&self) -> char {
+        match self {
+            Vertical => '│',
+            Horizontal => '─',
+            NorthToEast => '└',
+            NorthToWest => '┘',
+            SouthToWest => '┐',
+            SouthToEast => '┌',
+            other => other.to_char(),
         }
     }
 
@@ -237,18 +311,19 @@ impl Pipe {
         use Direction::*;
         match (a, b) {
             (North, South) | (South, North) => Vertical,
+            (East, West) | (West, East) => Horizontal,
+            (North, East) | (East, North) => NorthToEast,
+            (North, West) | (West, North) => NorthToWest,
             (West, South) | (South, West) => SouthToWest,
-            (East, South) | (South, East) => SouthToWest,
-            _ => {
-                unimplemented!("not used for my or test input yet, todo if i wanna try more inputs")
-            }
+            (East, South) | (South, East) => SouthToEast,
+            _ => unreachable!("the start connects two different sides"),
         }
     }
 }
 
 #[cfg(test)]
 mod test_p10 {
-    use crate::day10::{parse_map, solve};
+    use crate::day10::{parse_map, solve_p2};
 
     #[test]
     fn test_1() {
@@ -261,8 +336,8 @@ mod test_p10 {
 .|..|.|..|.
 .L--J.L--J.
 ..........."#;
-        let map = parse_map(input.lines());
-        let (_, res_p2) = solve(map);
+        let map = parse_map(input).expect("should parse");
+        let res_p2 = solve_p2(&map);
         assert_eq!(4, res_p2);
     }
 
@@ -278,8 +353,18 @@ L---JF-JLJ.||-FJLJJ7
 7-L-JL7||F7|L7F-7F7|
 L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L"#;
-        let map = parse_map(input.lines());
-        let (_, res_p2) = solve(map);
+        let map = parse_map(input).expect("should parse");
+        let res_p2 = solve_p2(&map);
         assert_eq!(10, res_p2);
     }
+
+    #[test]
+    fn test_broken_loop() {
+        let err = parse_map(".S-7.\n.|.|.\n.L-|.").expect_err("the loop is broken");
+        assert_eq!("3:4: expected a pipe that continues the loop from `S`", err.to_string());
+        let err = parse_map("S-7\n|.|\nL--").expect_err("the loop leads off the map");
+        assert_eq!((3, 3), (err.line, err.column));
+        let err = parse_map("...\n.S.\n...").expect_err("the start leads nowhere");
+        assert_eq!((2, 2), (err.line, err.column));
+    }
 }
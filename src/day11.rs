@@ -1,22 +1,78 @@
-use itertools::Itertools;
 use std::collections::HashSet;
-use std::fs;
-use std::str::Lines;
 
-pub fn run() {
-    let file = fs::read_to_string("input/day11").expect("Could not open file.");
+use itertools::Itertools;
 
-    let universe = parse_universe(file.lines(), 1);
-    let res_1 = solve(&universe);
+use crate::day::{Answer, Day};
+use crate::geometry::Point2;
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::render::{Cell, Picture, Render, Style};
 
-    let universe = parse_universe(file.lines(), 1000000);
-    let res_2 = solve(&universe);
+pub struct Solution;
 
-    println!("Day 11 Solution Part 1: {}", res_1);
-    println!("Day 11 Solution Part 2: {}", res_2);
+/// The galaxies as `(x, y)`, and which columns and rows hold none of them.
+pub struct Universe {
+    pub galaxies: HashSet<(usize, usize)>,
+    pub empty_x: Vec<bool>,
+    pub empty_y: Vec<bool>,
 }
 
-fn solve(universe: &HashSet<(usize, usize)>) -> usize {
+impl Day for Solution {
+    const DAY: u8 = 11;
+    type Input = Universe;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_universe(input)
+    }
+
+    fn part1(universe: &Self::Input) -> Answer {
+        solve(&universe.expand(1)).into()
+    }
+
+    fn part2(universe: &Self::Input) -> Answer {
+        solve(&universe.expand(1000000)).into()
+    }
+}
+
+impl Render for Solution {
+    /// The image after the expansion of part 1, with the inserted rows and columns in blue.
+    fn render(universe: &Self::Input) -> Vec<Picture> {
+        // the indices after expansion of the copies inserted next to every empty row or column
+        let inserted = |empty: &[bool]| {
+            let mut new_idx = 0;
+            let mut inserted = HashSet::new();
+            for is_empty in empty {
+                if *is_empty {
+                    inserted.insert(new_idx + 1);
+                    new_idx += 1;
+                }
+                new_idx += 1;
+            }
+            (new_idx, inserted)
+        };
+        let (width, inserted_x) = inserted(&universe.empty_x);
+        let (height, inserted_y) = inserted(&universe.empty_y);
+        let galaxies = universe.expand(1);
+        let mut image = Grid::new(width, height, Cell::new('.', Style::Dim));
+        for (position, cell) in image.iter_mut() {
+            if galaxies.contains(&position) {
+                *cell = Cell::new('#', Style::Yellow);
+            } else if inserted_x.contains(&position.0) || inserted_y.contains(&position.1) {
+                *cell = Cell::new('.', Style::Blue);
+            }
+        }
+        vec![Picture {
+            title: format!(
+                "day11: {} galaxies in the expanded {width}x{height} image",
+                galaxies.len()
+            ),
+            cells: image,
+        }]
+    }
+}
+
+/// The sum of the shortest paths between all pairs of galaxies.
+pub fn solve(universe: &HashSet<(usize, usize)>) -> usize {
     universe
         .iter()
         .tuple_combinations()
@@ -24,44 +80,55 @@ fn solve(universe: &HashSet<(usize, usize)>) -> usize {
         .sum()
 }
 
-fn calc_distance((g1_x, g1_y): &(usize, usize), (g2_x, g2_y): &(usize, usize)) -> usize {
-    if g1_x == g2_x && g1_y == g2_y {
-        0
-    } else {
-        (g1_x.max(g2_x) - g1_x.min(g2_x)) + (g1_y.max(g2_y) - g1_y.min(g2_y))
-    }
+/// Manhattan distance of two galaxies.
+pub fn calc_distance(g1: &(usize, usize), g2: &(usize, usize)) -> usize {
+    Point2::from(*g1).manhattan(Point2::from(*g2))
 }
 
-fn parse_universe(lines: Lines, expansion_factor: usize) -> HashSet<(usize, usize)> {
-    let mut empty_x = vec![true; 150];
-    let mut empty_y = vec![true; 150];
-    let mut universe = HashSet::new();
-    for (y_val, line) in lines.enumerate() {
-        for (x_val, char) in line.char_indices() {
-            match char {
-                '.' => (),
-                '#' => {
-                    universe.insert((x_val, y_val));
-                    empty_x[x_val] = false;
-                    empty_y[y_val] = false;
-                }
-                _ => unreachable!("other characters? O_o"),
+fn parse_universe(input: &str) -> Result<Universe, ParseError> {
+    let image = Grid::parse(input, "`.` or `#`", |char| match char {
+        '.' => Some(false),
+        '#' => Some(true),
+        _ => None,
+    })?;
+    let galaxies = image
+        .iter()
+        .filter(|(_, galaxy)| **galaxy)
+        .map(|(position, _)| position)
+        .collect();
+    let empty_x = image
+        .columns()
+        .map(|mut column| !column.any(|galaxy| *galaxy))
+        .collect();
+    let empty_y = image
+        .rows()
+        .map(|row| !row.contains(&true))
+        .collect();
+    Ok(Universe {
+        galaxies,
+        empty_x,
+        empty_y,
+    })
+}
+
+impl Universe {
+    /// The galaxy positions after every empty row and column grew `expansion_factor` times
+    /// (a factor of 1 still doubles them, as in part 1).
+    pub fn expand(&self, expansion_factor: usize) -> HashSet<(usize, usize)> {
+        let mut real_universe = HashSet::new();
+        for (galaxy_xpos, galaxy_ypos) in &self.galaxies {
+            let count_spaces = |spaaaace: &[bool], pos: usize| -> usize {
+                spaaaace[0..pos].iter().filter(|val| **val).count() * (expansion_factor - 1).max(1)
             };
-        }
-    }
-    let mut real_universe = HashSet::new();
-    for (galaxy_xpos, galaxy_ypos) in universe {
-        let count_spaces = |spaaaace: &[bool], pos: usize| -> usize {
-            spaaaace[0..pos].iter().filter(|val| **val).count() * (expansion_factor - 1).max(1)
-        };
 
-        let real_xpos = galaxy_xpos + count_spaces(&empty_x, galaxy_xpos);
-        let real_ypos = galaxy_ypos + count_spaces(&empty_y, galaxy_ypos);
+            let real_xpos = galaxy_xpos + count_spaces(&self.empty_x, *galaxy_xpos);
+            let real_ypos = galaxy_ypos + count_spaces(&self.empty_y, *galaxy_ypos);
 
-        real_universe.insert((real_xpos, real_ypos));
-    }
+            real_universe.insert((real_xpos, real_ypos));
+        }
 
-    real_universe
+        real_universe
+    }
 }
 
 #[test]
@@ -77,14 +144,10 @@ fn test() {
 .......#..
 #...#....."#;
 
-    let universe = parse_universe(test_input.lines(), 1);
-    let test_0 = solve(&universe);
-
-    let universe = parse_universe(test_input.lines(), 10);
-    let test_1 = solve(&universe);
-
-    let universe = parse_universe(test_input.lines(), 100);
-    let test_2 = solve(&universe);
+    let universe = parse_universe(test_input).expect("should parse");
+    let test_0 = solve(&universe.expand(1));
+    let test_1 = solve(&universe.expand(10));
+    let test_2 = solve(&universe.expand(100));
 
     assert_eq!(374, test_0);
     assert_eq!(1030, test_1);
@@ -1,65 +1,98 @@
-use std::fs;
-use std::str::Lines;
-use std::time::Instant;
-
 use itertools::Itertools;
 
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::day::{Answer, Day};
+use crate::parse::{self, ParseError};
 use crate::day12::Spring::{Damaged, Unknown};
 
-pub fn run() {
-    let file = fs::read_to_string("input/day12").expect("Could not open file.");
-    let now = Instant::now();
-    let (res_1, res_2) = solve(file.lines());
-    println!("Solutions took {} µs", now.elapsed().as_micros());
-    assert_eq!(7361, res_1);
-    println!("Day 12 Solution Part 1: {}", res_1);
-    println!("Day 12 Solution Part 2: {}", res_2);
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 12;
+    type Input = Vec<Row>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_rows(input)
+    }
+
+    fn part1(rows: &Self::Input) -> Answer {
+        Self::part1_cancellable(rows, &CancellationToken::new())
+    }
+
+    fn part2(rows: &Self::Input) -> Answer {
+        Self::part2_cancellable(rows, &CancellationToken::new())
+    }
+
+    fn part1_cancellable(rows: &Self::Input, token: &CancellationToken) -> Answer {
+        rows.iter()
+            .map(|row| row.calculate_arrangements(token))
+            .sum::<Result<usize, _>>()
+            .map_or(Answer::Unsolved, Answer::from)
+    }
+
+    fn part2_cancellable(rows: &Self::Input, token: &CancellationToken) -> Answer {
+        rows.iter()
+            .map(|row| {
+                crate::debug!("starting row {row:?}");
+                row.calculate_arrangements_expanded(token)
+            })
+            .sum::<Result<usize, _>>()
+            .map_or(Answer::Unsolved, Answer::from)
+    }
 }
 
 #[derive(PartialEq, Debug, Clone, Copy)]
-enum Spring {
+pub enum Spring {
     Damaged,
     Operational,
     Unknown,
 }
 
+/// A row of springs with the sizes of its contiguous groups of damaged springs.
 #[derive(Debug)]
-struct Row {
-    row: Vec<Spring>,
-    damage_groups: Vec<usize>,
-}
-
-struct Group {
-    elements: Vec<Spring>,
-    pure: bool,
+pub struct Row {
+    pub row: Vec<Spring>,
+    pub damage_groups: Vec<usize>,
 }
 
 impl Row {
-    fn calculate_arrangements(&self) -> usize {
-        Self::sub_calculate(&self.row, &self.damage_groups)
+    /// The number of ways to replace the unknown springs so that the groups match, unless
+    /// `token` is cancelled before they are all counted.
+    pub fn calculate_arrangements(&self, token: &CancellationToken) -> Result<usize, Cancelled> {
+        Self::sub_calculate(&self.row, &self.damage_groups, token)
     }
 
-    fn calculate_arrangements_expanded(&self) -> usize {
+    /// Like [`Row::calculate_arrangements`], on the row unfolded five times.
+    pub fn calculate_arrangements_expanded(
+        &self,
+        token: &CancellationToken,
+    ) -> Result<usize, Cancelled> {
         let mut expanded_row = self.row.clone();
         expanded_row.push(Unknown);
         let mut expanded_row = expanded_row.repeat(5);
         let _ = expanded_row.pop();
 
-        println!("Starting expanded");
-        Self::sub_calculate(&expanded_row, &self.damage_groups.repeat(5))
+        crate::trace!("starting expanded");
+        Self::sub_calculate(&expanded_row, &self.damage_groups.repeat(5), token)
     }
 
-    fn sub_calculate(rest_row: &[Spring], rest_groups: &[usize]) -> usize {
-        if rest_groups.len() == 0 {
-            if rest_row.iter().any(|filter| *filter == Damaged) {
-                return 0;
+    fn sub_calculate(
+        rest_row: &[Spring],
+        rest_groups: &[usize],
+        token: &CancellationToken,
+    ) -> Result<usize, Cancelled> {
+        // the unfolded rows of part 2 take far too long without memoization
+        token.check()?;
+        if rest_groups.is_empty() {
+            if rest_row.contains(&Damaged) {
+                return Ok(0);
             }
-            return 1;
+            return Ok(1);
         } else if rest_row.len() < rest_groups[0] {
-            return 0;
+            return Ok(0);
         }
         if Self::count_def_blocks(rest_row) > rest_groups.len() {
-            return 0;
+            return Ok(0);
         }
 
         let curr_group = rest_groups[0];
@@ -70,9 +103,11 @@ impl Row {
                 .all(|val| *val == Damaged || *val == Unknown);
             let res = match (all_following_may_be_damaged, rest_row.get(i + curr_group)) {
                 (true, Some(Unknown)) | (true, Some(Spring::Operational)) => {
-                    Self::sub_calculate(&rest_row[i + curr_group + 1..], &rest_groups[1..])
+                    Self::sub_calculate(&rest_row[i + curr_group + 1..], &rest_groups[1..], token)?
+                }
+                (true, None) => {
+                    Self::sub_calculate(&rest_row[i + curr_group..], &rest_groups[1..], token)?
                 }
-                (true, None) => Self::sub_calculate(&rest_row[i + curr_group..], &rest_groups[1..]),
                 (false, _) | (true, Some(Damaged)) => 0,
             };
             if res != 0 {
@@ -82,7 +117,7 @@ impl Row {
                 break 'outer;
             }
         }
-        total
+        Ok(total)
     }
 
     fn count_def_blocks(springs: &[Spring]) -> usize {
@@ -92,7 +127,7 @@ impl Row {
             match spring {
                 Spring::Operational => in_block = false,
                 Spring::Damaged => {
-                    if in_block == false {
+                    if !in_block {
                         count += 1;
                     }
                     in_block = true
@@ -102,99 +137,103 @@ impl Row {
         }
         count
     }
-
-    fn add_string(curr: &str, curr_group: usize, last: bool) -> String {
-        let res = String::from(curr) + &"#".repeat(curr_group);
-        if last {
-            res
-        } else {
-            res + &"."
-        }
-    }
 }
 
-fn solve(lines: Lines) -> (usize, usize) {
-    lines
+fn parse_rows(input: &str) -> Result<Vec<Row>, ParseError> {
+    input
+        .lines()
         .map(|line| {
-            line.split_whitespace()
-                .tuples::<(_, _)>()
-                .next()
-                .expect("should be here")
+            let (row_val, groups_val) = line
+                .split_whitespace()
+                .collect_tuple()
+                .ok_or_else(|| ParseError::at(input, line, "`<springs> <groups>`"))?;
+            Ok(Row {
+                row: parse_row(input, row_val)?,
+                damage_groups: parse_groups(input, groups_val)?,
+            })
         })
-        .map(|(row_val, groups_val)| (parse_row(row_val), parse_groups(groups_val)))
-        .map(|(row, damage_groups)| Row { row, damage_groups })
-        .map(|row| {
-            println!("Starting row {:?}", row);
-            (
-                row.calculate_arrangements(),
-                row.calculate_arrangements_expanded(),
-            )
-        })
-        .reduce(|(p1, p2), (q1, q2)| (p1 + q1, p2 + q2))
-        .expect("should give result")
+        .collect()
 }
 
-fn parse_row(row: &str) -> Vec<Spring> {
-    row.chars()
-        .map(|char| match char {
-            '.' => Spring::Operational,
-            '#' => Damaged,
-            '?' => Unknown,
-            _ => unreachable!("no clue"),
+fn parse_row(input: &str, row: &str) -> Result<Vec<Spring>, ParseError> {
+    row.char_indices()
+        .map(|(idx, char)| match char {
+            '.' => Ok(Spring::Operational),
+            '#' => Ok(Damaged),
+            '?' => Ok(Unknown),
+            _ => Err(ParseError::at(input, &row[idx..], "`.`, `#` or `?`")),
         })
         .collect()
 }
 
-fn parse_groups(groups: &str) -> Vec<usize> {
+fn parse_groups(input: &str, groups: &str) -> Result<Vec<usize>, ParseError> {
     groups
         .split(',')
-        .map(|val| val.parse::<usize>().expect("not a number"))
+        .map(|val| parse::number(input, val))
         .collect()
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day12::solve;
+    use crate::cancel::CancellationToken;
+    use crate::day::{Answer, Day};
+    use crate::day12::Solution;
+
+    fn solve(input: &str) -> (usize, usize) {
+        let rows = Solution::parse(input).expect("should parse");
+        let p1 = Solution::part1(&rows).to_string().parse().unwrap();
+        let p2 = Solution::part2(&rows).to_string().parse().unwrap();
+        (p1, p2)
+    }
 
     #[test]
     fn test_0() {
-        let test = solve("?###? 3".lines());
+        let test = solve("?###? 3");
         assert_eq!((1, 1), test);
     }
 
     #[test]
     fn test_1() {
-        let test = solve("???.### 1,1,3".lines());
+        let test = solve("???.### 1,1,3");
         assert_eq!((1, 1), test);
     }
 
     #[test]
     fn test_2() {
-        let test = solve(".??..??...?##. 1,1,3".lines());
+        let test = solve(".??..??...?##. 1,1,3");
         assert_eq!((4, 16384), test);
     }
 
     #[test]
     fn test_3() {
-        let test = solve("?#?#?#?#?#?#?#? 1,3,1,6".lines());
+        let test = solve("?#?#?#?#?#?#?#? 1,3,1,6");
         assert_eq!((1, 1), test);
     }
 
     #[test]
     fn test_4() {
-        let test = solve("????.#...#... 4,1,1".lines());
+        let test = solve("????.#...#... 4,1,1");
         assert_eq!((1, 16), test);
     }
 
     #[test]
     fn test_5() {
-        let test = solve("????.######..#####. 1,6,5".lines());
+        let test = solve("????.######..#####. 1,6,5");
         assert_eq!((4, 2500), test);
     }
 
     #[test]
     fn test_6() {
-        let test = solve("?###???????? 3,2,1".lines());
+        let test = solve("?###???????? 3,2,1");
         assert_eq!((10, 506250), test);
     }
+
+    #[test]
+    fn test_cancelled() {
+        let rows = Solution::parse("?###???????? 3,2,1").expect("should parse");
+        let token = CancellationToken::new();
+        token.cancel();
+        assert_eq!(Answer::Unsolved, Solution::part2_cancellable(&rows, &token));
+        assert_eq!(Answer::from(10), Solution::part1(&rows));
+    }
 }
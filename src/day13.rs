@@ -1,37 +1,154 @@
-use std::fs;
+use std::collections::HashSet;
 use std::ops::BitXor;
 use std::str::Lines;
-use std::time::Instant;
-
-pub fn run() {
-    let file = fs::read_to_string("input/day13").expect("Could not open file.");
-    let now = Instant::now();
-    let (res_1, res_2) = solve(file.lines());
-    println!("Solutions took {} µs", now.elapsed().as_micros());
-    println!("Day 13 Solution Part 1: {}", res_1);
-    println!("Day 13 Solution Part 2: {}", res_2);
+
+use crate::day::{Answer, Day};
+use crate::fuzz::{Fuzz, Rng};
+use crate::parse::ParseError;
+
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 13;
+    type Input = Vec<Grid>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut lines = input.lines();
+        let mut grids = Vec::new();
+        while let Some(grid) = parse_grid(input, &mut lines)? {
+            grids.push(grid);
+        }
+        Ok(grids)
+    }
+
+    fn part1(grids: &Self::Input) -> Answer {
+        grids
+            .iter()
+            .map(|grid| {
+                determine_reflections(&grid.rows) * 100 + determine_reflections(&grid.columns)
+            })
+            .sum::<usize>()
+            .into()
+    }
+
+    fn part2(grids: &Self::Input) -> Answer {
+        grids
+            .iter()
+            .map(|grid| {
+                determine_smudged_reflection(&grid.rows) * 100
+                    + determine_smudged_reflection(&grid.columns)
+            })
+            .sum::<usize>()
+            .into()
+    }
 }
 
-struct Grid {
-    rows: Vec<usize>,
-    columns: Vec<usize>,
+impl Fuzz for Solution {
+    const PART: u8 = 2;
+
+    /// Patterns mirrored at a random line, then smudged at a single cell of the mirrored part.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let max_len = (size as u64 + 4).min(17);
+        let patterns: Vec<String> = (0..rng.range(1..size as u64 + 1))
+            .map(|_| {
+                let mut cells = Vec::new();
+                for _ in 0..20 {
+                    cells = smudged_pattern(rng, max_len);
+                    if smudged_reflections(&cells).len() == 1 {
+                        break;
+                    }
+                }
+                cells
+                    .iter()
+                    .map(|row| row.iter().map(|&ash| if ash { '#' } else { '.' }).collect())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            })
+            .collect();
+        patterns.join("\n\n") + "\n"
+    }
+
+    /// Tries every single smudge and keeps the reflection lines that were not there before.
+    fn reference(grids: &Vec<Grid>) -> Option<Answer> {
+        let mut total = 0;
+        for grid in grids {
+            let width = grid.columns.len();
+            let cells: Vec<Vec<bool>> = grid
+                .rows
+                .iter()
+                .map(|row| (0..width).rev().map(|bit| row >> bit & 1 == 1).collect())
+                .collect();
+            let lines = smudged_reflections(&cells);
+            if lines.len() != 1 {
+                return None;
+            }
+            total += lines.into_iter().sum::<usize>();
+        }
+        Some(total.into())
+    }
 }
 
-fn solve(mut lines: Lines) -> (usize, usize) {
-    let mut total = 0;
-    let mut smudged_total = 0;
-    while let Some(grid) = parse_grid(&mut lines) {
-        total += determine_reflections(&grid.rows) * 100;
-        total += determine_reflections(&grid.columns);
+/// A pattern with every row and column packed into bits, `#` being 1.
+pub struct Grid {
+    pub rows: Vec<usize>,
+    pub columns: Vec<usize>,
+}
+
+/// Mirrors a random pattern at a random row, breaks one mirrored cell and sometimes transposes it.
+fn smudged_pattern(rng: &mut Rng, max_len: u64) -> Vec<Vec<bool>> {
+    let height = rng.range(2..max_len + 1) as usize;
+    let width = rng.range(2..max_len + 1) as usize;
+    let mut cells: Vec<Vec<bool>> = (0..height)
+        .map(|_| (0..width).map(|_| rng.bool()).collect())
+        .collect();
+    let line = rng.range(1..height as u64) as usize;
+    let reach = line.min(height - line);
+    for offset in 0..reach {
+        cells[line + offset] = cells[line - 1 - offset].clone();
+    }
+    let row = line + rng.range(0..reach as u64) as usize;
+    let column = rng.range(0..width as u64) as usize;
+    cells[row][column] = !cells[row][column];
 
-        smudged_total += determine_smudged_reflection(&grid.rows) * 100;
-        smudged_total += determine_smudged_reflection(&grid.columns);
+    if rng.bool() {
+        cells = (0..width)
+            .map(|column| cells.iter().map(|row| row[column]).collect())
+            .collect();
     }
+    cells
+}
 
-    (total, smudged_total)
+/// All reflection lines, scored like the puzzle: rows above times 100 or columns to the left.
+fn reflections(cells: &[Vec<bool>]) -> HashSet<usize> {
+    let height = cells.len();
+    let width = cells[0].len();
+    let rows = (1..height).filter(|&line| {
+        (0..line.min(height - line)).all(|offset| cells[line - 1 - offset] == cells[line + offset])
+    });
+    let columns = (1..width).filter(|&line| {
+        cells.iter().all(|row| {
+            (0..line.min(width - line)).all(|offset| row[line - 1 - offset] == row[line + offset])
+        })
+    });
+    rows.map(|line| line * 100).chain(columns).collect()
 }
 
-fn determine_smudged_reflection(val: &[usize]) -> usize {
+fn smudged_reflections(cells: &[Vec<bool>]) -> HashSet<usize> {
+    let original = reflections(cells);
+    let mut found = HashSet::new();
+    let mut cells = cells.to_vec();
+    for row in 0..cells.len() {
+        for column in 0..cells[row].len() {
+            cells[row][column] = !cells[row][column];
+            found.extend(reflections(&cells).difference(&original));
+            cells[row][column] = !cells[row][column];
+        }
+    }
+    found
+}
+
+/// Like [`determine_reflections`], but for the line that needs exactly one bit flipped.
+pub fn determine_smudged_reflection(val: &[usize]) -> usize {
     for pivot in 1..val.len() {
         let steps = pivot.max(val.len() - pivot);
         let non_matching = (0..=steps)
@@ -65,7 +182,8 @@ fn bit_diff_one(a: &Option<&usize>, b: &Option<&usize>) -> bool {
     }
 }
 
-fn determine_reflections(val: &[usize]) -> usize {
+/// The number of rows (or columns) before the first line the pattern mirrors at, 0 if none.
+pub fn determine_reflections(val: &[usize]) -> usize {
     for pivot in 1..val.len() {
         let steps = pivot.max(val.len() - pivot);
         if (0..=steps).all(|idx| {
@@ -89,11 +207,11 @@ fn matches(a: &Option<&usize>, b: &Option<&usize>) -> bool {
     }
 }
 
-fn parse_grid(lines: &mut Lines) -> Option<Grid> {
+fn parse_grid(input: &str, lines: &mut Lines<'_>) -> Result<Option<Grid>, ParseError> {
     let mut rows = Vec::new();
     let mut columns = vec![0; 25];
     let mut col_num = 0;
-    while let Some(line) = lines.next() {
+    for line in lines.by_ref() {
         if line.is_empty() {
             break;
         }
@@ -103,12 +221,12 @@ fn parse_grid(lines: &mut Lines) -> Option<Grid> {
 
         for (y_pos, symbol) in line.char_indices() {
             let mut col_val = columns[y_pos];
-            col_val = col_val << 1;
-            row_val = row_val << 1;
+            col_val <<= 1;
+            row_val <<= 1;
             let symbol_value = match symbol {
                 '#' => 1,
                 '.' => 0,
-                _ => unreachable!("strange symbol"),
+                _ => return Err(ParseError::at(input, &line[y_pos..], "`#` or `.`")),
             };
             col_val += symbol_value;
             row_val += symbol_value;
@@ -119,10 +237,10 @@ fn parse_grid(lines: &mut Lines) -> Option<Grid> {
         rows.push(row_val);
     }
     columns.truncate(col_num);
-    if rows.len() == 0 {
-        None
+    if rows.is_empty() {
+        Ok(None)
     } else {
-        Some(Grid { rows, columns })
+        Ok(Some(Grid { rows, columns }))
     }
 }
 
@@ -135,7 +253,7 @@ fn test_1() {
 ..#.##.#.
 ..##..##.
 #.#.##.#."#;
-    assert_eq!((5, 300), solve(test.lines()));
+    assert_eq!((5, 300), solve(test));
 }
 
 #[test]
@@ -147,5 +265,13 @@ fn test_2() {
 #####.##.
 ..##..###
 #....#..#"#;
-    assert_eq!((400, 100), solve(test.lines()));
+    assert_eq!((400, 100), solve(test));
+}
+
+#[cfg(test)]
+fn solve(input: &str) -> (usize, usize) {
+    let grids = Solution::parse(input).expect("should parse");
+    let p1 = Solution::part1(&grids).to_string().parse().unwrap();
+    let p2 = Solution::part2(&grids).to_string().parse().unwrap();
+    (p1, p2)
 }
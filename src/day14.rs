@@ -1,175 +1,210 @@
 use std::collections::HashMap;
-use std::fs;
-use std::str::Lines;
-use std::time::Instant;
-
-use itertools::Itertools;
 
+use crate::day::{Answer, Day};
+use crate::debugger::{Simulate, Simulation};
+use crate::geometry::Direction::{self, East, North, South, West};
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::render::{Cell, Picture, Render, Style};
 use crate::day14::Rock::{Cube, Round};
 
-#[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Ord, Eq)]
-enum Rock {
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Hash)]
+pub enum Rock {
     Round,
     Cube,
 }
 
-#[derive(PartialEq, Clone)]
-struct MirrorMess {
-    width: usize,
-    height: usize,
-    grid: HashMap<(usize, usize), Rock>,
+#[derive(PartialEq, Eq, Hash, Clone)]
+/// The platform, with a rock or nothing on every tile.
+pub struct MirrorMess {
+    pub grid: Grid<Option<Rock>>,
 }
 
-#[derive(PartialOrd, PartialEq, Hash)]
-struct Comparable(Vec<usize>);
-
 impl MirrorMess {
-    fn to_comparable(&self) -> Vec<usize> {
-        let mut compare = Vec::with_capacity(3);
-        for ((x_val, y_val), rock) in self.grid.iter().sorted() {
-            compare.push(*x_val);
-            compare.push(*y_val);
-            match rock {
-                Round => compare.push(1),
-                Cube => compare.push(2),
-            }
-        }
-        compare
-    }
-
-    fn tilt_north(&self) -> MirrorMess {
-        let mut columns = vec![Vec::new(); self.width];
-        for entry in &self.grid {
-            columns[entry.0 .1].push(entry);
-        }
-
-        let mut tilted_grid = HashMap::new();
-        for mut col in columns {
-            col.sort_by_key(|((x_val, _y_val), _rock)| x_val);
-
+    /// Rolls every round rock as far north as it goes.
+    pub fn tilt_north(&self) -> MirrorMess {
+        let mut tilted_grid = Grid::new(self.grid.width(), self.grid.height(), None);
+        for (x_pos, column) in self.grid.columns().enumerate() {
             let mut curr_idx = 0;
-            for ((x_pos, y_pos), rock) in col {
-                use Rock::*;
+            for (y_pos, rock) in column.enumerate() {
                 match rock {
-                    Cube => {
-                        tilted_grid.insert((*x_pos, *y_pos), Cube);
-                        curr_idx = x_pos + 1;
+                    Some(Cube) => {
+                        tilted_grid[(x_pos, y_pos)] = Some(Cube);
+                        curr_idx = y_pos + 1;
                     }
-                    Round => {
-                        tilted_grid.insert((curr_idx, *y_pos), Round);
+                    Some(Round) => {
+                        tilted_grid[(x_pos, curr_idx)] = Some(Round);
                         curr_idx += 1;
                     }
+                    None => (),
                 }
             }
         }
 
+        MirrorMess { grid: tilted_grid }
+    }
+
+    fn rotate(&self) -> MirrorMess {
         MirrorMess {
-            width: self.width,
-            height: self.height,
-            grid: tilted_grid,
+            grid: self.grid.rotate_clockwise(),
         }
     }
+}
 
-    fn rotate(&self) -> MirrorMess {
-        let mut tilted = HashMap::new();
-        for ((x_val, y_val), rock) in &self.grid {
-            let x_tilt = *y_val;
-            let y_tilt = self.height - x_val - 1;
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 14;
+    type Input = MirrorMess;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_grid(input)
+    }
+
+    fn part1(mirror_mess: &Self::Input) -> Answer {
+        calculate_costs(&mirror_mess.tilt_north()).into()
+    }
+
+    fn part2(mirror_mess: &Self::Input) -> Answer {
+        solve_p2(mirror_mess).into()
+    }
+}
+
+impl Render for Solution {
+    /// The platform after part 1 tilted it north.
+    fn render(mirror_mess: &Self::Input) -> Vec<Picture> {
+        let tilted = mirror_mess.tilt_north();
+        let cells = tilted.grid.map(|rock| match rock {
+            Some(Round) => Cell::new('O', Style::Yellow),
+            Some(Cube) => Cell::new('#', Style::Blue),
+            None => Cell::new('.', Style::Dim),
+        });
+        vec![Picture {
+            title: format!(
+                "day14: tilted north, the load is {}",
+                calculate_costs(&tilted)
+            ),
+            cells,
+        }]
+    }
+}
+
+/// The platform during spin cycles, tilted one direction at a time.
+#[derive(Clone)]
+pub struct SpinCycle {
+    mirror_mess: MirrorMess,
+    tilts: usize,
+}
 
-            tilted.insert((x_tilt, y_tilt), *rock);
+impl SpinCycle {
+    /// The order of the tilts in a spin cycle, with the clockwise quarter turns that bring each
+    /// direction north.
+    const TILTS: [(Direction, usize); 4] = [(North, 0), (West, 1), (South, 2), (East, 3)];
+}
+
+impl Simulation for SpinCycle {
+    /// One tilt, in the order of the spin cycles.
+    fn step(&mut self) -> Option<String> {
+        let (direction, turns) = Self::TILTS[self.tilts % Self::TILTS.len()];
+        let mut turned = self.mirror_mess.clone();
+        for _ in 0..turns {
+            turned = turned.rotate();
+        }
+        let mut tilted = turned.tilt_north();
+        for _ in 0..(4 - turns) % 4 {
+            tilted = tilted.rotate();
         }
+        self.mirror_mess = tilted;
+        self.tilts += 1;
+        Some(format!(
+            "tilt {} ({direction:?}): the load is {}",
+            self.tilts,
+            calculate_costs(&self.mirror_mess)
+        ))
+    }
 
-        MirrorMess {
-            width: self.height,
-            height: self.width,
-            grid: tilted,
+    fn inspect(&self) -> String {
+        let mut platform = String::new();
+        for row in self.mirror_mess.grid.rows() {
+            platform.extend(row.iter().map(|rock| match rock {
+                Some(Round) => 'O',
+                Some(Cube) => '#',
+                None => '.',
+            }));
+            platform.push('\n');
         }
+        platform
     }
-}
 
-pub fn run() {
-    let file = fs::read_to_string("input/day14").expect("Could not open file.");
-    let now = Instant::now();
-    let (res_1, res_2) = solve(file.lines());
-    println!("Solutions took {} µs", now.elapsed().as_micros());
-    println!("Day 14 Solution Part 1: {}", res_1);
-    println!("Day 14 Solution Part 2: {}", res_2);
+    fn watches(&self) -> Vec<(&'static str, i64)> {
+        vec![
+            ("tilts", self.tilts as i64),
+            ("cycles", (self.tilts / Self::TILTS.len()) as i64),
+            ("load", calculate_costs(&self.mirror_mess) as i64),
+        ]
+    }
 }
 
-fn solve(lines: Lines) -> (usize, usize) {
-    let mirror_mess = parse_grid(lines);
+impl Simulate for Solution {
+    type Simulation = SpinCycle;
 
-    let tilted = mirror_mess.tilt_north();
+    fn simulation(mirror_mess: &Self::Input) -> SpinCycle {
+        SpinCycle {
+            mirror_mess: mirror_mess.clone(),
+            tilts: 0,
+        }
+    }
+}
 
+/// The load on the north beams after a billion spin cycles, found by detecting the cycle.
+pub fn solve_p2(mirror_mess: &MirrorMess) -> usize {
     let mut found_maps = HashMap::new();
-    let mut current_map = mirror_mess;
+    let mut current_map = mirror_mess.clone();
     let mut curr_cycle: usize = 1;
     let cycle_repeat;
     loop {
-        let current_compare = current_map.to_comparable();
-        if !found_maps.contains_key(&current_compare) {
-            let tilted = current_map.tilt_north();
-            let west = tilted.rotate();
-            let west_tilted = west.tilt_north();
-            let south = west_tilted.rotate();
-            let south_tilted = south.tilt_north();
-            let east = south_tilted.rotate();
-            let east_tilted = east.tilt_north();
-            current_map = east_tilted.rotate();
-            found_maps.insert(current_compare, (curr_cycle, current_map.clone()));
-            curr_cycle += 1;
-        } else {
-            cycle_repeat = found_maps.get(&current_compare).expect("wait, what?").0;
+        if let Some((found_cycle, _)) = found_maps.get(&current_map) {
+            cycle_repeat = *found_cycle;
             break;
         }
+        let tilted = current_map.tilt_north();
+        let west = tilted.rotate();
+        let west_tilted = west.tilt_north();
+        let south = west_tilted.rotate();
+        let south_tilted = south.tilt_north();
+        let east = south_tilted.rotate();
+        let east_tilted = east.tilt_north();
+        let previous_map = std::mem::replace(&mut current_map, east_tilted.rotate());
+        found_maps.insert(previous_map, (curr_cycle, current_map.clone()));
+        curr_cycle += 1;
     }
     let repeat_frequency = curr_cycle - cycle_repeat;
     let remaining = (1000000000 - cycle_repeat) % repeat_frequency;
-    let res_2 = found_maps
+    found_maps
         .values()
-        .filter_map(|(cycle_no, val)| {
-            (*cycle_no == cycle_repeat + remaining).then(|| calculate_costs(val))
-        })
-        .next()
-        .expect("no result :(");
-
-    (calculate_costs(&tilted), res_2)
+        .find(|(cycle_no, _)| *cycle_no == cycle_repeat + remaining)
+        .map(|(_, val)| calculate_costs(val))
+        .expect("no result :(")
 }
 
-fn calculate_costs(mirror_mess: &MirrorMess) -> usize {
+/// The total load on the north support beams.
+pub fn calculate_costs(mirror_mess: &MirrorMess) -> usize {
     mirror_mess
         .grid
         .iter()
-        .filter_map(|(coord, rock)| (*rock == Round).then(|| coord))
-        .map(|(x_val, _y_val)| mirror_mess.height - *x_val)
+        .filter(|(_coord, rock)| **rock == Some(Round))
+        .map(|((_x_val, y_val), _rock)| mirror_mess.grid.height() - y_val)
         .sum()
 }
 
-fn parse_grid(lines: Lines) -> MirrorMess {
-    use Rock::*;
-    let mut grid = HashMap::new();
-    let mut width = 0;
-    let mut height = 0;
-    for (x_pos, line) in lines.enumerate() {
-        width = line.len();
-        height += 1;
-        for (y_pos, char) in line.char_indices() {
-            match char {
-                'O' => {
-                    grid.insert((x_pos, y_pos), Round);
-                }
-                '#' => {
-                    grid.insert((x_pos, y_pos), Cube);
-                }
-                _ => (),
-            }
-        }
-    }
-    MirrorMess {
-        width,
-        height,
-        grid,
-    }
+fn parse_grid(input: &str) -> Result<MirrorMess, ParseError> {
+    let grid = Grid::parse(input, "`O`, `#` or `.`", |char| match char {
+        'O' => Some(Some(Round)),
+        '#' => Some(Some(Cube)),
+        '.' => Some(None),
+        _ => None,
+    })?;
+    Ok(MirrorMess { grid })
 }
 
 #[test]
@@ -185,6 +220,32 @@ O.#..O.#.#
 #....###..
 #OO..#...."#;
 
-    let test_res = solve(test_input.lines());
-    assert_eq!((136, 64), test_res);
+    let mirror_mess = Solution::parse(test_input).expect("should parse");
+    assert_eq!(Answer::from(136), Solution::part1(&mirror_mess));
+    assert_eq!(Answer::from(64), Solution::part2(&mirror_mess));
+}
+
+#[test]
+fn test_simulation() {
+    let input = std::fs::read_to_string("examples/day14/example1.txt").expect("fixture exists");
+    let mut spin_cycle = Solution::simulation(&Solution::parse(&input).expect("should parse"));
+    for _ in 0..4 {
+        spin_cycle.step().expect("spins forever");
+    }
+    // the platform after the first cycle, as the puzzle text shows it
+    assert_eq!(
+        ".....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....
+",
+        spin_cycle.inspect()
+    );
+    assert_eq!(vec![("tilts", 4), ("cycles", 1), ("load", 87)], spin_cycle.watches());
 }
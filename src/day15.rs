@@ -1,37 +1,65 @@
-use std::fs;
-use std::time::Instant;
+use crate::day::{Answer, Day};
+use crate::parse::{self, ParseError};
 
-pub fn run() {
-    let file = fs::read_to_string("input/day15").expect("Could not open file.");
-    let now = Instant::now();
-    let (res_1, res_2) = solve(file.lines().next().unwrap());
-    println!("Solutions took {} µs", now.elapsed().as_micros());
-    println!("Day 15 Solution Part 1: {}", res_1);
-    println!("Day 15 Solution Part 2: {}", res_2);
-}
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 15;
+    type Input = Vec<Step>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let line = input
+            .lines()
+            .next()
+            .ok_or_else(|| ParseError::at_end(input, "an initialization sequence"))?;
+        line.split(',')
+            .map(|raw| {
+                let (operation, bucket) = determine_operation(input, raw)?;
+                Ok(Step {
+                    raw: String::from(raw),
+                    operation,
+                    bucket,
+                })
+            })
+            .collect()
+    }
+
+    fn part1(steps: &Self::Input) -> Answer {
+        steps
+            .iter()
+            .map(|step| hash_val(&step.raw))
+            .sum::<usize>()
+            .into()
+    }
 
-fn solve(input: &str) -> (usize, usize) {
-    let res_1 = input.split(',').map(hash_val).sum();
+    fn part2(steps: &Self::Input) -> Answer {
+        focusing_power(steps).into()
+    }
+}
 
+/// Runs the steps on the 256 boxes and sums up the focusing power of all lenses left in them.
+pub fn focusing_power(steps: &[Step]) -> usize {
     let mut lens_boxes = vec![Vec::new(); 256];
-    for (ops, bucket) in input.split(',').map(determine_operation) {
-        use Operation::*;
-        match ops {
-            Remove(label) => remove_label(&label, &mut lens_boxes[bucket]),
-            Set(label, lens_value) => set(&label, lens_value, &mut lens_boxes[bucket]),
+        for Step {
+            operation, bucket, ..
+        } in steps
+        {
+            use Operation::*;
+            match operation {
+                Remove(label) => remove_label(label, &mut lens_boxes[*bucket]),
+                Set(label, lens_value) => set(label, *lens_value, &mut lens_boxes[*bucket]),
+            }
         }
-    }
 
-    let mut total = 0;
-    for box_no in 0..lens_boxes.len() {
-        let lenses = &lens_boxes[box_no];
-        for (pos, lens_val) in lenses.iter().map(|(_label, lens_val)| lens_val).enumerate() {
-            let focus_power = (box_no + 1) * (pos + 1) * lens_val;
-            total += focus_power;
+        let mut total = 0;
+        for (box_no, lenses) in lens_boxes.iter().enumerate() {
+            for (pos, lens_val) in lenses.iter().map(|(_label, lens_val)| lens_val).enumerate() {
+                let focus_power = (box_no + 1) * (pos + 1) * lens_val;
+                total += focus_power;
+            }
         }
-    }
 
-    (res_1, total)
+    total
 }
 
 fn set(label: &str, lens_val: usize, list: &mut Vec<(String, usize)>) {
@@ -58,31 +86,37 @@ fn remove_label(label: &str, list: &mut Vec<(String, usize)>) {
     }
 }
 
-enum Operation {
+/// A step of the initialization sequence, kept verbatim for the part 1 hash.
+pub struct Step {
+    pub raw: String,
+    pub operation: Operation,
+    /// The box the label hashes to.
+    pub bucket: usize,
+}
+
+pub enum Operation {
     Remove(String),
     Set(String, usize),
 }
 
-fn determine_operation(val: &str) -> (Operation, usize) {
+fn determine_operation(input: &str, val: &str) -> Result<(Operation, usize), ParseError> {
     let splits: Vec<_> = val.trim().split(&['=', '-'][..]).collect();
     if splits.len() == 1 || splits[1].is_empty() {
         let label = splits[0];
         let bucket = hash_val(label);
-        (Operation::Remove(String::from(label)), bucket)
+        Ok((Operation::Remove(String::from(label)), bucket))
     } else if splits.len() == 2 {
         let label = splits[0];
-        let lens_val = splits[1]
-            .parse::<usize>()
-            .expect("Could not parse lens val");
+        let lens_val = parse::number(input, splits[1])?;
         let bucket = hash_val(label);
-        (Operation::Set(String::from(label), lens_val), bucket)
+        Ok((Operation::Set(String::from(label), lens_val), bucket))
     } else {
-        println!("val: {val}");
-        unreachable!("uh?");
+        Err(ParseError::at(input, val, "`<label>=<focal length>` or `<label>-`"))
     }
 }
 
-fn hash_val(val: &str) -> usize {
+/// The HASH algorithm of the puzzle.
+pub fn hash_val(val: &str) -> usize {
     let mut curr_val = 0;
     for char in val.chars() {
         curr_val += char as usize;
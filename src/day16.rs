@@ -1,136 +1,153 @@
-use crate::day16::Direction::{Down, Left, Right, Up};
+use crate::day::{Answer, Day};
+use crate::debugger::{Simulate, Simulation};
+use crate::export::{Export, Image, Rgb};
+use crate::geometry::Direction::{self, East, North, South, West};
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::render::{Cell, Picture, Render, Style};
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::Read;
-use std::time::Instant;
-
-const MAP_WIDTH: usize = 111; // including newline
-const MAP_HEIGHT: usize = 110;
-const MIRROR_BS: u8 = '\\' as u8;
-const MIRROR_FS: u8 = '/' as u8;
-const SPLIT_HOR: u8 = '-' as u8;
-const SPLIT_VER: u8 = '|' as u8;
-const EMPTY: u8 = '.' as u8;
-
-pub fn run() {
-    let mut input = Vec::new();
-    let mut file = File::open("input/day16").expect("Could not open file.");
-    file.read_to_end(&mut input).expect("could not read");
-    let now = Instant::now();
-    let (res_1, res_2) = solve(&input);
-    println!("Solutions took {} µs", now.elapsed().as_micros());
-    println!("Day 16 Solution Part 1: {}", res_1);
-    println!("Day 16 Solution Part 2: {}", res_2);
-}
-
-#[derive(PartialEq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-fn solve(input: &[u8]) -> (usize, usize) {
-    let res_1 = start_beam(0, Right, input);
-    let res_2 = moar_energy(input);
-
-    (res_1, res_2)
-}
-
-fn moar_energy(map: &[u8]) -> usize {
-    let last_row = MAP_WIDTH * (MAP_HEIGHT - 1);
-    let mut max = 0;
-    for idx in 0..(MAP_WIDTH - 1) {
-        let res = start_beam(idx, Down, map);
-        max = max.max(res);
-        let res = start_beam(last_row + idx, Up, map);
-        max = max.max(res);
+use std::iter;
+use std::rc::Rc;
+
+const MIRROR_BS: u8 = b'\\';
+const MIRROR_FS: u8 = b'/';
+const SPLIT_HOR: u8 = b'-';
+const SPLIT_VER: u8 = b'|';
+const EMPTY: u8 = b'.';
+
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 16;
+    type Input = Contraption;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let tiles = [MIRROR_BS, MIRROR_FS, SPLIT_HOR, SPLIT_VER, EMPTY];
+        Grid::parse(input, "one of `.`, `/`, `\\`, `-` or `|`", |char| {
+            u8::try_from(char).ok().filter(|tile| tiles.contains(tile))
+        })
+    }
+
+    fn part1(map: &Self::Input) -> Answer {
+        start_beam((0, 0), East, map).into()
+    }
+
+    fn part2(map: &Self::Input) -> Answer {
+        moar_energy(map).into()
+    }
+}
+
+impl Render for Solution {
+    /// The beam of part 1 over the contraption; plain pictures use the puzzle's `#` and `.`.
+    fn render(map: &Contraption) -> Vec<Picture> {
+        let energized = energized_tiles((0, 0), East, map);
+        let cells = map
+            .iter()
+            .map(|(position, tile)| {
+                if energized.contains(&position) {
+                    Cell::new(*tile as char, Style::Yellow).or_plain('#')
+                } else {
+                    Cell::new(*tile as char, Style::Dim).or_plain('.')
+                }
+            })
+            .collect();
+        vec![Picture {
+            title: format!("day16: {} tiles energized from the top left", energized.len()),
+            cells: Grid::from_cells(map.width(), map.height(), cells),
+        }]
+    }
+}
+
+impl Export for Solution {
+    /// A heat map of how many of the beams of part 2 energize each tile.
+    fn export(map: &Contraption) -> Vec<Image> {
+        let coverage = coverage(map);
+        let most = coverage.cells().iter().copied().max().unwrap_or(0).max(1);
+        vec![Image {
+            name: String::from("day16-coverage"),
+            pixels: coverage.map(|beams| Rgb::heat(*beams as f64 / most as f64)),
+        }]
+    }
+}
+
+/// How many of the beams entering at an edge tile energize each tile.
+fn coverage(map: &Contraption) -> Grid<usize> {
+    let mut coverage = Grid::new(map.width(), map.height(), 0);
+    for (position, direction) in edge_beams(map) {
+        for tile in energized_tiles(position, direction, map) {
+            coverage[tile] += 1;
+        }
+    }
+    coverage
+}
+
+/// The tiles as raw bytes.
+pub type Contraption = Grid<u8>;
+
+/// The most tiles a beam entering from any edge tile can energize.
+pub fn moar_energy(map: &Contraption) -> usize {
+    edge_beams(map)
+        .into_iter()
+        .map(|(position, direction)| start_beam(position, direction, map))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Every beam that enters the contraption at an edge tile, heading inwards.
+fn edge_beams(map: &Contraption) -> Vec<((usize, usize), Direction)> {
+    let (last_column, last_row) = (map.width() - 1, map.height() - 1);
+    let mut beams = Vec::new();
+    for idx in 0..map.width() {
+        beams.push(((idx, 0), South));
+        beams.push(((idx, last_row), North));
     }
-    for idx in 0..MAP_HEIGHT {
-        let res = start_beam(idx * MAP_WIDTH, Right, map);
-        max = max.max(res);
-        let res = start_beam(idx * MAP_WIDTH + (MAP_WIDTH - 2), Left, map);
-        max = max.max(res);
+    for idx in 0..map.height() {
+        beams.push(((0, idx), East));
+        beams.push(((last_column, idx), West));
     }
-    max
+    beams
 }
 
-fn start_beam(position: usize, direction: Direction, map: &[u8]) -> usize {
+/// The number of tiles energized by a beam entering at `position` heading in `direction`.
+pub fn start_beam(position: (usize, usize), direction: Direction, map: &Contraption) -> usize {
+    energized_tiles(position, direction, map).len()
+}
+
+/// The tiles energized by a beam entering at `position` heading in `direction`.
+pub fn energized_tiles(
+    position: (usize, usize),
+    direction: Direction,
+    map: &Contraption,
+) -> HashSet<(usize, usize)> {
     let mut energized = HashSet::new();
 
     run_beam(position, direction, map, &mut energized);
-    energized.len()
+    energized
 }
 
-fn run_beam(position: usize, direction: Direction, map: &[u8], energized: &mut HashSet<usize>) {
+fn run_beam(
+    position: (usize, usize),
+    direction: Direction,
+    map: &Contraption,
+    energized: &mut HashSet<(usize, usize)>,
+) {
     let mut position = position;
     let mut direction = direction;
     loop {
-        let mut additional_direction = None;
-        let next_direction = match map[position] {
-            MIRROR_BS => {
-                energized.insert(position);
-                match direction {
-                    Up => Left,
-                    Down => Right,
-                    Left => Up,
-                    Right => Down,
-                }
-            }
-            MIRROR_FS => {
-                energized.insert(position);
-                match direction {
-                    Up => Right,
-                    Down => Left,
-                    Left => Down,
-                    Right => Up,
-                }
-            }
-            SPLIT_HOR => {
-                if energized.contains(&position) {
-                    return;
-                }
-                if direction == Up || direction == Down {
-                    energized.insert(position);
-                    additional_direction = Some(Left);
-                    Right
-                } else {
-                    energized.insert(position);
-                    direction
-                }
-            }
-            SPLIT_VER => {
-                if energized.contains(&position) {
-                    return;
-                }
-                if direction == Left || direction == Right {
-                    energized.insert(position);
-                    additional_direction = Some(Up);
-                    Down
-                } else {
-                    energized.insert(position);
-                    direction
-                }
-            }
-            EMPTY => {
-                energized.insert(position);
-                direction
-            }
-
-            _ => {
-                println!("position {}, char {}", position, map[position]);
-                unreachable!()
-            }
-        };
+        let tile = map[position];
+        if matches!(tile, SPLIT_HOR | SPLIT_VER) && energized.contains(&position) {
+            return;
+        }
+        energized.insert(position);
+        let (next_direction, additional_direction) = bounce(tile, direction);
 
         if let Some(split_beam) = additional_direction {
-            if let Some(next_pos) = step(position, &split_beam) {
+            if let Some(next_pos) = map.offset(position, split_beam.delta()) {
                 run_beam(next_pos, split_beam, map, energized);
             }
         }
 
-        if let Some(next_pos) = step(position, &next_direction) {
+        if let Some(next_pos) = map.offset(position, next_direction.delta()) {
             direction = next_direction;
             position = next_pos;
         } else {
@@ -139,36 +156,99 @@ fn run_beam(position: usize, direction: Direction, map: &[u8], energized: &mut H
     }
 }
 
-fn step(position: usize, direction: &Direction) -> Option<usize> {
-    match direction {
-        Up => {
-            if position < MAP_WIDTH {
-                None
-            } else {
-                Some(position - MAP_WIDTH)
-            }
+/// Where a beam heading in `direction` leaves `tile`, and where the second beam goes if it splits.
+fn bounce(tile: u8, direction: Direction) -> (Direction, Option<Direction>) {
+    match tile {
+        MIRROR_BS if direction.is_vertical() => (direction.turn_left(), None),
+        MIRROR_BS => (direction.turn_right(), None),
+        MIRROR_FS if direction.is_vertical() => (direction.turn_right(), None),
+        MIRROR_FS => (direction.turn_left(), None),
+        SPLIT_HOR if direction.is_vertical() => (East, Some(West)),
+        SPLIT_VER if direction.is_horizontal() => (South, Some(North)),
+        SPLIT_HOR | SPLIT_VER | EMPTY => (direction, None),
+        _ => unreachable!("`{}` is not a tile", tile as char),
+    }
+}
+
+/// The beam of part 1 moving one tile per step, with the beams it splits into side by side.
+#[derive(Clone)]
+pub struct BeamTracer {
+    map: Rc<Contraption>,
+    /// The tiles the beams are on and where they head, before the tiles turn them.
+    heads: Vec<((usize, usize), Direction)>,
+    seen: HashSet<((usize, usize), Direction)>,
+    energized: HashSet<(usize, usize)>,
+    steps: usize,
+}
+
+impl Simulation for BeamTracer {
+    /// Moves every beam one tile on; the simulation ends once all beams left or loop.
+    fn step(&mut self) -> Option<String> {
+        if self.heads.is_empty() {
+            return None;
         }
-        Down => {
-            let next_pos = position + MAP_WIDTH;
-            if next_pos >= MAP_WIDTH * MAP_HEIGHT {
-                None
-            } else {
-                Some(next_pos)
+        let mut heads = Vec::new();
+        for (position, direction) in std::mem::take(&mut self.heads) {
+            self.energized.insert(position);
+            let (next, split) = bounce(self.map[position], direction);
+            for direction in iter::once(next).chain(split) {
+                if let Some(next_position) = self.map.offset(position, direction.delta()) {
+                    if self.seen.insert((next_position, direction)) {
+                        heads.push((next_position, direction));
+                    }
+                }
             }
         }
-        Left => {
-            if position % MAP_WIDTH == 0 {
-                None
-            } else {
-                Some(position - 1)
+        self.heads = heads;
+        self.steps += 1;
+        Some(format!(
+            "step {}: {} beams, {} tiles energized",
+            self.steps,
+            self.heads.len(),
+            self.energized.len()
+        ))
+    }
+
+    /// The contraption with the beams as arrows and energized empty tiles as `#`.
+    fn inspect(&self) -> String {
+        let mut tiles = String::new();
+        for (position, tile) in self.map.iter() {
+            let head = self.heads.iter().find(|(head, _)| *head == position);
+            tiles.push(match head {
+                Some((_, North)) => '^',
+                Some((_, East)) => '>',
+                Some((_, South)) => 'v',
+                Some((_, _)) => '<',
+                None if *tile == EMPTY && self.energized.contains(&position) => '#',
+                None => *tile as char,
+            });
+            if position.0 == self.map.width() - 1 {
+                tiles.push('\n');
             }
         }
-        Right => {
-            if position % MAP_WIDTH == MAP_WIDTH - 2 {
-                None
-            } else {
-                Some(position + 1)
-            }
+        tiles
+    }
+
+    fn watches(&self) -> Vec<(&'static str, i64)> {
+        vec![
+            ("steps", self.steps as i64),
+            ("beams", self.heads.len() as i64),
+            ("energized", self.energized.len() as i64),
+        ]
+    }
+}
+
+impl Simulate for Solution {
+    type Simulation = BeamTracer;
+
+    fn simulation(map: &Self::Input) -> BeamTracer {
+        let start = ((0, 0), East);
+        BeamTracer {
+            map: Rc::new(map.clone()),
+            heads: vec![start],
+            seen: HashSet::from([start]),
+            energized: HashSet::new(),
+            steps: 0,
         }
     }
 }
@@ -185,6 +265,38 @@ fn test() {
 .-.-/..|..
 .|....-|.\
 ..//.|...."#;
-    let res = solve(input.as_bytes());
-    assert_eq!((46, 5), res);
+    let map = Solution::parse(input).expect("should parse");
+    let res = (Solution::part1(&map), Solution::part2(&map));
+    assert_eq!((Answer::from(46), Answer::from(51)), res);
+}
+
+#[test]
+fn test_export() {
+    let input = std::fs::read_to_string("examples/day16/example1.txt").expect("fixture exists");
+    let map = Solution::parse(&input).expect("should parse");
+    // 24 of the 40 edge beams cross the tile at the start of the eighth row, no tile sees more
+    let coverage = coverage(&map);
+    let hottest: Vec<_> = coverage.iter().filter(|(_, beams)| **beams == 24).collect();
+    assert_eq!(vec![((0, 7), &24)], hottest);
+    assert_eq!(Some(&24), coverage.cells().iter().max());
+
+    let images = Solution::export(&map);
+    assert_eq!(1, images.len());
+    assert_eq!("day16-coverage", images[0].name);
+    assert_eq!(Rgb::WHITE, images[0].pixels[(0, 7)]);
+    assert_eq!((10, 10), (images[0].pixels.width(), images[0].pixels.height()));
+}
+
+#[test]
+fn test_simulation() {
+    let input = std::fs::read_to_string("examples/day16/example1.txt").expect("fixture exists");
+    let map = Solution::parse(&input).expect("should parse");
+    let mut tracer = Solution::simulation(&map);
+    let mut events = Vec::new();
+    while let Some(event) = tracer.step() {
+        events.push(event);
+    }
+    assert_eq!(Some("step 1: 1 beams, 1 tiles energized"), events.first().map(String::as_str));
+    // the beams energize the same tiles as part 1, just one step at a time
+    assert_eq!(Some(&("energized", 46)), tracer.watches().last());
 }
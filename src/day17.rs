@@ -1,19 +1,11 @@
-use crate::day17::Direction::Left;
-use itertools::Itertools;
-use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashMap};
-use std::fs::File;
-use std::io::Read;
-use std::ops::{Range, RangeInclusive};
-use std::time::Instant;
-
-#[derive(PartialEq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use crate::day::{Answer, Day};
+use crate::export::{Export, Image, Rgb};
+use crate::geometry::Direction;
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::render::{Cell, Picture, Render, Style};
+use crate::search;
+use std::cmp::Ordering;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
 enum Face {
@@ -31,111 +23,141 @@ impl Face {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Path {
-    cost: usize,
-    target: usize,
-    facing: Face,
-}
+/// A block the crucible stops on, and the axis it moves along next.
+type Crucible = ((usize, usize), Face);
 
-#[derive(Debug)]
-struct InputMap<'a> {
-    values: &'a [u8],
-    width: usize,
-    height: usize,
-}
+pub struct Solution;
 
-impl Path {
-    fn new(cost: usize, target: usize, facing: Face) -> Self {
-        Path {
-            cost,
-            target,
-            facing,
-        }
+impl Day for Solution {
+    const DAY: u8 = 17;
+    type Input = Grid<u8>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Grid::parse(input, "a heat loss digit", |val| {
+            val.to_digit(10).filter(|digit| *digit > 0).map(|digit| digit as u8)
+        })
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input, 1, 3).map_or(Answer::Unsolved, Answer::from)
     }
-}
 
-pub fn run() {
-    let mut input = Vec::new();
-    let mut file = File::open("input/day17").expect("Could not open file.");
-    file.read_to_end(&mut input).expect("could not read");
-    let now = Instant::now();
-    let (res_1, res_2) = solve(&input);
-    println!("Solutions took {} ms", now.elapsed().as_millis());
-    println!("Day 17 Solution Part 1: {}", res_1);
-    println!("Day 17 Solution Part 2: {}", res_2);
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p1(input, 4, 10).map_or(Answer::Unsolved, Answer::from)
+    }
 }
 
-fn solve(input: &[u8]) -> (usize, usize) {
-    let p1 = solve_p1(input, 1, 3);
-    let p2 = solve_p1(input, 4, 10);
-    (p1, p2)
+/// The least heat loss from the top left to the bottom right block of the map for a crucible that
+/// moves between `range_start` and `range_end_incl` blocks before it turns; None if it cannot get
+/// there.
+pub fn solve_p1(map: &Grid<u8>, range_start: usize, range_end_incl: usize) -> Option<usize> {
+    cheapest_route(map, range_start, range_end_incl).map(|(heat_loss, _)| heat_loss)
 }
 
-fn solve_p1(input: &[u8], range_start: usize, range_end_incl: usize) -> usize {
-    let mut map = initialize_input_map(input);
-    let mut paths_available = BinaryHeap::new();
-    for path in next_targets(0, Face::Horizontal, 0, range_start, range_end_incl, &map) {
-        paths_available.push(Reverse(path));
+/// The least heat loss like [`solve_p1`], and every block the crucible passes on the way there,
+/// starting with the top left one.
+pub fn cheapest_route(
+    map: &Grid<u8>,
+    range_start: usize,
+    range_end_incl: usize,
+) -> Option<(usize, Vec<(usize, usize)>)> {
+    let goal = (map.width() - 1, map.height() - 1);
+    let starts = [((0, 0), Face::Horizontal), ((0, 0), Face::Vertical)];
+    let found = search::dijkstra(
+        starts,
+        |(position, facing)| next_targets(*position, *facing, range_start, range_end_incl, map),
+        |(position, _)| *position == goal,
+    )?;
+
+    let mut blocks = vec![(0, 0)];
+    for (to, _) in found.states.iter().skip(1) {
+        while blocks.last() != Some(to) {
+            let (x, y) = *blocks.last().expect("starts with the top left block");
+            blocks.push((step_towards(x, to.0), step_towards(y, to.1)));
+        }
     }
-    for path in next_targets(0, Face::Vertical, 0, range_start, range_end_incl, &map) {
-        paths_available.push(Reverse(path));
+    Some((found.cost, blocks))
+}
+
+impl Render for Solution {
+    /// The routes of both crucibles, drawn with arrows where colours are not available.
+    fn render(map: &Self::Input) -> Vec<Picture> {
+        [("crucible", 1, 3), ("ultra crucible", 4, 10)]
+            .into_iter()
+            .filter_map(|(name, range_start, range_end_incl)| {
+                let (heat_loss, blocks) = cheapest_route(map, range_start, range_end_incl)?;
+                let mut cells = map.map(|loss| Cell::new(char::from(b'0' + loss), Style::Dim));
+                for pair in blocks.windows(2) {
+                    let ((from_x, from_y), to) = (pair[0], pair[1]);
+                    let arrow = match (to.0.cmp(&from_x), to.1.cmp(&from_y)) {
+                        (Ordering::Greater, _) => '>',
+                        (Ordering::Less, _) => '<',
+                        (_, Ordering::Greater) => 'v',
+                        _ => '^',
+                    };
+                    cells[to] = Cell::new(cells[to].glyph, Style::Red).or_plain(arrow);
+                }
+                Some(Picture {
+                    title: format!("day17: {name} route losing {heat_loss} heat"),
+                    cells,
+                })
+            })
+            .collect()
     }
+}
 
-    let mut curr_found = HashMap::new();
-
-    while let Some(Reverse(path)) = paths_available.pop() {
-        if let Some(_) = curr_found.get(&(path.target, path.facing)) {
-            continue;
-        } else {
-            if path.target == (map.width * map.height) - 2 {
-                return path.cost;
-            }
-            curr_found.insert((path.target, path.facing), path.cost);
-            let next_targets = next_targets(
-                path.target,
-                path.facing,
-                path.cost,
-                range_start,
-                range_end_incl,
-                &map,
-            );
-            for next in next_targets {
-                if !curr_found.contains_key(&(next.target, next.facing)) {
-                    paths_available.push(Reverse(next));
+impl Export for Solution {
+    /// The routes of both crucibles in red over the map, darker blocks losing more heat.
+    fn export(map: &Self::Input) -> Vec<Image> {
+        [("crucible", 1, 3), ("ultra-crucible", 4, 10)]
+            .into_iter()
+            .filter_map(|(name, range_start, range_end_incl)| {
+                let (_, blocks) = cheapest_route(map, range_start, range_end_incl)?;
+                let mut pixels = map.map(|loss| Rgb::grey(1.0 - f64::from(*loss) / 10.0));
+                for block in blocks {
+                    pixels[block] = Rgb::RED;
                 }
-            }
-        }
+                Some(Image {
+                    name: format!("day17-{name}"),
+                    pixels,
+                })
+            })
+            .collect()
     }
+}
 
-    panic!("no result found;")
+fn step_towards(from: usize, to: usize) -> usize {
+    match from.cmp(&to) {
+        Ordering::Less => from + 1,
+        Ordering::Equal => from,
+        Ordering::Greater => from - 1,
+    }
 }
 
+/// The blocks the crucible can turn on next, with the heat lost on the way there.
 fn next_targets(
-    curr_pos: usize,
+    curr_pos: (usize, usize),
     facing: Face,
-    cost: usize,
     range_start: usize,
     range_end_incl: usize,
-    map: &InputMap,
-) -> Vec<Path> {
+    map: &Grid<u8>,
+) -> Vec<(Crucible, usize)> {
     use Direction::*;
     let directions = match facing {
-        Face::Vertical => [Up, Down],
-        Face::Horizontal => [Left, Right],
+        Face::Vertical => [North, South],
+        Face::Horizontal => [West, East],
     };
 
     let mut next_target = Vec::new();
     for direction in directions {
-        let mut path_cost = cost;
+        let mut path_cost = 0;
         let mut mv_pos = curr_pos;
         for idx in 1..=range_end_incl {
-            if let Some(pos) = step(mv_pos, &direction, map.width, map.height) {
+            if let Some(pos) = map.offset(mv_pos, direction.delta()) {
                 mv_pos = pos;
-                path_cost += (map.values[mv_pos] - '0' as u8) as usize;
+                path_cost += map[mv_pos] as usize;
                 if idx >= range_start {
-                    let new_path = Path::new(path_cost, mv_pos, facing.other());
-                    next_target.push(new_path);
+                    next_target.push(((mv_pos, facing.other()), path_cost));
                 }
             }
         }
@@ -144,67 +166,6 @@ fn next_targets(
     next_target
 }
 
-struct Dimensions {
-    width: usize,
-    height: usize,
-}
-fn initialize_input_map(input: &[u8]) -> InputMap {
-    let width = input
-        .iter()
-        .enumerate()
-        .find_or_first(|(_, val)| **val as char == '\n');
-
-    let width = width
-        .expect("Could not determine width: no linebreak found")
-        .0
-        + 1;
-
-    let height = (input.len() + 1) / width;
-
-    InputMap {
-        values: input,
-        width,
-        height,
-    }
-}
-
-fn step(position: usize, direction: &Direction, width: usize, height: usize) -> Option<usize> {
-    use Direction::*;
-    match direction {
-        Up => {
-            if position < width {
-                None
-            } else {
-                Some(position - width)
-            }
-        }
-        Down => {
-            let next_pos = position + width;
-            if next_pos >= width * height {
-                None
-            } else {
-                Some(next_pos)
-            }
-        }
-        Left => {
-            if position % width == 0 {
-                None
-            } else {
-                Some(position - 1)
-            }
-        }
-        Right => {
-            if position % width == width - 2 {
-                None
-            } else if position % width == width - 1 {
-                unreachable!("this is not allowed, this is position contains a backslash");
-            } else {
-                Some(position + 1)
-            }
-        }
-    }
-}
-
 #[test]
 fn test() {
     let input = r#"2413432311323
@@ -220,25 +181,48 @@ fn test() {
 1224686865563
 2546548887735
 4322674655533"#;
-    let res = solve(input.as_bytes());
-    assert_eq!((102, 94), res);
+    let input = Solution::parse(input).expect("should parse");
+    let res = (Solution::part1(&input), Solution::part2(&input));
+    assert_eq!((Answer::from(102), Answer::from(94)), res);
 }
 
 #[test]
 fn test2() {
     let input = r#"1111999999
 2222999999"#;
-    let res = solve_p1(input.as_bytes(), 1, 3);
-    assert_eq!(70, res);
+    let map = Solution::parse(input).expect("should parse");
+    let res = solve_p1(&map, 1, 3);
+    assert_eq!(Some(70), res);
+}
+
+#[test]
+fn test_unreachable() {
+    let map = Solution::parse("19").expect("should parse");
+    assert_eq!(Answer::from(9), Solution::part1(&map));
+    assert_eq!(Answer::Unsolved, Solution::part2(&map));
 }
 
 #[test]
 fn test_step_function() {
     use Direction::*;
-    // assert_eq!(None, step(0, &Left, 5, 5));
-    // assert_eq!(None, step(4, &Right, 5, 5));
-    // assert_eq!(Some(4), step(3, &Right, 5, 1));
-    assert_eq!(None, step(26, &Right, 14, 15));
-    assert_eq!(Some(180), step(179, &Right, 14, 15));
-    assert_eq!(Some(180), step(166, &Down, 14, 15));
+    let map = Grid::new(13, 15, 1);
+    assert_eq!(None, map.offset((0, 0), West.delta()));
+    assert_eq!(None, map.offset((12, 1), East.delta()));
+    assert_eq!(Some((12, 12)), map.offset((11, 12), East.delta()));
+    assert_eq!(Some((12, 12)), map.offset((12, 11), South.delta()));
+    assert_eq!(None, map.offset((12, 14), South.delta()));
+}
+
+#[test]
+fn test_route() {
+    let input = r#"1111999999
+2222999999"#;
+    let map = Solution::parse(input).expect("should parse");
+    let (heat_loss, blocks) = cheapest_route(&map, 1, 3).expect("should find a route");
+    assert_eq!(70, heat_loss);
+    assert_eq!(Some(&(0, 0)), blocks.first());
+    assert_eq!(Some(&(9, 1)), blocks.last());
+    assert!(blocks.windows(2).all(|pair| map.neighbours(pair[0]).any(|next| next == pair[1])));
+    let lost: usize = blocks.iter().skip(1).map(|block| map[*block] as usize).sum();
+    assert_eq!(heat_loss, lost);
 }
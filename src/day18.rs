@@ -1,77 +1,89 @@
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet};
-use std::fs;
 use std::str::FromStr;
-use std::time::Instant;
 
-use ascii::AsAsciiStr;
 use itertools::{chain, Itertools};
-use parse_display::{Display, FromStr};
-
-#[derive(Display, FromStr, PartialEq, Debug)]
-#[display("{direction} {value} ({color_code}")]
-struct DigInstruction {
-    direction: Direction,
-    value: usize,
-    color_code: LongInstruction,
+
+use crate::day::{Answer, Day};
+use crate::export::{Export, Image, Rgb};
+use crate::fuzz::{Fuzz, Rng};
+use crate::geometry::Direction::{self, East, North, South, West};
+use crate::geometry::{BoundingBox, Point2};
+use crate::grid::Grid;
+use crate::parse::ParseError;
+
+/// The directions in the order of their digit in the colour, each with its letter in the plan.
+const DIRECTIONS: [(Direction, &str); 4] = [(East, "R"), (South, "D"), (West, "L"), (North, "U")];
+
+#[derive(PartialEq, Debug)]
+/// A line of the dig plan: the short instruction of part 1 and the one hidden in the colour.
+pub struct DigInstruction {
+    pub direction: Direction,
+    pub value: usize,
+    pub color_code: LongInstruction,
+}
+
+impl FromStr for DigInstruction {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (letter, rest) = input.split_once(' ').ok_or(())?;
+        let (value, color_code) = rest.split_once(" (").ok_or(())?;
+        let (direction, _) = DIRECTIONS
+            .iter()
+            .find(|(_, known)| *known == letter)
+            .ok_or(())?;
+        Ok(DigInstruction {
+            direction: *direction,
+            value: value.parse().map_err(|_| ())?,
+            color_code: color_code.parse()?,
+        })
+    }
 }
 
-#[derive(Display, FromStr, PartialEq, Debug)]
-#[display("{}")]
-enum Direction {
-    #[display("R")]
-    Right,
-    #[display("L")]
-    Left,
-    #[display("U")]
-    Up,
-    #[display("D")]
-    Down,
+#[derive(PartialEq, Debug)]
+pub struct LongInstruction {
+    pub direction: Direction,
+    pub value: i32,
 }
 
-#[derive(PartialEq, Debug, Display)]
-#[display("{direction}{value}")]
-struct LongInstruction {
-    direction: Direction,
-    value: i32,
+impl LongInstruction {
+    /// The colour this instruction was hidden in, as `0xRRGGBB`.
+    pub fn color(&self) -> u32 {
+        let code = DIRECTIONS
+            .iter()
+            .position(|(direction, _)| *direction == self.direction)
+            .expect("the plan only goes in cardinal directions");
+        ((self.value as u32) << 4) | code as u32
+    }
 }
 
 impl FromStr for LongInstruction {
     type Err = ();
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let value = i32::from_str_radix(&input[1..6], 16).expect(&format!(
-            "Could not parse number {:?}",
-            input[1..6].as_ascii_str()
-        ));
-        let direction = match input.chars().nth(6).expect("meh") {
-            '0' => Direction::Right,
-            '1' => Direction::Down,
-            '2' => Direction::Left,
-            '3' => Direction::Up,
-            _ => unreachable!("apparently did not parse direction"),
-        };
-        Ok(LongInstruction { direction, value })
+        let hex = input
+            .strip_prefix('#')
+            .and_then(|hex| hex.strip_suffix(')'))
+            .filter(|hex| hex.len() == 6 && hex.is_ascii())
+            .ok_or(())?;
+        let value = i32::from_str_radix(&hex[..5], 16).map_err(|_| ())?;
+        let code = hex[5..].parse::<usize>().map_err(|_| ())?;
+        let (direction, _) = DIRECTIONS.get(code).ok_or(())?;
+        Ok(LongInstruction {
+            direction: *direction,
+            value,
+        })
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 struct Edge {
     height: i32,
     start_idx: i32,
     end_idx: i32,
 }
 
-impl Eq for Edge {}
-
-impl PartialEq<Self> for Edge {
-    fn eq(&self, other: &Self) -> bool {
-        self.height == other.height
-            && self.start_idx == other.start_idx
-            && self.end_idx == other.end_idx
-    }
-}
-
 impl PartialOrd<Self> for Edge {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -95,11 +107,11 @@ impl Ord for Edge {
 }
 
 impl Edge {
-    fn new_horizontal(a: (i32, i32), b: (i32, i32)) -> Self {
-        assert_eq!(a.0, b.0, "wtfx??");
-        let height = a.0;
-        let start_idx = a.1.min(b.1);
-        let end_idx = a.1.max(b.1);
+    fn new_horizontal(a: Point2<i32>, b: Point2<i32>) -> Self {
+        assert_eq!(a.y, b.y, "wtfx??");
+        let height = a.y;
+        let start_idx = a.x.min(b.x);
+        let end_idx = a.x.max(b.x);
         Edge {
             height,
             start_idx,
@@ -107,11 +119,11 @@ impl Edge {
         }
     }
 
-    fn new_vertical(a: (i32, i32), b: (i32, i32)) -> Self {
-        assert_eq!(a.1, b.1, "wtfxx??");
-        let height = a.1;
-        let start_idx = a.0.min(b.0);
-        let end_idx = a.0.max(b.0);
+    fn new_vertical(a: Point2<i32>, b: Point2<i32>) -> Self {
+        assert_eq!(a.x, b.x, "wtfxx??");
+        let height = a.x;
+        let start_idx = a.y.min(b.y);
+        let end_idx = a.y.max(b.y);
         Edge {
             height,
             start_idx,
@@ -132,37 +144,249 @@ impl Edge {
     }
 }
 
-pub fn run() {
-    let file = fs::read_to_string("input/day18").expect("Could not open file.");
-    let dig_instructions: Vec<DigInstruction> = file
-        .lines()
-        .map(|str| str.parse::<DigInstruction>().expect("could not parse line"))
-        .collect();
-    let now = Instant::now();
-    let (res_1, res_2) = (solve(&dig_instructions), solve_p2(&dig_instructions));
-    println!("Solutions took {} µs", now.elapsed().as_micros());
-    println!("Day 18 Solution Part 1: {}", res_1);
-    println!("Day 18 Solution Part 2: {}", res_2);
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 18;
+    type Input = Vec<DigInstruction>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let instructions: Vec<DigInstruction> = input
+            .lines()
+            .map(|line| {
+                line.parse::<DigInstruction>()
+                    .map_err(|_| ParseError::at(input, line, "`<U|D|L|R> <meters> (#<hex color>)`"))
+            })
+            .collect::<Result<_, _>>()?;
+        // both parts follow the trench around the lagoon, which has to end where it started
+        let short = instructions
+            .iter()
+            .map(|instr| (instr.direction, i32::try_from(instr.value).ok()));
+        let long = instructions
+            .iter()
+            .map(|instr| (instr.color_code.direction, Some(instr.color_code.value)));
+        for (part, moves) in [(1, short.collect_vec()), (2, long.collect_vec())] {
+            match trench_end(&moves) {
+                Some(end) if end == Point2::new(0, 0) => (),
+                Some(end) => {
+                    return Err(ParseError::at_end(
+                        input,
+                        format!("a trench back to its start, the one of part {part} ends at {end}"),
+                    ))
+                }
+                None => {
+                    return Err(ParseError::at_end(
+                        input,
+                        format!("a trench within i32 coordinates in part {part}"),
+                    ))
+                }
+            }
+        }
+        Ok(instructions)
+    }
+
+    fn part1(dig_instructions: &Self::Input) -> Answer {
+        solve(dig_instructions).into()
+    }
+
+    fn part2(dig_instructions: &Self::Input) -> Answer {
+        solve_p2(dig_instructions).into()
+    }
+}
+
+impl Fuzz for Solution {
+    const PART: u8 = 2;
+
+    /// A polygon that is monotone along one axis: a strip of columns, each spanning some rows and
+    /// overlapping its neighbours. Both parts dig the same trench.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut spans = vec![{
+            let top = rng.range(0..10) as i32;
+            (top, top + rng.range(1..size as u64 + 2) as i32)
+        }];
+        for _ in 1..rng.range(1..size as u64 + 1) {
+            let &(top, bottom) = spans.last().expect("starts with a column");
+            let next_top = rng.range(0..(bottom - top + 5) as u64) as i32 + top - 5;
+            let next_top = next_top.min(bottom - 1);
+            let floor = top.max(next_top);
+            spans.push((next_top, floor + rng.range(1..size as u64 + 2) as i32));
+        }
+
+        // (direction, meters) along the top from left to right and back along the bottom
+        let mut moves = Vec::new();
+        let widths: Vec<i32> = spans.iter().map(|_| rng.range(1..4) as i32).collect();
+        for (idx, (top, _)) in spans.iter().enumerate() {
+            if idx > 0 {
+                moves.push((South, top - spans[idx - 1].0));
+            }
+            moves.push((East, widths[idx]));
+        }
+        let last = spans.last().expect("starts with a column");
+        moves.push((South, last.1 - last.0));
+        for (idx, (_, bottom)) in spans.iter().enumerate().rev() {
+            moves.push((West, widths[idx]));
+            if idx > 0 {
+                moves.push((North, bottom - spans[idx - 1].1));
+            }
+        }
+        moves.push((North, spans[0].1 - spans[0].0));
+
+        let transpose = rng.bool();
+        let mut merged: Vec<(Direction, i32)> = Vec::new();
+        for (mut direction, mut meters) in moves {
+            if meters < 0 {
+                (direction, meters) = (direction.reverse(), -meters);
+            }
+            if transpose {
+                direction = match direction {
+                    East => South,
+                    South => East,
+                    West => North,
+                    North => West,
+                    diagonal => diagonal,
+                };
+            }
+            match merged.last_mut() {
+                _ if meters == 0 => (),
+                Some((last, last_meters)) if *last == direction => *last_meters += meters,
+                _ => merged.push((direction, meters)),
+            }
+        }
+        if rng.bool() {
+            merged.reverse();
+            merged
+                .iter_mut()
+                .for_each(|(direction, _)| *direction = direction.reverse());
+        }
+        let start = rng.range(0..merged.len() as u64) as usize;
+        merged.rotate_left(start);
+
+        merged
+            .iter()
+            .map(|(direction, meters)| {
+                let code = DIRECTIONS
+                    .iter()
+                    .position(|(known, _)| known == direction)
+                    .expect("the plan only goes in cardinal directions");
+                let letter = DIRECTIONS[code].1;
+                format!("{letter} {meters} (#{meters:05x}{code})\n")
+            })
+            .collect()
+    }
+
+    /// Digs the trench cell by cell and counts everything that cannot be reached from outside.
+    fn reference(instructions: &Vec<DigInstruction>) -> Option<Answer> {
+        let origin = Point2::new(0, 0);
+        let mut trench = HashSet::new();
+        let mut pos = origin;
+        let mut previous: Option<Direction> = None;
+        for LongInstruction { direction, value } in instructions.iter().map(|i| &i.color_code) {
+            // the trench turns at every corner and never touches itself
+            if *value <= 0
+                || *value > 1000
+                || previous.is_some_and(|p| p.is_vertical() == direction.is_vertical())
+            {
+                return None;
+            }
+            previous = Some(*direction);
+            for _ in 0..*value {
+                pos = pos.step(*direction, 1)?;
+                if !trench.insert(pos) && pos != origin {
+                    return None;
+                }
+            }
+        }
+        let first = instructions.first().map(|i| i.color_code.direction);
+        if pos != origin
+            || first
+                .zip(previous)
+                .is_some_and(|(a, b)| a.is_vertical() == b.is_vertical())
+        {
+            return None;
+        }
+
+        let bounds = BoundingBox::of(trench.iter().copied())?.expand(1);
+        let outside = outside(&bounds, |pos| trench.contains(pos));
+        let area = (bounds.width() * bounds.height()) as usize;
+        Some((area - outside.len()).into())
+    }
+}
+
+impl Export for Solution {
+    /// The trench of part 1 in the colours of its instructions, around the lagoon in grey.
+    fn export(instructions: &Self::Input) -> Vec<Image> {
+        let mut trench = HashMap::new();
+        let mut pos = Point2::new(0, 0);
+        for instr in instructions {
+            for _ in 0..instr.value {
+                pos = pos
+                    .step(instr.direction, 1)
+                    .expect("the lagoon fits into i32");
+                trench.insert(pos, Rgb::from_hex(instr.color_code.color()));
+            }
+        }
+        let Some(bounds) = BoundingBox::of(trench.keys().copied()) else {
+            return Vec::new();
+        };
+        let bounds = bounds.expand(1);
+        let outside = outside(&bounds, |pos| trench.contains_key(pos));
+        let pixels = bounds
+            .points()
+            .map(|point| match trench.get(&point) {
+                Some(color) => *color,
+                None if outside.contains(&point) => Rgb::WHITE,
+                None => Rgb::grey(0.8),
+            })
+            .collect();
+        vec![Image {
+            name: String::from("day18-lagoon"),
+            pixels: Grid::from_cells(bounds.width() as usize, bounds.height() as usize, pixels),
+        }]
+    }
 }
 
-fn solve_p2(instructions: &[DigInstruction]) -> usize {
+/// Flood fills `bounds` from its top left corner, which has to lie outside the trench.
+fn outside(
+    bounds: &BoundingBox<i32>,
+    is_trench: impl Fn(&Point2<i32>) -> bool,
+) -> HashSet<Point2<i32>> {
+    let mut outside = HashSet::from([bounds.min]);
+    let mut todo = vec![bounds.min];
+    while let Some(pos) = todo.pop() {
+        for next in Direction::CARDINAL.iter().filter_map(|d| pos.step(*d, 1)) {
+            if bounds.contains(next) && !is_trench(&next) && outside.insert(next) {
+                todo.push(next);
+            }
+        }
+    }
+    outside
+}
+
+/// Where the trench dug by `moves` from the origin ends, `None` if it leaves the range of i32.
+fn trench_end(moves: &[(Direction, Option<i32>)]) -> Option<Point2<i32>> {
+    moves
+        .iter()
+        .try_fold(Point2::new(0, 0), |pos, (direction, meters)| {
+            pos.step(*direction, (*meters)?)
+        })
+}
+
+/// The lagoon size for the instructions hidden in the colours, sweeping the edges instead of
+/// digging every cube.
+pub fn solve_p2(instructions: &[DigInstruction]) -> usize {
     let mut horizontal_edges: BinaryHeap<Edge> = BinaryHeap::new();
     let mut vertical_edges = HashSet::new();
-    let mut curr_pos: (i32, i32) = (0, 0);
+    let mut curr_pos: Point2<i32> = Point2::new(0, 0);
     for instr in instructions {
         let LongInstruction { direction, value } = &instr.color_code;
-        let next_pos = match direction {
-            Direction::Up => (curr_pos.0 - value, curr_pos.1),
-            Direction::Down => (curr_pos.0 + value, curr_pos.1),
-            Direction::Right => (curr_pos.0, curr_pos.1 + value),
-            Direction::Left => (curr_pos.0, curr_pos.1 - value),
-        };
+        let next_pos = curr_pos
+            .step(*direction, *value)
+            .expect("the lagoon fits into i32");
 
-        match direction {
-            Direction::Up | Direction::Down => {
-                vertical_edges.insert(Edge::new_vertical(curr_pos, next_pos));
-            }
-            _ => horizontal_edges.push(Edge::new_horizontal(curr_pos, next_pos)),
+        if direction.is_vertical() {
+            vertical_edges.insert(Edge::new_vertical(curr_pos, next_pos));
+        } else {
+            horizontal_edges.push(Edge::new_horizontal(curr_pos, next_pos));
         }
         curr_pos = next_pos
     }
@@ -302,17 +526,14 @@ fn find_next_relevant_horizontal(
 // }
 
 fn find_and_remove(height: i32, idx: i32, edges: &mut HashSet<Edge>) -> Edge {
-    if let Some(edge) = find(height, idx, &edges) {
+    if let Some(edge) = find(height, idx, edges) {
         let edge = *edge;
         edges.remove(&edge);
         edge
     } else {
-        println!(
-            "Did not found anything for {height}, {idx}, {}",
-            edges.len()
-        );
+        crate::debug!("found nothing for {height}, {idx}, {}", edges.len());
         for edge in edges.iter() {
-            println!("having edge {:?}", edge);
+            crate::debug!("having edge {edge:?}");
         }
         panic!("meh.");
     }
@@ -333,48 +554,45 @@ fn find_vert_edges(hor_edges: &[Edge], vertical_edges: &HashSet<Edge>) -> Vec<Ed
             .iter()
             .filter_map(|hor| find(hor.height, hor.end_idx, vertical_edges)),
     )
-    .map(|val| *val)
+    .copied()
     .collect()
 }
 
-fn solve(instructions: &[DigInstruction]) -> usize {
+/// The lagoon size for the plain instructions: digs the trench and flood fills the outside.
+pub fn solve(instructions: &[DigInstruction]) -> usize {
     let mut dug = HashMap::new();
-    let mut curr_pos = (0, 0);
-    let mut minmax = (0, 0, 0, 0);
+    let mut curr_pos = Point2::new(0, 0);
+    let mut bounds = BoundingBox {
+        min: curr_pos,
+        max: curr_pos,
+    };
     for instr in instructions {
         for _ in 0..instr.value {
-            match instr.direction {
-                Direction::Up => curr_pos = (curr_pos.0 - 1, curr_pos.1),
-                Direction::Down => curr_pos = (curr_pos.0 + 1, curr_pos.1),
-                Direction::Right => curr_pos = (curr_pos.0, curr_pos.1 + 1),
-                Direction::Left => curr_pos = (curr_pos.0, curr_pos.1 - 1),
-            }
-            minmax = (
-                minmax.0.min(curr_pos.0),
-                minmax.1.min(curr_pos.1),
-                minmax.2.max(curr_pos.0),
-                minmax.3.max(curr_pos.1),
-            );
+            curr_pos = curr_pos
+                .step(instr.direction, 1)
+                .expect("the lagoon fits into i32");
+            bounds.include(curr_pos);
             dug.insert(curr_pos, 0);
         }
     }
 
     let mut borders = HashSet::new();
     let mut color = 0;
-    for x_val in minmax.0..minmax.2 {
-        for y_val in minmax.1..minmax.3 {
+    for y_val in bounds.min.y..bounds.max.y {
+        for x_val in bounds.min.x..bounds.max.x {
             color += 1;
-            if !dug.contains_key(&(x_val, y_val)) {
-                color_map(&mut dug, (x_val, y_val), color, &mut borders, minmax);
+            let pos = Point2::new(x_val, y_val);
+            if !dug.contains_key(&pos) {
+                color_map(&mut dug, pos, color, &mut borders, &bounds);
             }
         }
     }
 
-    println!("max_color {}", color);
-    println!("minmax dbg {:?}", minmax);
-    println!(
+    crate::debug!("max_color {color}");
+    crate::debug!("minmax dbg {bounds:?}");
+    crate::debug!(
         "minmax val {}",
-        (minmax.2 - minmax.0) * (minmax.3 - minmax.1)
+        (bounds.max.x - bounds.min.x) * (bounds.max.y - bounds.min.y)
     );
     dug.values()
         .filter(|value| !borders.contains(value))
@@ -382,28 +600,20 @@ fn solve(instructions: &[DigInstruction]) -> usize {
 }
 
 fn color_map(
-    dug: &mut HashMap<(i32, i32), usize>,
-    pos: (i32, i32),
+    dug: &mut HashMap<Point2<i32>, usize>,
+    pos: Point2<i32>,
     color: usize,
     borders: &mut HashSet<usize>,
-    minmax: (i32, i32, i32, i32),
+    bounds: &BoundingBox<i32>,
 ) {
     let mut fill_map = Vec::new();
     fill_map.push(pos);
-    while let Some((x_val, y_val)) = fill_map.pop() {
-        dug.insert((x_val, y_val), color);
-        let neighbors = [
-            (x_val + 1, y_val),
-            (x_val - 1, y_val),
-            (x_val, y_val + 1),
-            (x_val, y_val - 1),
-        ];
+    while let Some(pos) = fill_map.pop() {
+        dug.insert(pos, color);
+        let neighbors = Direction::CARDINAL
+            .map(|direction| pos.step(direction, 1).expect("the lagoon fits into i32"));
         for neighbor in neighbors {
-            if neighbor.0 < minmax.0
-                || neighbor.1 < minmax.1
-                || neighbor.0 > minmax.2
-                || neighbor.1 > minmax.3
-            {
+            if !bounds.contains(neighbor) {
                 borders.insert(color);
             } else if dug.contains_key(&neighbor) {
                 continue;
@@ -416,7 +626,49 @@ fn color_map(
 
 #[cfg(test)]
 pub mod test18 {
-    use crate::day18::{solve_p2, DigInstruction};
+    use crate::day::Day;
+    use crate::day18::{solve_p2, DigInstruction, Solution};
+    use crate::export::{Export, Rgb};
+
+    #[test]
+    fn test_export() {
+        let input = std::fs::read_to_string("examples/day18/example1.txt").expect("fixture exists");
+        let images = Solution::export(&Solution::parse(&input).expect("should parse"));
+        assert_eq!(1, images.len());
+        let lagoon = &images[0];
+        assert_eq!("day18-lagoon", lagoon.name);
+        // the 7 by 10 trench with a border of one pixel
+        assert_eq!((9, 12), (lagoon.pixels.width(), lagoon.pixels.height()));
+        let count = |color: Rgb| lagoon.pixels.cells().iter().filter(|pixel| **pixel == color).count();
+        assert_eq!(6, count(Rgb::from_hex(0x70c710)));
+        assert_eq!(5, count(Rgb::from_hex(0x0dc571)));
+        assert_eq!(24, count(Rgb::grey(0.8)));
+        assert_eq!(9 * 12 - 62, count(Rgb::WHITE));
+        assert_eq!(Rgb::WHITE, lagoon.pixels[(0, 0)]);
+        assert_eq!(Rgb::from_hex(0x7a21e3), lagoon.pixels[(1, 1)]);
+    }
+
+    #[test]
+    fn test_open_trench() {
+        let input = std::fs::read_to_string("examples/day18/example1.txt").expect("fixture exists");
+        let lines: Vec<&str> = input.lines().collect();
+        let open = lines[..lines.len() - 2].join("\n");
+        let err = Solution::parse(&open).expect_err("the trench does not close");
+        assert_eq!(
+            "12:14: expected a trench back to its start, the one of part 1 ends at 2,2",
+            err.to_string()
+        );
+        // the colours alone can leave the trench open as well
+        let err = Solution::parse("R 1 (#000010)\nL 1 (#000012)\nR 1 (#000010)\nL 1 (#000022)")
+            .expect_err("part 2 does not close");
+        assert!(err.expected.contains("part 2 ends at -1,0"));
+    }
+
+    #[test]
+    fn test_color() {
+        let instruction: DigInstruction = "R 6 (#70c710)".parse().expect("should parse");
+        assert_eq!(0x70c710, instruction.color_code.color());
+    }
 
     #[test]
     fn test_basic() {
@@ -1,10 +1,12 @@
+use crate::day::{Answer, Day};
+use crate::dot::{Dot, Graph, NodeStyle};
+use crate::fuzz::{Fuzz, Rng};
+use crate::interval::{Interval, IntervalBox};
+use crate::parse::ParseError;
 use itertools::Itertools;
 use parse_display::{Display, FromStr};
 use std::collections::HashMap;
-use std::fs;
 use std::str::FromStr;
-use std::string::ParseError;
-use std::time::Instant;
 
 #[derive(Debug, FromStr, Clone)]
 #[display("{name}{{{rules}}}")]
@@ -14,27 +16,33 @@ struct Workflow {
 }
 
 #[derive(Debug, Clone)]
-struct Rules {
+pub struct Rules {
     rules: Vec<Rule>,
     default: String,
 }
 
 impl FromStr for Rules {
-    type Err = ParseError;
+    type Err = parse_display::ParseError;
 
     fn from_str(rule_string: &str) -> Result<Self, Self::Err> {
         let mut rules_str: Vec<&str> = rule_string.split(',').collect();
-        let mut rules = Vec::new();
         let default = rules_str
             .pop()
-            .expect("should have at least the default rule")
-            .parse()?;
+            .filter(|default| !default.is_empty())
+            .ok_or(parse_display::ParseError::with_message("missing default rule"))?;
+        let mut rules = Vec::new();
         for rule_str in rules_str {
-            let rule = rule_str.parse().expect("why cannot i use ? here???");
+            let rule: Rule = rule_str.parse()?;
+            if !"xmas".contains(rule.variable) {
+                return Err(parse_display::ParseError::with_message("unknown variable"));
+            }
             rules.push(rule);
         }
 
-        Ok(Rules { rules, default })
+        Ok(Rules {
+            rules,
+            default: String::from(default),
+        })
     }
 }
 
@@ -51,19 +59,20 @@ struct Rule {
 
 impl Rules {
     fn eval(&self, rating: &Rating) -> EvalResult {
-        for rule in &self.rules {
-            match rule.eval(rating) {
-                Some(result) => return result,
-                None => (),
-            }
-        }
+        EvalResult::create_from_str(self.target(rating))
+    }
 
-        EvalResult::create_from_str(&self.default)
+    /// Where `rating` goes next: a workflow, `A` or `R`.
+    fn target(&self, rating: &Rating) -> &str {
+        self.rules
+            .iter()
+            .find(|rule| rule.clears(rating))
+            .map_or(&self.default, |rule| &rule.success)
     }
 }
 
 impl Rule {
-    fn eval(&self, rating: &Rating) -> Option<EvalResult> {
+    fn clears(&self, rating: &Rating) -> bool {
         let val = match self.variable {
             'a' => rating.a_rat,
             'm' => rating.m_rat,
@@ -72,15 +81,9 @@ impl Rule {
             _ => unreachable!("strange variant"),
         };
 
-        let clears = match self.operation {
+        match self.operation {
             Operation::GT => val > self.value,
             Operation::LT => val < self.value,
-        };
-
-        if clears {
-            Some(EvalResult::create_from_str(&self.success))
-        } else {
-            None
         }
     }
 }
@@ -100,13 +103,6 @@ impl EvalResult {
             rest => EvalResult::NextRule(String::from(rest)),
         }
     }
-
-    fn is_final(&self) -> bool {
-        match self {
-            EvalResult::Accept | EvalResult::Reject => true,
-            EvalResult::NextRule(_) => false,
-        }
-    }
 }
 
 #[derive(Display, FromStr, Copy, Clone, Debug)]
@@ -119,11 +115,11 @@ enum Operation {
 
 #[derive(Display, FromStr, Copy, Clone, Debug)]
 #[display("{{x={x_rat},m={m_rat},a={a_rat},s={s_rat}}}")]
-struct Rating {
-    x_rat: usize,
-    m_rat: usize,
-    a_rat: usize,
-    s_rat: usize,
+pub struct Rating {
+    pub x_rat: usize,
+    pub m_rat: usize,
+    pub a_rat: usize,
+    pub s_rat: usize,
 }
 
 impl Rating {
@@ -132,103 +128,231 @@ impl Rating {
     }
 }
 
-pub fn run() {
-    let file = fs::read_to_string("input/day19").expect("Could not open file.");
-    let mut lines = file.lines();
-    let workflows = lines
-        .by_ref()
-        .map_while(|line| line.parse::<Workflow>().ok())
-        .map(|workflow| (workflow.name, workflow.rules))
-        .collect::<HashMap<String, Rules>>();
-    let ratings = lines
-        .map(|line| line.parse::<Rating>().expect("no rating"))
-        .collect::<Vec<Rating>>();
-
-    let now = Instant::now();
-    let (res_1, res_2) = (solve(&ratings, &workflows), solve_p2(&workflows));
-    println!("Solutions took {} µs", now.elapsed().as_micros());
-    println!("Day 19 Solution Part 1: {}", res_1);
-    println!("Day 19 Solution Part 2: {}", res_2);
+pub struct Solution;
+
+/// The workflows by name and the ratings of the parts to sort.
+pub struct System {
+    pub workflows: HashMap<String, Rules>,
+    pub ratings: Vec<Rating>,
 }
 
-fn solve(ratings: &[Rating], workflows: &HashMap<String, Rules>) -> usize {
-    let start_rule = workflows.get("in").expect("start should be available");
-    let mut total = 0;
-    for rating in ratings {
-        let mut result = start_rule.eval(rating);
-        while !result.is_final() {
-            if let EvalResult::NextRule(next_workflow) = &result {
-                let next = workflows
-                    .get(next_workflow)
-                    .expect("could not find next workflow");
-                result = next.eval(rating);
-            } else {
-                unreachable!("huh?");
-            }
+impl Day for Solution {
+    const DAY: u8 = 19;
+    type Input = System;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut lines = input.lines();
+        let mut workflows = HashMap::new();
+        let mut workflow_lines = Vec::new();
+        for line in lines.by_ref().take_while(|line| !line.is_empty()) {
+            let workflow = line.parse::<Workflow>().map_err(|_| {
+                ParseError::at(input, line, "a workflow like `px{a<2006:qkq,m>2090:A,rfg}`")
+            })?;
+            workflows.insert(workflow.name, workflow.rules);
+            workflow_lines.push(line);
+        }
+        if !workflows.contains_key("in") {
+            return Err(ParseError::at_end(input, "a workflow named `in`"));
         }
-        if result == EvalResult::Accept {
-            total += rating.sum_up();
+        // the target of every rule is the part after its `:`, or all of the default rule
+        for line in workflow_lines {
+            let (_, rules) = line.split_once('{').expect("the workflow parsed");
+            let targets = rules[..rules.len() - 1]
+                .split(',')
+                .map(|rule| rule.rsplit(':').next().expect("split yields a part"));
+            for target in targets {
+                if !["A", "R"].contains(&target) && !workflows.contains_key(target) {
+                    return Err(ParseError::at(
+                        input,
+                        target,
+                        format!("`A`, `R` or a workflow, found `{target}`"),
+                    ));
+                }
+            }
         }
+        let ratings = lines
+            .map(|line| {
+                line.parse::<Rating>().map_err(|_| {
+                    ParseError::at(input, line, "a rating like `{x=787,m=2655,a=1222,s=2876}`")
+                })
+            })
+            .collect::<Result<Vec<Rating>, _>>()?;
+        Ok(System { workflows, ratings })
+    }
+
+    fn part1(system: &Self::Input) -> Answer {
+        solve(&system.ratings, &system.workflows).into()
+    }
+
+    fn part2(system: &Self::Input) -> Answer {
+        solve_p2(&system.workflows).into()
     }
-    total
 }
 
-#[derive(Clone, Debug, PartialOrd, PartialEq)]
-struct ValidRatings {
-    x_rat: (usize, usize),
-    m_rat: (usize, usize),
-    a_rat: (usize, usize),
-    s_rat: (usize, usize),
+impl Dot for Solution {
+    /// Workflows as boxes, `A` in green and `R` in red, with the rules as edge labels. The way of
+    /// the first part through the workflows is highlighted.
+    fn graph(system: &Self::Input) -> Graph {
+        let mut graph = Graph::new("day19-workflows");
+        graph.node("A", NodeStyle::color("green").shape("doublecircle"));
+        graph.node("R", NodeStyle::color("red").shape("doublecircle"));
+        let workflows = system.workflows.iter().sorted_by_key(|(name, _)| *name);
+        for (name, _) in workflows.clone() {
+            graph.node(name.as_str(), NodeStyle::PLAIN.shape("box"));
+        }
+        for (name, rules) in workflows {
+            for rule in &rules.rules {
+                let condition = format!("{}{}{}", rule.variable, rule.operation, rule.value);
+                graph.edge(name.as_str(), rule.success.as_str(), Some(condition));
+            }
+            graph.edge(name.as_str(), rules.default.as_str(), None);
+        }
+        if let Some(rating) = system.ratings.first() {
+            graph.highlighted = route(rating, &system.workflows).into_iter().collect();
+        }
+        graph
+    }
 }
 
-impl ValidRatings {
-    fn initial() -> Self {
-        let init_rating = (1, 4000);
-        ValidRatings {
-            x_rat: init_rating,
-            m_rat: init_rating,
-            a_rat: init_rating,
-            s_rat: init_rating,
+impl Fuzz for Solution {
+    const PART: u8 = 2;
+
+    /// Workflows only send parts on to workflows further down the list, so there are no loops.
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let count = rng.range(1..size as u64 + 1) as usize;
+        let name = |idx: usize| match idx {
+            0 => String::from("in"),
+            idx => format!("w{}", (b'a' + (idx % 26) as u8) as char) + &"z".repeat(idx / 26),
+        };
+        let mut input = String::new();
+        for idx in 0..count {
+            let target = |rng: &mut Rng| match rng.range(idx as u64..count as u64 + 2) {
+                next if next as usize == count => String::from("A"),
+                next if next as usize == count + 1 => String::from("R"),
+                next if next as usize == idx => String::from(*rng.pick(&["A", "R"])),
+                next => name(next as usize),
+            };
+            let rules = (0..rng.range(1..4))
+                .map(|_| {
+                    let variable = rng.pick(&['x', 'm', 'a', 's']);
+                    let operation = rng.pick(&['<', '>']);
+                    let value = rng.range(1..4001);
+                    format!("{variable}{operation}{value}:{}", target(rng))
+                })
+                .join(",");
+            input.push_str(&format!("{}{{{rules},{}}}\n", name(idx), target(rng)));
+        }
+        input.push('\n');
+        for _ in 0..rng.range(1..4) {
+            let [x, m, a, s] = [(); 4].map(|_| rng.range(1..4001));
+            input.push_str(&format!("{{x={x},m={m},a={a},s={s}}}\n"));
         }
+        input
     }
 
-    fn modify(&self, pos: char, value: (usize, usize)) -> ValidRatings {
-        let mut res = self.clone();
-        match pos {
-            'x' => res.x_rat = value,
-            'm' => res.m_rat = value,
-            'a' => res.a_rat = value,
-            's' => res.s_rat = value,
-            _ => unreachable!("nope variant"),
+    /// Splits every rating at the values the rules compare against and sends one part of each
+    /// resulting block through the workflows.
+    fn reference(system: &System) -> Option<Answer> {
+        let mut cuts: HashMap<char, Vec<usize>> = "xmas"
+            .chars()
+            .map(|variable| (variable, vec![1, 4001]))
+            .collect();
+        for rule in system.workflows.values().flat_map(|rules| &rules.rules) {
+            if !(1..=4000).contains(&rule.value) {
+                return None;
+            }
+            let cut = match rule.operation {
+                Operation::LT => rule.value,
+                Operation::GT => rule.value + 1,
+            };
+            cuts.get_mut(&rule.variable)?.push(cut);
+        }
+        let blocks = |variable: char| -> Vec<(usize, usize)> {
+            cuts[&variable]
+                .iter()
+                .copied()
+                .sorted()
+                .dedup()
+                .tuple_windows()
+                .collect()
+        };
+
+        let mut total = 0;
+        for (x, m, a, s) in itertools::iproduct!(blocks('x'), blocks('m'), blocks('a'), blocks('s'))
+        {
+            let rating = Rating {
+                x_rat: x.0,
+                m_rat: m.0,
+                a_rat: a.0,
+                s_rat: s.0,
+            };
+            if accepted(&rating, &system.workflows)? {
+                total += (x.1 - x.0) * (m.1 - m.0) * (a.1 - a.0) * (s.1 - s.0);
+            }
         }
-        res
+        Some(total.into())
     }
+}
 
-    fn is_disjunct(&self, other: &ValidRatings) -> bool {
-        let compare = |(s_min, s_max), (o_min, o_max)| {
-            let self_fully_below = s_max < o_min;
-            let other_fully_below = o_max < s_min;
-            self_fully_below || other_fully_below
+/// The summed up ratings of all accepted parts.
+/// The workflows `rating` passes through from `in`, ending in `A` or `R` unless a workflow is
+/// missing or the workflows loop.
+fn route(rating: &Rating, workflows: &HashMap<String, Rules>) -> Vec<String> {
+    let mut route = vec![String::from("in")];
+    for _ in 0..workflows.len() {
+        let Some(rules) = workflows.get(route.last().expect("starts with `in`")) else {
+            break;
         };
-        let x_disjunct = compare(self.x_rat, other.x_rat);
-        let m_disjunct = compare(self.m_rat, other.m_rat);
-        let a_disjunct = compare(self.a_rat, other.a_rat);
-        let s_disjunct = compare(self.s_rat, other.s_rat);
-        x_disjunct || m_disjunct || a_disjunct || s_disjunct
+        route.push(String::from(rules.target(rating)));
     }
+    route
+}
+
+pub fn solve(ratings: &[Rating], workflows: &HashMap<String, Rules>) -> usize {
+    ratings
+        .iter()
+        .filter(|rating| accepted(rating, workflows).expect("workflows should end in A or R"))
+        .map(Rating::sum_up)
+        .sum()
+}
 
-    fn total(&self) -> usize {
-        let x_delta = self.x_rat.1 - self.x_rat.0 + 1;
-        let s_delta = self.s_rat.1 - self.s_rat.0 + 1;
-        let a_delta = self.a_rat.1 - self.a_rat.0 + 1;
-        let m_delta = self.m_rat.1 - self.m_rat.0 + 1;
-        x_delta * s_delta * a_delta * m_delta
+/// Sends the rating through the workflows starting at `in`; `None` if a workflow is missing or
+/// the workflows loop.
+pub fn accepted(rating: &Rating, workflows: &HashMap<String, Rules>) -> Option<bool> {
+    let mut result = workflows.get("in")?.eval(rating);
+    for _ in 0..workflows.len() {
+        match result {
+            EvalResult::Accept => return Some(true),
+            EvalResult::Reject => return Some(false),
+            EvalResult::NextRule(next_workflow) => {
+                result = workflows.get(&next_workflow)?.eval(rating);
+            }
+        }
+    }
+    match result {
+        EvalResult::NextRule(_) => None,
+        result => Some(result == EvalResult::Accept),
     }
 }
 
+/// The ratings a part can still have, one interval per variable in `xmas` order.
+type ValidRatings = IntervalBox<usize, 4>;
+
+/// Every rating from 1 to 4000.
+fn all_ratings() -> ValidRatings {
+    IntervalBox::new([Interval::new(1, 4001); 4])
+}
+
+/// The axis of `variable` in [`ValidRatings`].
+fn axis(variable: char) -> usize {
+    "xmas"
+        .find(variable)
+        .expect("variables are checked while parsing")
+}
+
 impl Rules {
     fn expand(&self, valid_ratings: &ValidRatings) -> Vec<ExpansionResult> {
-        let mut next = Some(valid_ratings.clone());
+        let mut next = Some(*valid_ratings);
         let mut results = Vec::new();
         for rule in &self.rules {
             if let Some(to_apply) = next {
@@ -260,50 +384,23 @@ impl Rule {
         &self,
         rating: &ValidRatings,
     ) -> (Option<ExpansionResult>, Option<ValidRatings>) {
-        let val = match self.variable {
-            'a' => rating.a_rat,
-            'm' => rating.m_rat,
-            's' => rating.s_rat,
-            'x' => rating.x_rat,
-            _ => unreachable!("strange variant"),
-        };
-        let split = match self.operation {
+        let axis = axis(self.variable);
+        let (applied, not_applied) = match self.operation {
             Operation::GT => {
-                if val.1 <= self.value {
-                    (None, Some(val))
-                } else if val.0 > self.value {
-                    (Some(val), None)
-                } else {
-                    (Some((self.value + 1, val.1)), Some((val.0, self.value)))
-                }
-            }
-            Operation::LT => {
-                if val.0 >= self.value {
-                    (None, Some(val))
-                } else if val.1 < self.value {
-                    (Some(val), None)
-                } else {
-                    (Some((val.0, self.value - 1)), Some((self.value, val.1)))
-                }
+                let (below, above) = rating.split(axis, self.value + 1);
+                (above, below)
             }
+            Operation::LT => rating.split(axis, self.value),
         };
 
-        let exp_res = match (split.0, &self.success) {
+        let exp_res = match (applied, &self.success) {
             (None, _) => None,
-            (Some(val), str) if str == "A" => {
-                Some(ExpansionResult::Accept(rating.modify(self.variable, val)))
-            }
+            (Some(applied), str) if str == "A" => Some(ExpansionResult::Accept(applied)),
             (Some(_), str) if str == "R" => None,
-            (Some(val), str) => Some(ExpansionResult::Next(
-                String::from(str),
-                rating.modify(self.variable, val),
-            )),
+            (Some(applied), str) => Some(ExpansionResult::Next(String::from(str), applied)),
         };
 
-        (
-            exp_res,
-            split.1.map(|val| rating.modify(self.variable, val)),
-        )
+        (exp_res, not_applied)
     }
 }
 
@@ -312,13 +409,17 @@ enum ExpansionResult {
     Next(String, ValidRatings),
 }
 
-fn solve_p2(workflows: &HashMap<String, Rules>) -> usize {
+/// How many of all ratings between 1 and 4000 get accepted, splitting the rating ranges at every
+/// rule instead of trying them one by one.
+pub fn solve_p2(workflows: &HashMap<String, Rules>) -> usize {
     let mut candidates = Vec::new();
-    candidates.push((String::from("in"), ValidRatings::initial()));
+    candidates.push((String::from("in"), all_ratings()));
     let mut accepted = Vec::new();
 
     while let Some(candidate) = candidates.pop() {
-        let rules = workflows.get(&candidate.0).unwrap();
+        let rules = workflows
+            .get(&candidate.0)
+            .expect("parse checks that every target is a workflow");
         let results = rules.expand(&candidate.1);
         for result in results {
             match result {
@@ -329,12 +430,12 @@ fn solve_p2(workflows: &HashMap<String, Rules>) -> usize {
     }
     for accept in &accepted {
         for accept_b in &accepted {
-            if accept != accept_b && !accept.is_disjunct(accept_b) {
-                println!("not disjunct {:?} {:?}", accept, accept_b);
+            if accept != accept_b && accept.intersects(accept_b) {
+                crate::warn!("not disjunct {accept:?} {accept_b:?}");
             }
         }
     }
-    accepted.iter().map(ValidRatings::total).sum()
+    accepted.iter().map(ValidRatings::volume).sum()
 }
 
 #[test]
@@ -361,5 +462,41 @@ hdj{m>838:A,pv}
 
 #[test]
 fn test_eq() {
-    assert!(!ValidRatings::initial().is_disjunct(&ValidRatings::initial()))
+    assert!(all_ratings().intersects(&all_ratings()))
+}
+
+#[test]
+fn test_parse_error() {
+    let input = "in{s<1351:px,qqz}\npx{a<2006:qkq,y>2090:A,rfg}\n\n{x=787,m=2655,a=1222,s=2876}\n";
+    let err = Solution::parse(input).err().expect("unknown variable");
+    assert_eq!(
+        "2:1: expected a workflow like `px{a<2006:qkq,m>2090:A,rfg}`",
+        err.to_string()
+    );
+
+    let input = "in{s<1351:px,A}\npx{A}\n\n{x=787,m=2655,a=1222}\n";
+    let err = Solution::parse(input).err().expect("incomplete rating");
+    assert_eq!((4, 1), (err.line, err.column));
+
+    let input = "in{s<1351:px,A}\npx{a<2006:qkq,R}\n\n{x=787,m=2655,a=1222,s=2876}\n";
+    let err = Solution::parse(input).err().expect("unknown target");
+    assert_eq!(
+        "2:11: expected `A`, `R` or a workflow, found `qkq`",
+        err.to_string()
+    );
+    let err = Solution::parse("in{s<1351:A,px}\n").err().expect("unknown default");
+    assert_eq!((1, 13), (err.line, err.column));
+}
+
+#[test]
+fn test_graph() {
+    let input = std::fs::read_to_string("examples/day19/example1.txt").expect("fixture exists");
+    let graph = Solution::graph(&Solution::parse(&input).expect("should parse"));
+    assert_eq!("day19-workflows", graph.name);
+    // the way of the first part, as the puzzle text walks it
+    assert_eq!(
+        std::collections::BTreeSet::from(["in", "qqz", "qs", "lnx", "A"].map(String::from)),
+        graph.highlighted
+    );
+    assert_eq!(13, graph.nodes.len());
 }
@@ -1,32 +1,95 @@
-use crate::day20::Pulse::{HIGH, LOW};
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::day::{Answer, Day};
+use crate::debugger::{Simulate, Simulation};
+use crate::dot::{Dot, Graph, NodeStyle};
+use crate::parse::{self, ParseError};
+use crate::day20::Pulse::{High, Low};
 use crate::day20::Type::{Conjunction, FlipFlop};
 use itertools::Itertools;
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::fs;
-use std::str::Lines;
-use std::time::Instant;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::rc::Rc;
 
 const START: &str = "broadcaster";
-pub fn run() {
-    let file = fs::read_to_string("input/day20").expect("Could not open file.");
-    let modules = parse(file.lines());
-
-    let now = Instant::now();
-    let (res_1, res_2) = (solve(&modules), solvey_solve(&modules));
-    println!("Solutions took {} µs", now.elapsed().as_micros());
-    println!("Day 20 Solution Part 1: {}", res_1);
-    println!("Day 20 Solution Part 2: {}", res_2);
+
+/// Every module by name, with its type and the modules it sends pulses to.
+pub type Modules = HashMap<String, (Type, Vec<String>)>;
+
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = 20;
+    type Input = Modules;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(modules: &Self::Input) -> Answer {
+        solve(modules).into()
+    }
+
+    fn part2(modules: &Self::Input) -> Answer {
+        Self::part2_cancellable(modules, &CancellationToken::new())
+    }
+
+    fn part2_cancellable(modules: &Self::Input, token: &CancellationToken) -> Answer {
+        match solvey_solve(modules, token) {
+            Ok(Some(presses)) => presses.into(),
+            Ok(None) | Err(_) => Answer::Unsolved,
+        }
+    }
 }
 
-fn parse(lines: Lines) -> HashMap<&str, (Type, Vec<&str>)> {
+impl Dot for Solution {
+    /// Flip-flops in blue, conjunctions in red and the broadcaster as a box. Highlights `rx` and
+    /// the two layers of conjunctions feeding it, whose cycles `solvey_solve` combines.
+    fn graph(modules: &Self::Input) -> Graph {
+        let mut graph = Graph::new("day20-modules");
+        let modules = modules.iter().sorted_by_key(|(name, _)| *name);
+        for (name, (typ, _)) in modules.clone() {
+            let style = match typ {
+                Type::Start => NodeStyle::PLAIN.shape("box"),
+                FlipFlop => NodeStyle::color("blue"),
+                Conjunction => NodeStyle::color("red"),
+            };
+            graph.node(name.as_str(), style);
+        }
+        for (name, (_, targets)) in modules.clone() {
+            for target in targets {
+                graph.edge(name.as_str(), target.as_str(), None);
+            }
+        }
+        if graph.nodes.contains_key("rx") {
+            let mut highlighted = BTreeSet::from([String::from("rx")]);
+            for _ in 0..2 {
+                let feeders: Vec<String> = modules
+                    .clone()
+                    .filter(|(_, (_, targets))| targets.iter().any(|t| highlighted.contains(t)))
+                    .map(|(name, _)| name.clone())
+                    .collect();
+                highlighted.extend(feeders);
+            }
+            graph.highlighted = highlighted;
+        }
+        graph
+    }
+}
+
+fn parse(input: &str) -> Result<Modules, ParseError> {
     let mut modules = HashMap::new();
-    for line in lines {
-        parse_module(line, &mut modules);
+    for line in input.lines() {
+        parse_module(input, line, &mut modules)?;
+    }
+    if !modules.contains_key(START) {
+        return Err(ParseError::at_end(input, "a `broadcaster` module"));
     }
-    modules
+    Ok(modules)
 }
 
-fn solve_p2(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
+/// Exploration of the flip-flop counters feeding `rs`, which led to the hardcoded conjunctions
+/// in `solvey_solve`.
+#[allow(dead_code)]
+fn solve_p2(modules: &Modules) -> usize {
     // Asertion: rx is always fed by one conjunction, conjoining n conjunctions
     // the joined conjunctions are independent
     // we need to find out the cycle time of each conjunction (they all need to fire high in the same turn)
@@ -35,37 +98,27 @@ fn solve_p2(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
     let reverse_modules = modules
         .iter()
         .flat_map(|(name, (typ, following))| {
-            following.iter().map(|follower| (*follower, (*name, *typ)))
+            following
+                .iter()
+                .map(|follower| (follower.as_str(), (name.as_str(), *typ)))
         })
         .fold(
             HashMap::new(),
             |mut acc: HashMap<&str, HashSet<(&str, Type)>>, (key, value)| {
-                let entry = acc
-                    .entry(key)
-                    .and_modify(|values| {
-                        values.insert(value);
-                    })
-                    .or_insert({
-                        let mut set = HashSet::new();
-                        set.insert(value);
-                        set
-                    });
-
+                acc.entry(key).or_default().insert(value);
                 acc
             },
         );
 
     let mut flip_flop_groups = HashMap::new();
 
-    for (idx, (name, (typ, others))) in modules.iter().enumerate() {
+    for (name, (typ, _others)) in modules {
         if *typ == FlipFlop {
-            put_or_follow(*name, idx, &mut flip_flop_groups, modules);
+            put_or_follow(name, &mut flip_flop_groups, modules);
         }
     }
 
-    let mut node_conjunctions: HashMap<&str, Option<usize>> = flip_flop_groups
-        .iter()
-        .map(|(_ff_name, conj_name)| *conj_name)
+    let _node_conjunctions: HashMap<&str, Option<usize>> = flip_flop_groups.values().copied()
         .unique()
         .map(|val| (val, None))
         .collect();
@@ -81,7 +134,7 @@ fn solve_p2(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
         let relevant_flops: HashSet<&str> = flip_flop_groups
             .iter()
             .filter(|(_ff, g_name)| *g_name == node_conjunction)
-            .map(|(ff, g_name)| *ff)
+            .map(|(ff, _g_name)| *ff)
             .unique()
             .collect();
         let start_flop = modules
@@ -89,16 +142,16 @@ fn solve_p2(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
             .unwrap()
             .1
             .iter()
-            .find(|val| relevant_flops.contains(*val))
+            .find(|val| relevant_flops.contains(val.as_str()))
             .unwrap();
         let mut queue = VecDeque::new();
         let mut relevant_sorted = Vec::new();
         let mut curr_sorting = Some(START);
         'outer: while let Some(previous) = curr_sorting {
             for next in &modules.get(previous).unwrap().1 {
-                if relevant_flops.contains(next) {
-                    relevant_sorted.push(*next);
-                    curr_sorting = Some(next);
+                if relevant_flops.contains(next.as_str()) {
+                    relevant_sorted.push(next.as_str());
+                    curr_sorting = Some(next.as_str());
                     continue 'outer;
                 }
             }
@@ -106,20 +159,22 @@ fn solve_p2(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
         }
         relevant_sorted.reverse();
 
-        print!("NODES    |");
-        for val in &relevant_sorted {
-            if direct_flip_flops.contains_key(val) {
-                print!("++ {} ++|", val);
-            } else {
-                print!("   {}   |", val);
-            }
-        }
-        println!();
+        crate::debug!(
+            "NODES    |{}",
+            relevant_sorted
+                .iter()
+                .map(|val| if direct_flip_flops.contains_key(val) {
+                    format!("++ {val} ++|")
+                } else {
+                    format!("   {val}   |")
+                })
+                .collect::<String>()
+        );
 
-        let mut last_push = LOW;
+        let mut last_push = Low;
 
         for press in 1..81920 {
-            queue.push_front((*start_flop, Pulse::LOW, START));
+            queue.push_front((start_flop.as_str(), Pulse::Low, START));
             while let Some((name, pulse, source)) = queue.pop_back() {
                 let module = modules.get(name).unwrap();
                 match module {
@@ -127,14 +182,14 @@ fn solve_p2(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
                         unreachable!("that was not the plan");
                     }
                     (Type::FlipFlop, targets) => {
-                        if pulse != HIGH {
+                        if pulse != High {
                             let state = *flip_flop_state.get(name).unwrap_or(&false);
 
-                            let pulse = if state { LOW } else { HIGH };
+                            let pulse = if state { Low } else { High };
                             flip_flop_state.insert(name, !state);
 
                             for target in targets {
-                                queue.push_front((*target, pulse, name));
+                                queue.push_front((target.as_str(), pulse, name));
                             }
                         }
                     }
@@ -148,16 +203,16 @@ fn solve_p2(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
                         last_push = pulse;
 
                         for target in targets {
-                            if relevant_flops.contains(target) {
-                                queue.push_front((*target, pulse, name));
+                            if relevant_flops.contains(target.as_str()) {
+                                queue.push_front((target.as_str(), pulse, name));
                             }
                         }
                     }
                 }
             }
 
-            if last_push == LOW {
-                println!("aaaaaaaaaaaaaaaaaaaaah {press}");
+            if last_push == Low {
+                crate::debug!("aaaaaaaaaaaaaaaaaaaaah {press}");
             }
 
             if press == 4003
@@ -171,7 +226,7 @@ fn solve_p2(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
                 || press == 8192
             {
                 let mut number_collect = Vec::new();
-                print!("STP {number:>3} |", number = press);
+                let mut flops = String::new();
                 for sort in &relevant_sorted {
                     let val = if *flip_flop_state.get(sort).unwrap_or(&false) {
                         number_collect.push(1);
@@ -180,37 +235,31 @@ fn solve_p2(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
                         number_collect.push(0);
                         "l"
                     };
-                    print!("    {val}   |");
+                    flops.push_str(&format!("    {val}   |"));
                 }
 
                 let mut number = 0;
                 for val in number_collect.iter() {
-                    number = number << 1;
+                    number <<= 1;
                     number += val;
                 }
 
-                println!("  {number}");
-
-                print!("CONJ  {node_conjunction} |");
+                crate::debug!("STP {press:>3} |{flops}  {number}");
 
                 let state = conjunction_state.get(node_conjunction).unwrap();
+                let mut inputs = String::new();
                 for sort in &relevant_sorted {
                     let pulse = state.iter().find(|val| val.0 == *sort).map(|val| val.1);
-                    let print = match pulse {
-                        Some(HIGH) => "    H   |",
-                        Some(LOW) => "    l   |",
+                    inputs.push_str(match pulse {
+                        Some(High) => "    H   |",
+                        Some(Low) => "    l   |",
                         None => "        |",
-                    };
-                    print!("{print}");
+                    });
                 }
-                println!(
-                    "  {:?} -- {}",
+                crate::debug!(
+                    "CONJ  {node_conjunction} |{inputs}  {:?} -- {}",
                     last_push,
-                    conjunction_state
-                        .get(node_conjunction)
-                        .unwrap()
-                        .iter()
-                        .all(|val| val.1 == HIGH)
+                    state.iter().all(|val| val.1 == High)
                 );
             }
 
@@ -238,150 +287,247 @@ fn solve_p2(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
     }
 
     let val = usize::from_str_radix("111110100011", 2).unwrap();
-    println!("val? {val}");
+    crate::debug!("val? {val}");
 
     5
 }
 
 fn put_or_follow<'a>(
     name: &'a str,
-    idx: usize,
     groups: &mut HashMap<&'a str, &'a str>,
-    modules: &HashMap<&str, (Type, Vec<&'a str>)>,
+    modules: &'a Modules,
 ) -> Option<&'a str> {
     let mut conj_name = None;
     if let Some(group) = groups.get(&name) {
         conj_name = Some(*group);
-    } else {
-        if let Some((FlipFlop, following)) = modules.get(&name) {
-            for next in following {
-                let new_name = put_or_follow(*next, idx, groups, modules);
-                if new_name.is_some() {
-                    conj_name = new_name;
-                }
+    } else if let Some((FlipFlop, following)) = modules.get(name) {
+        for next in following {
+            let new_name = put_or_follow(next, groups, modules);
+            if new_name.is_some() {
+                conj_name = new_name;
             }
-            groups.insert(name, conj_name.expect("did not get group"));
-        } else if let Some((Conjunction, _)) = modules.get(&name) {
-            conj_name = Some(&name)
         }
+        groups.insert(name, conj_name.expect("did not get group"));
+    } else if let Some((Conjunction, _)) = modules.get(name) {
+        conj_name = Some(name)
     }
     conj_name
 }
 
-fn solve(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
-    let mut conjunction_state = extract_conjunction_states(modules);
-    let mut flip_flop_state: HashMap<&str, bool> = HashMap::new();
+/// The product of the low and the high pulses sent during 1000 button presses.
+pub fn solve(modules: &Modules) -> usize {
+    let mut machine = PulseMachine::new(modules);
+    for _ in 0..1000 {
+        machine.press();
+    }
+    machine.tally.low_pulses * machine.tally.high_pulses
+}
 
-    let mut queue = VecDeque::new();
-    let mut tally = Tally::new();
-    let mut presses_needed = None;
+/// The modules together with what they remember, between two presses of the button.
+#[derive(Clone)]
+pub struct PulseMachine {
+    modules: Rc<Modules>,
+    /// Flip-flops that are on; all others are off.
+    flip_flops: HashSet<String>,
+    /// The last pulse every conjunction got from each of its inputs.
+    conjunctions: HashMap<String, Vec<(String, Pulse)>>,
+    presses: usize,
+    tally: Tally,
+}
 
-    for press in 1..=1000 {
-        tally.count(LOW);
-        queue.push_front((START, Pulse::LOW, START));
-        while let Some((name, pulse, source)) = queue.pop_back() {
-            let module = modules.get(name);
-            let module = if let Some(module) = module {
-                module
-            } else {
-                assert_eq!("rx", name);
-                if pulse == LOW && presses_needed.is_none() {
-                    presses_needed = Some(press);
+impl PulseMachine {
+    pub fn new(modules: &Modules) -> Self {
+        let conjunctions = extract_conjunction_states(modules)
+            .into_iter()
+            .map(|(name, inputs)| {
+                let inputs = inputs
+                    .into_iter()
+                    .map(|(input, pulse)| (String::from(input), pulse))
+                    .collect();
+                (String::from(name), inputs)
+            })
+            .collect();
+        PulseMachine {
+            modules: Rc::new(modules.clone()),
+            flip_flops: HashSet::new(),
+            conjunctions,
+            presses: 0,
+            tally: Tally::new(),
+        }
+    }
+
+    /// Presses the button once and returns every pulse that set off as `(source, pulse, target)`,
+    /// in the order they arrived.
+    fn press(&mut self) -> Vec<(String, Pulse, String)> {
+        self.presses += 1;
+        let mut arrived = Vec::new();
+        let mut queue = VecDeque::from([(String::from("button"), Low, String::from(START))]);
+        while let Some((source, pulse, name)) = queue.pop_front() {
+            self.tally.count(pulse);
+            // untyped modules like `output` or `rx` only receive pulses
+            let sent = match self.modules.get(&name) {
+                None => None,
+                Some((Type::Start, _)) => Some(pulse),
+                Some((FlipFlop, _)) if pulse == High => None,
+                Some((FlipFlop, _)) => {
+                    if self.flip_flops.remove(&name) {
+                        Some(Low)
+                    } else {
+                        self.flip_flops.insert(name.clone());
+                        Some(High)
+                    }
                 }
-                continue;
-            };
-            match module {
-                (Type::Start, targets) => {
-                    for target in targets {
-                        tally.count(pulse);
-                        queue.push_front((*target, pulse, name))
+                Some((Conjunction, _)) => {
+                    let memory = self
+                        .conjunctions
+                        .get_mut(&name)
+                        .expect("every conjunction has a memory");
+                    for (input, last) in memory.iter_mut() {
+                        if *input == source {
+                            *last = pulse;
+                        }
                     }
+                    let all_high = memory.iter().all(|(_, last)| *last == High);
+                    Some(if all_high { Low } else { High })
                 }
-                (Type::FlipFlop, targets) => {
-                    if pulse != HIGH {
-                        let state = *flip_flop_state.get(name).unwrap_or(&false);
+            };
+            if let Some(sent) = sent {
+                for target in &self.modules[&name].1 {
+                    queue.push_back((name.clone(), sent, target.clone()));
+                }
+            }
+            arrived.push((source, pulse, name));
+        }
+        arrived
+    }
+}
 
-                        let pulse = if state { LOW } else { HIGH };
-                        flip_flop_state.insert(name, !state);
+impl Simulation for PulseMachine {
+    /// One press of the button; the event names the pulses that reached untyped modules.
+    fn step(&mut self) -> Option<String> {
+        let pulses = self.press();
+        let low = pulses.iter().filter(|(_, pulse, _)| *pulse == Low).count();
+        let mut event = format!(
+            "press {}: {low} low and {} high pulses",
+            self.presses,
+            pulses.len() - low
+        );
+        let untyped = pulses
+            .iter()
+            .filter(|(_, _, target)| !self.modules.contains_key(target))
+            .map(|(_, pulse, target)| (target, *pulse))
+            .counts();
+        for ((target, pulse), count) in untyped.into_iter().sorted() {
+            let pulse = if pulse == Low { "low" } else { "high" };
+            event.push_str(&format!(", {count}x {pulse} -> {target}"));
+        }
+        Some(event)
+    }
 
-                        for target in targets {
-                            tally.count(pulse);
-                            queue.push_front((*target, pulse, name));
-                        }
-                    }
+    fn inspect(&self) -> String {
+        let mut state = String::new();
+        for (name, (typ, _)) in self.modules.iter().sorted_by_key(|(name, _)| *name) {
+            match typ {
+                Type::Start => (),
+                FlipFlop => {
+                    let on = if self.flip_flops.contains(name) { "on" } else { "off" };
+                    state.push_str(&format!("%{name}: {on}\n"));
                 }
-                (Type::Conjunction, targets) => {
-                    let pulse =
-                        update_conjunction_and_trigger(name, source, pulse, &mut conjunction_state);
-                    for target in targets {
-                        tally.count(pulse);
-                        queue.push_front((*target, pulse, name));
-                    }
+                Conjunction => {
+                    let memory = self.conjunctions[name]
+                        .iter()
+                        .sorted()
+                        .map(|(input, pulse)| format!("{input}={pulse:?}"))
+                        .join(" ");
+                    state.push_str(&format!("&{name}: {memory}\n"));
                 }
             }
         }
+        state
+    }
+
+    fn watches(&self) -> Vec<(&'static str, i64)> {
+        vec![
+            ("presses", self.presses as i64),
+            ("low", self.tally.low_pulses as i64),
+            ("high", self.tally.high_pulses as i64),
+            ("on", self.flip_flops.len() as i64),
+        ]
+    }
+}
+
+impl Simulate for Solution {
+    type Simulation = PulseMachine;
+
+    fn simulation(modules: &Self::Input) -> PulseMachine {
+        PulseMachine::new(modules)
     }
-    tally.low_pulses * tally.high_pulses
 }
 
-fn solvey_solve(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
+/// The fewest button presses until `rx` gets a low pulse, from the cycles of the conjunctions
+/// that feed it (their names are specific to our input); stops once `token` is cancelled.
+/// `None` for an input without these conjunctions.
+pub fn solvey_solve(
+    modules: &Modules,
+    token: &CancellationToken,
+) -> Result<Option<usize>, Cancelled> {
+    let relevant = ["dd", "fh", "fc", "xp"];
+    if !relevant
+        .iter()
+        .all(|name| matches!(modules.get(*name), Some((Conjunction, _))))
+    {
+        return Ok(None);
+    }
+
     let mut conjunction_state = extract_conjunction_states(modules);
     let mut flip_flop_state: HashMap<&str, bool> = HashMap::new();
 
     let mut queue = VecDeque::new();
-    let mut presses_needed = None;
 
-    let relevant = ["dd", "fh", "fc", "xp"];
     let mut relev_map: HashMap<&str, Option<usize>> =
         relevant.iter().map(|val| (*val, None)).collect();
 
-    for press in 1.. {
-        queue.push_front((START, Pulse::LOW, START));
+    let mut press = 0;
+    loop {
+        press += 1;
+        // runs forever if one of the relevant conjunctions never fires
+        token.check()?;
+        queue.push_front((START, Pulse::Low, START));
         while let Some((name, pulse, source)) = queue.pop_back() {
-            if pulse == HIGH && relevant.contains(&source) {
+            if pulse == High && relevant.contains(&source) {
                 if let Some(value) = &relev_map.get(source).unwrap() {
                     if press % value != 0 {
-                        println!("for {source} found {value} but {press} does not match");
+                        crate::debug!("for {source} found {value} but {press} does not match");
                     }
                 } else {
-                    println!("initial found {source} at {press}");
+                    crate::debug!("initial found {source} at {press}");
                     relev_map.insert(source, Some(press));
 
                     if relev_map.values().all(|val| val.is_some()) {
-                        return relev_map
-                            .values()
-                            .map(|val| val.unwrap())
-                            .reduce(|a, b| a * b)
-                            .unwrap();
+                        return Ok(relev_map.values().map(|val| val.unwrap()).reduce(|a, b| a * b));
                     }
                 }
             }
 
-            let module = modules.get(name);
-            let module = if let Some(module) = module {
-                module
-            } else {
-                assert_eq!("rx", name);
-                if pulse == LOW && presses_needed.is_none() {
-                    presses_needed = Some(press);
-                }
+            // untyped modules like `output` or `rx` only receive pulses
+            let Some(module) = modules.get(name) else {
                 continue;
             };
             match module {
                 (Type::Start, targets) => {
                     for target in targets {
-                        queue.push_front((*target, pulse, name))
+                        queue.push_front((target.as_str(), pulse, name))
                     }
                 }
                 (Type::FlipFlop, targets) => {
-                    if pulse != HIGH {
+                    if pulse != High {
                         let state = *flip_flop_state.get(name).unwrap_or(&false);
 
-                        let pulse = if state { LOW } else { HIGH };
+                        let pulse = if state { Low } else { High };
                         flip_flop_state.insert(name, !state);
 
                         for target in targets {
-                            queue.push_front((*target, pulse, name));
+                            queue.push_front((target.as_str(), pulse, name));
                         }
                     }
                 }
@@ -389,28 +535,24 @@ fn solvey_solve(modules: &HashMap<&str, (Type, Vec<&str>)>) -> usize {
                     let pulse =
                         update_conjunction_and_trigger(name, source, pulse, &mut conjunction_state);
                     for target in targets {
-                        queue.push_front((*target, pulse, name));
+                        queue.push_front((target.as_str(), pulse, name));
                     }
                 }
             }
         }
     }
-    5
 }
 
-fn extract_conjunction_states<'a>(
-    modules: &HashMap<&'a str, (Type, Vec<&'a str>)>,
-) -> HashMap<&'a str, Vec<(&'a str, Pulse)>> {
+fn extract_conjunction_states(modules: &Modules) -> HashMap<&str, Vec<(&str, Pulse)>> {
     let mut conjunction_state: HashMap<_, _> = modules
         .iter()
         .filter(|(_, (typ, _))| *typ == Conjunction)
-        .map(|(key, _)| (*key, Vec::new()))
+        .map(|(key, _)| (key.as_str(), Vec::new()))
         .collect();
     for (name, (_, targets)) in modules {
         for target in targets {
-            if conjunction_state.contains_key(target) {
-                let entry = conjunction_state.entry(target);
-                entry.and_modify(|val| val.push((*name, LOW)));
+            if let Some(state) = conjunction_state.get_mut(target.as_str()) {
+                state.push((name.as_str(), Low));
             }
         }
     }
@@ -431,18 +573,19 @@ fn update_conjunction_and_trigger(
         if trigger_name == val.0 {
             val.1 = pulse;
         }
-        if val.1 == LOW {
+        if val.1 == Low {
             all_high = false;
         }
     }
 
     if all_high {
-        LOW
+        Low
     } else {
-        HIGH
+        High
     }
 }
 
+#[derive(Clone)]
 struct Tally {
     low_pulses: usize,
     high_pulses: usize,
@@ -457,44 +600,49 @@ impl Tally {
     }
     fn count(&mut self, pulse: Pulse) {
         match pulse {
-            Pulse::LOW => self.low_pulses += 1,
-            Pulse::HIGH => self.high_pulses += 1,
+            Pulse::Low => self.low_pulses += 1,
+            Pulse::High => self.high_pulses += 1,
         }
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 enum Pulse {
-    LOW,
-    HIGH,
+    Low,
+    High,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-enum Type {
+pub enum Type {
     Start,
     FlipFlop,
     Conjunction,
 }
 
-fn parse_module<'map>(line: &'map str, modules: &mut HashMap<&'map str, (Type, Vec<&'map str>)>) {
-    let mut line = line.split("->");
-    let name = line.next().expect("could not parse name").trim();
-    let (typ, name) = if name.starts_with("%") {
-        (FlipFlop, &name[1..])
-    } else if name.starts_with("&") {
-        (Conjunction, &name[1..])
-    } else {
+fn parse_module(input: &str, line: &str, modules: &mut Modules) -> Result<(), ParseError> {
+    let (name, targets) = parse::split_once(input, line, "->")?;
+    let name = name.trim();
+    let (typ, name) = if let Some(name) = name.strip_prefix('%') {
+        (FlipFlop, name)
+    } else if let Some(name) = name.strip_prefix('&') {
+        (Conjunction, name)
+    } else if name == START {
         (Type::Start, START)
+    } else {
+        return Err(ParseError::at(
+            input,
+            name,
+            "`%<name>`, `&<name>` or `broadcaster`",
+        ));
     };
 
-    let targets = line
-        .next()
-        .expect("could not parse targets")
+    let targets = targets
         .split(',')
-        .map(|val| val.trim())
+        .map(|val| val.trim().to_string())
         .collect();
 
-    modules.insert(name, (typ, targets));
+    modules.insert(name.to_string(), (typ, targets));
+    Ok(())
 }
 
 #[test]
@@ -504,6 +652,38 @@ fn test_example() {
 %b -> c
 %c -> inv
 &inv -> a"#;
-    let modules = parse(input.lines());
+    let modules = parse(input).expect("should parse");
     assert_eq!(32000000, solve(&modules));
 }
+
+#[test]
+fn test_output_module() {
+    // `output` has no type of its own, it only receives pulses
+    let input = r#"broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output"#;
+    let modules = parse(input).expect("should parse");
+    assert_eq!(11687500, solve(&modules));
+    // without the conjunctions that feed `rx` there is nothing to combine
+    assert_eq!(Answer::Unsolved, Solution::part2(&modules));
+}
+
+#[test]
+fn test_simulation() {
+    let input = std::fs::read_to_string("examples/day20/example2.txt").expect("fixture exists");
+    let mut machine = Solution::simulation(&Solution::parse(&input).expect("should parse"));
+    assert_eq!(
+        Some(String::from("press 1: 4 low and 4 high pulses, 1x low -> output, 1x high -> output")),
+        machine.step()
+    );
+    for _ in 1..1000 {
+        machine.step();
+    }
+    // the pulses part 1 multiplies, see the puzzle text
+    assert_eq!(
+        vec![("presses", 1000), ("low", 4250), ("high", 2750)],
+        machine.watches()[..3]
+    );
+}
@@ -1,35 +1,39 @@
 use parse_display::{Display, FromStr};
-use std::fs;
-use std::time::Instant;
+
+use crate::day::{Answer, Day};
+use crate::geometry::Point3;
+use crate::parse::ParseError;
 
 #[derive(Display, FromStr, Debug, Copy, Clone)]
 #[display("{from}~{to}")]
-struct Brick {
-    from: Vector,
-    to: Vector,
+/// A brick given by two opposite corner cubes.
+pub struct Brick {
+    pub from: Point3<usize>,
+    pub to: Point3<usize>,
 }
 
-#[derive(Display, FromStr, Debug, Copy, Clone)]
-#[display("{x},{y},{z}")]
-struct Vector {
-    x: usize,
-    y: usize,
-    z: usize,
-}
+pub struct Solution;
 
-pub fn run() {
-    let file = fs::read_to_string("input/day22").expect("Could not open file.");
-    let bricks: Vec<Brick> = file
-        .lines()
-        .map(|str| str.parse::<Brick>().expect("could not parse line"))
-        .collect();
-    let now = Instant::now();
-    let (res_1, res_2) = (5, 5);
-    println!("Solutions took {} µs", now.elapsed().as_micros());
-    println!("Day 22 Solution Part 1: {}", res_1);
-    println!("Day 22 Solution Part 2: {}", res_2);
-}
+impl Day for Solution {
+    const DAY: u8 = 22;
+    const SOLVED: bool = false;
+    type Input = Vec<Brick>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input
+            .lines()
+            .map(|line| {
+                line.parse::<Brick>()
+                    .map_err(|_| ParseError::at(input, line, "`x,y,z~x,y,z`"))
+            })
+            .collect()
+    }
+
+    fn part1(_bricks: &Self::Input) -> Answer {
+        Answer::Unsolved
+    }
 
-fn solve(bricks: &[Brick]) -> usize {
-    todo!()
+    fn part2(_bricks: &Self::Input) -> Answer {
+        Answer::Unsolved
+    }
 }
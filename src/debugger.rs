@@ -0,0 +1,410 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+
+use crate::day::Day;
+
+/// How many earlier states `rewind` can go back to.
+const HISTORY: usize = 10_000;
+/// How many events `continue` takes at most when it is not given a limit.
+const DEFAULT_RUN_LIMIT: usize = 100_000;
+
+pub const HELP: &str = "commands:
+  step [<n>]          take the next <n> events (default: 1)
+  continue [<n>]      take events until a breakpoint hits, the simulation ends or <n> events passed
+  break [<condition>] stop `continue` when <condition> holds, e.g. `load>64` or `low -> rx`;
+                      without a condition, list the breakpoints
+  delete [<n>]        remove breakpoint <n>, or all of them
+  rewind [<n>]        go back <n> events (default: 1)
+  print               show the whole state
+  watch               show the values breakpoints can test
+  help                show this help
+  quit                leave the debugger";
+
+/// A process that advances one event at a time, like a button press or a beam step. Cloning it
+/// has to capture its whole state, `rewind` goes back to earlier clones.
+pub trait Simulation: Clone {
+    /// Takes the next event and describes it, or returns `None` once nothing happens any more.
+    fn step(&mut self) -> Option<String>;
+
+    /// The whole state, for `print`.
+    fn inspect(&self) -> String;
+
+    /// The named numbers breakpoints can test, like `presses` or `load`.
+    fn watches(&self) -> Vec<(&'static str, i64)>;
+}
+
+/// A day whose solver can be stepped through in the debugger.
+pub trait Simulate: Day {
+    type Simulation: Simulation + 'static;
+
+    fn simulation(input: &Self::Input) -> Self::Simulation;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    /// Longer operators first, so `<=` is not taken for `<`.
+    const OPERATORS: [(&'static str, Comparison); 7] = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("!=", Comparison::NotEqual),
+        ("==", Comparison::Equal),
+        ("=", Comparison::Equal),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+
+    fn holds(self, left: i64, right: i64) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+/// A condition that stops `continue` after the event that makes it hold.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Breakpoint {
+    /// A watched value compared with a number, like `load>64`.
+    Watch {
+        name: String,
+        comparison: Comparison,
+        value: i64,
+    },
+    /// A text in the description of an event, like `low -> rx`.
+    Event(String),
+}
+
+impl Breakpoint {
+    /// A comparison if the condition is a value name, an operator and a number, otherwise a text
+    /// to look for in the events.
+    pub fn parse(condition: &str) -> Breakpoint {
+        let comparison = Comparison::OPERATORS
+            .iter()
+            .find_map(|(operator, comparison)| {
+                let (name, value) = condition.split_once(operator)?;
+                let name = name.trim();
+                let is_name = !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|char| char.is_ascii_alphanumeric() || char == '_');
+                let value = value.trim().parse().ok().filter(|_| is_name)?;
+                Some(Breakpoint::Watch {
+                    name: String::from(name),
+                    comparison: *comparison,
+                    value,
+                })
+            });
+        comparison.unwrap_or_else(|| Breakpoint::Event(String::from(condition.trim())))
+    }
+
+    fn hits(&self, event: &str, watches: &[(&'static str, i64)]) -> bool {
+        match self {
+            Breakpoint::Watch {
+                name,
+                comparison,
+                value,
+            } => watches
+                .iter()
+                .any(|(watched, current)| watched == name && comparison.holds(*current, *value)),
+            Breakpoint::Event(text) => event.contains(text.as_str()),
+        }
+    }
+}
+
+/// Steps through a simulation, keeping earlier states around to rewind to.
+pub struct Debugger<S> {
+    current: S,
+    /// Earlier states, oldest first.
+    history: VecDeque<S>,
+    /// How many events lie between the start and the current state.
+    events: usize,
+    breakpoints: Vec<(String, Breakpoint)>,
+}
+
+impl<S: Simulation> Debugger<S> {
+    pub fn new(simulation: S) -> Self {
+        Debugger {
+            current: simulation,
+            history: VecDeque::new(),
+            events: 0,
+            breakpoints: Vec::new(),
+        }
+    }
+
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    /// Takes one event, remembering the state before it. `None` once the simulation ended.
+    fn advance(&mut self) -> Option<String> {
+        let before = self.current.clone();
+        let event = self.current.step()?;
+        if self.history.len() == HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(before);
+        self.events += 1;
+        Some(event)
+    }
+
+    fn step(&mut self, count: usize, out: &mut dyn Write) -> io::Result<()> {
+        for _ in 0..count {
+            match self.advance() {
+                Some(event) => writeln!(out, "#{} {event}", self.events)?,
+                None => return writeln!(out, "the simulation has ended"),
+            }
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, limit: usize, out: &mut dyn Write) -> io::Result<()> {
+        let (mut last, mut ended) = (None, false);
+        for _ in 0..limit {
+            let Some(event) = self.advance() else {
+                ended = true;
+                break;
+            };
+            let watches = self.current.watches();
+            let hit = self
+                .breakpoints
+                .iter()
+                .position(|(_, breakpoint)| breakpoint.hits(&event, &watches));
+            if let Some(idx) = hit {
+                writeln!(out, "#{} {event}", self.events)?;
+                return writeln!(
+                    out,
+                    "breakpoint {} `{}` hit",
+                    idx + 1,
+                    self.breakpoints[idx].0
+                );
+            }
+            last = Some(event);
+        }
+        if let Some(event) = last {
+            writeln!(out, "#{} {event}", self.events)?;
+        }
+        if ended {
+            writeln!(out, "the simulation has ended")
+        } else {
+            writeln!(
+                out,
+                "stopped after {limit} events without hitting a breakpoint"
+            )
+        }
+    }
+
+    fn rewind(&mut self, count: usize, out: &mut dyn Write) -> io::Result<()> {
+        for _ in 0..count {
+            let Some(previous) = self.history.pop_back() else {
+                return writeln!(out, "no earlier state is kept, at event #{}", self.events);
+            };
+            self.current = previous;
+            self.events -= 1;
+        }
+        writeln!(out, "back at event #{}", self.events)
+    }
+
+    fn add_breakpoint(&mut self, condition: &str, out: &mut dyn Write) -> io::Result<()> {
+        let breakpoint = Breakpoint::parse(condition);
+        if let Breakpoint::Watch { name, .. } = &breakpoint {
+            let watches = self.current.watches();
+            if !watches.iter().any(|(watched, _)| watched == name) {
+                let names: Vec<&str> = watches.iter().map(|(name, _)| *name).collect();
+                return writeln!(
+                    out,
+                    "unknown value `{name}`, try one of {}",
+                    names.join(", ")
+                );
+            }
+        }
+        self.breakpoints
+            .push((String::from(condition.trim()), breakpoint));
+        writeln!(
+            out,
+            "breakpoint {} `{}`",
+            self.breakpoints.len(),
+            condition.trim()
+        )
+    }
+
+    fn delete_breakpoint(&mut self, number: Option<usize>, out: &mut dyn Write) -> io::Result<()> {
+        match number {
+            None => {
+                self.breakpoints.clear();
+                writeln!(out, "deleted all breakpoints")
+            }
+            Some(number) if (1..=self.breakpoints.len()).contains(&number) => {
+                let (condition, _) = self.breakpoints.remove(number - 1);
+                writeln!(out, "deleted breakpoint {number} `{condition}`")
+            }
+            Some(number) => writeln!(out, "there is no breakpoint {number}"),
+        }
+    }
+}
+
+/// A debugger with its simulation type erased, so the runner can drive any day.
+pub trait Session {
+    /// Runs one command line and writes what it did. Returns `false` once the user quits.
+    fn command(&mut self, line: &str, out: &mut dyn Write) -> io::Result<bool>;
+}
+
+impl<S: Simulation> Session for Debugger<S> {
+    fn command(&mut self, line: &str, out: &mut dyn Write) -> io::Result<bool> {
+        let (command, argument) = match line.trim().split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line.trim(), ""),
+        };
+        let count = |default: usize| match argument {
+            "" => Some(default),
+            number => number.parse().ok(),
+        };
+        match (command, count(1)) {
+            ("", _) => (),
+            ("step" | "s", Some(count)) => self.step(count, out)?,
+            ("rewind" | "r", Some(count)) => self.rewind(count, out)?,
+            ("continue" | "c", _) => match count(DEFAULT_RUN_LIMIT) {
+                Some(limit) => self.run(limit, out)?,
+                None => writeln!(out, "`{argument}` is not a number")?,
+            },
+            ("break" | "b", _) if argument.is_empty() => {
+                for (idx, (condition, _)) in self.breakpoints.iter().enumerate() {
+                    writeln!(out, "{}: {condition}", idx + 1)?;
+                }
+            }
+            ("break" | "b", _) => self.add_breakpoint(argument, out)?,
+            ("delete" | "d", _) if argument.is_empty() => self.delete_breakpoint(None, out)?,
+            ("delete" | "d", Some(number)) => self.delete_breakpoint(Some(number), out)?,
+            ("print" | "p", _) => {
+                writeln!(out, "event #{}", self.events)?;
+                write!(out, "{}", self.current.inspect())?;
+            }
+            ("watch" | "w", _) => {
+                let watches = self.current.watches();
+                let values: Vec<String> = watches
+                    .iter()
+                    .map(|(name, value)| format!("{name}={value}"))
+                    .collect();
+                writeln!(out, "{}", values.join(" "))?;
+            }
+            ("help" | "h", _) => writeln!(out, "{HELP}")?,
+            ("quit" | "q", _) => return Ok(false),
+            ("step" | "s" | "rewind" | "r" | "delete" | "d", None) => {
+                writeln!(out, "`{argument}` is not a number")?
+            }
+            (command, _) => writeln!(out, "unknown command `{command}`, try `help`")?,
+        }
+        Ok(true)
+    }
+}
+
+/// Prompts for commands on `out` and runs them until `input` ends or the user quits.
+pub fn run_session(
+    session: &mut dyn Session,
+    prompt: &str,
+    input: impl BufRead,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    write!(out, "{prompt}")?;
+    out.flush()?;
+    for line in input.lines() {
+        if !session.command(&line?, out)? {
+            return Ok(());
+        }
+        write!(out, "{prompt}")?;
+        out.flush()?;
+    }
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::debugger::{run_session, Breakpoint, Comparison, Debugger, Session, Simulation};
+
+    /// Counts up to 5 and stops there.
+    #[derive(Clone)]
+    struct Counter(i64);
+
+    impl Simulation for Counter {
+        fn step(&mut self) -> Option<String> {
+            (self.0 < 5).then(|| {
+                self.0 += 1;
+                format!("count {}", self.0)
+            })
+        }
+
+        fn inspect(&self) -> String {
+            format!("{}\n", self.0)
+        }
+
+        fn watches(&self) -> Vec<(&'static str, i64)> {
+            vec![("count", self.0)]
+        }
+    }
+
+    fn session(commands: &str) -> String {
+        let mut debugger = Debugger::new(Counter(0));
+        let mut out = Vec::new();
+        run_session(&mut debugger, "> ", commands.as_bytes(), &mut out).expect("writes to memory");
+        String::from_utf8(out).expect("output is utf-8")
+    }
+
+    #[test]
+    fn test_breakpoint() {
+        assert_eq!(
+            Breakpoint::Watch {
+                name: String::from("load"),
+                comparison: Comparison::GreaterOrEqual,
+                value: 64
+            },
+            Breakpoint::parse("load >= 64")
+        );
+        assert_eq!(
+            Breakpoint::Event(String::from("low -> rx")),
+            Breakpoint::parse("low -> rx")
+        );
+        assert_eq!(
+            Breakpoint::Event(String::from("load>many")),
+            Breakpoint::parse("load>many")
+        );
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(
+            "> #1 count 1\n#2 count 2\n> back at event #1\n> event #1\n1\n> ",
+            session("step 2\nrewind\nprint\nquit\nstep\n")
+        );
+        assert_eq!(
+            "> breakpoint 1 `count=3`\n> #3 count 3\nbreakpoint 1 `count=3` hit\n> \
+             #5 count 5\nthe simulation has ended\n> count=5\n> \n",
+            session("break count=3\ncontinue\ncontinue\nwatch\n")
+        );
+        assert!(session("break speed>3\n").contains("unknown value `speed`, try one of count"));
+        assert!(session("rewind\n").contains("no earlier state is kept, at event #0"));
+        assert!(session("jump\n").contains("unknown command `jump`"));
+        assert!(session("step many\n").contains("`many` is not a number"));
+    }
+
+    #[test]
+    fn test_rewind_restores_the_state() {
+        let mut debugger = Debugger::new(Counter(0));
+        let mut out = Vec::new();
+        debugger.command("continue", &mut out).unwrap();
+        assert_eq!(5, debugger.current().0);
+        debugger.command("rewind 3", &mut out).unwrap();
+        assert_eq!(2, debugger.current().0);
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use crate::day::Day;
+
+/// Highlighted nodes keep the colour of their outline, so the highlight fills them instead.
+const HIGHLIGHT_NODE: &str = r#"penwidth="3", style="filled", fillcolor="gold""#;
+const HIGHLIGHT_EDGE: &str = r#"penwidth="3", color="gold""#;
+
+/// A day whose input is a graph that can be drawn with Graphviz.
+pub trait Dot: Day {
+    /// The graph of `input`, with a highlighted path or subgraph that helps to follow the solver.
+    fn graph(input: &Self::Input) -> Graph;
+}
+
+/// How a node is drawn; `None` keeps the Graphviz default.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct NodeStyle {
+    pub color: Option<&'static str>,
+    pub shape: Option<&'static str>,
+}
+
+impl NodeStyle {
+    pub const PLAIN: NodeStyle = NodeStyle {
+        color: None,
+        shape: None,
+    };
+
+    pub fn color(color: &'static str) -> Self {
+        NodeStyle {
+            color: Some(color),
+            ..NodeStyle::PLAIN
+        }
+    }
+
+    pub fn shape(self, shape: &'static str) -> Self {
+        NodeStyle {
+            shape: Some(shape),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub label: Option<String>,
+}
+
+/// A directed graph. Edges between two highlighted nodes are highlighted as well, so a path or a
+/// subgraph is picked by its nodes alone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph {
+    pub name: String,
+    pub nodes: BTreeMap<String, NodeStyle>,
+    pub edges: Vec<Edge>,
+    pub highlighted: BTreeSet<String>,
+}
+
+impl Graph {
+    pub fn new(name: impl Into<String>) -> Self {
+        Graph {
+            name: name.into(),
+            nodes: BTreeMap::new(),
+            edges: Vec::new(),
+            highlighted: BTreeSet::new(),
+        }
+    }
+
+    /// Adds a node, or restyles it if it is already there.
+    pub fn node(&mut self, id: impl Into<String>, style: NodeStyle) {
+        self.nodes.insert(id.into(), style);
+    }
+
+    /// Adds an edge; nodes that were not added before are added in the plain style.
+    pub fn edge(&mut self, from: impl Into<String>, to: impl Into<String>, label: Option<String>) {
+        let (from, to) = (from.into(), to.into());
+        for id in [&from, &to] {
+            if !self.nodes.contains_key(id) {
+                self.nodes.insert(id.clone(), NodeStyle::PLAIN);
+            }
+        }
+        self.edges.push(Edge { from, to, label });
+    }
+
+    fn is_highlighted(&self, edge: &Edge) -> bool {
+        self.highlighted.contains(&edge.from) && self.highlighted.contains(&edge.to)
+    }
+}
+
+/// Writes `graph` in the DOT language, nodes sorted by name and edges in the order they were added.
+pub fn write_dot(out: &mut impl Write, graph: &Graph) -> io::Result<()> {
+    writeln!(out, "digraph {} {{", quote(&graph.name))?;
+    for (id, style) in &graph.nodes {
+        let mut attributes = Vec::new();
+        if let Some(color) = style.color {
+            attributes.push(format!("color={}", quote(color)));
+        }
+        if let Some(shape) = style.shape {
+            attributes.push(format!("shape={}", quote(shape)));
+        }
+        if graph.highlighted.contains(id) {
+            attributes.push(String::from(HIGHLIGHT_NODE));
+        }
+        writeln!(out, "  {}{};", quote(id), attribute_list(&attributes))?;
+    }
+    for edge in &graph.edges {
+        let mut attributes = Vec::new();
+        if let Some(label) = &edge.label {
+            attributes.push(format!("label={}", quote(label)));
+        }
+        if graph.is_highlighted(edge) {
+            attributes.push(String::from(HIGHLIGHT_EDGE));
+        }
+        writeln!(
+            out,
+            "  {} -> {}{};",
+            quote(&edge.from),
+            quote(&edge.to),
+            attribute_list(&attributes)
+        )?;
+    }
+    writeln!(out, "}}")
+}
+
+fn attribute_list(attributes: &[String]) -> String {
+    if attributes.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", attributes.join(", "))
+    }
+}
+
+/// A DOT string literal; quoting every id keeps names like `11A` valid.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dot::{write_dot, Graph, NodeStyle};
+
+    fn dot(graph: &Graph) -> String {
+        let mut out = Vec::new();
+        write_dot(&mut out, graph).expect("writes to memory");
+        String::from_utf8(out).expect("dot is utf-8")
+    }
+
+    #[test]
+    fn test_write_dot() {
+        let mut graph = Graph::new("test");
+        graph.node("b", NodeStyle::color("red").shape("box"));
+        graph.edge("a", "b", Some(String::from("x<5")));
+        graph.edge("b", "c", None);
+        graph.edge("c", "a", Some(String::from("say \"hi\"")));
+        graph.highlighted.extend(["a", "b"].map(String::from));
+        assert_eq!(
+            r#"digraph "test" {
+  "a" [penwidth="3", style="filled", fillcolor="gold"];
+  "b" [color="red", shape="box", penwidth="3", style="filled", fillcolor="gold"];
+  "c";
+  "a" -> "b" [label="x<5", penwidth="3", color="gold"];
+  "b" -> "c";
+  "c" -> "a" [label="say \"hi\""];
+}
+"#,
+            dot(&graph)
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use crate::answers::{AnswerKey, AnswerStore, Verdict};
+use crate::day::{Answer, Registration};
+use crate::pool;
+
+pub const EXAMPLES_DIR: &str = "examples";
+/// Expected answers of all fixtures, in the answers store format with the fixture name as input.
+pub const EXPECTED_FILE: &str = "expected.txt";
+
+/// An example input at `<examples>/dayNN/<name>.txt`.
+#[derive(Debug, PartialEq)]
+pub struct Fixture {
+    pub day: u8,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    /// The day does not solve this part yet, so there is nothing to compare.
+    Unsolved,
+    Fail(String),
+}
+
+#[derive(Debug)]
+pub struct CheckResult {
+    pub fixture: String,
+    pub part: Option<u8>,
+    pub status: Status,
+}
+
+impl Display for CheckResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.fixture)?;
+        if let Some(part) = self.part {
+            write!(f, " part {part}")?;
+        }
+        match &self.status {
+            Status::Pass => write!(f, ": pass"),
+            Status::Unsolved => write!(f, ": unsolved"),
+            Status::Fail(reason) => write!(f, ": FAIL {reason}"),
+        }
+    }
+}
+
+/// Lists all fixtures below `dir`, ordered by day and name.
+pub fn find_fixtures(dir: &Path) -> Result<Vec<Fixture>, String> {
+    let read_dir = |dir: &Path| -> Result<Vec<PathBuf>, String> {
+        fs::read_dir(dir)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect()
+            })
+            .map_err(|err| format!("Could not list {}: {err}", dir.display()))
+    };
+    let mut fixtures = Vec::new();
+    for day_dir in read_dir(dir)? {
+        let day = day_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("day"))
+            .and_then(|day| day.parse::<u8>().ok());
+        let Some(day) = day else {
+            continue;
+        };
+        for path in read_dir(&day_dir)? {
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                let name = path
+                    .file_stem()
+                    .map_or(String::new(), |name| name.to_string_lossy().into_owned());
+                fixtures.push(Fixture { day, name, path });
+            }
+        }
+    }
+    fixtures.sort_by(|a, b| (a.day, &a.name).cmp(&(b.day, &b.name)));
+    Ok(fixtures)
+}
+
+/// Runs the selected days against their fixtures below `dir`, solving only the parts that have
+/// an expected answer. Expected answers without a fixture are reported as failures as well.
+pub fn check_examples(selected: &[&Registration], dir: &Path) -> Result<Vec<CheckResult>, String> {
+    let expected = AnswerStore::load(&dir.join(EXPECTED_FILE)).map_err(|err| err.to_string())?;
+    let fixtures: Vec<Fixture> = find_fixtures(dir)?
+        .into_iter()
+        .filter(|fixture| selected.iter().any(|reg| reg.day == fixture.day))
+        .collect();
+
+    let mut results = Vec::new();
+    for key in expected.keys() {
+        let found = fixtures
+            .iter()
+            .any(|fixture| fixture.day == key.day && fixture.name == key.input);
+        if !found && selected.iter().any(|reg| reg.day == key.day) {
+            results.push(CheckResult {
+                fixture: format!("day{:02}/{}.txt", key.day, key.input),
+                part: Some(key.part),
+                status: Status::Fail(String::from("fixture is missing")),
+            });
+        }
+    }
+    for fixture in &fixtures {
+        let registration = selected
+            .iter()
+            .find(|reg| reg.day == fixture.day)
+            .expect("fixtures are filtered by day");
+        results.extend(check_fixture(registration, fixture, &expected));
+    }
+    Ok(results)
+}
+
+fn check_fixture(
+    registration: &Registration,
+    fixture: &Fixture,
+    expected: &AnswerStore,
+) -> Vec<CheckResult> {
+    let location = fixture.path.display().to_string();
+    let key = |part| AnswerKey {
+        day: fixture.day,
+        part,
+        input: fixture.name.clone(),
+    };
+    let failure = |reason: String| {
+        vec![CheckResult {
+            fixture: location.clone(),
+            part: None,
+            status: Status::Fail(reason),
+        }]
+    };
+
+    let parts: Vec<u8> = [1, 2]
+        .into_iter()
+        .filter(|part| expected.get(&key(*part)).is_some())
+        .collect();
+    if parts.is_empty() {
+        return failure(format!("no expected answers in {EXPECTED_FILE}"));
+    }
+    let input = match fs::read_to_string(&fixture.path) {
+        Ok(input) => input,
+        Err(err) => return failure(format!("could not read: {err}")),
+    };
+    let report =
+        match panic::catch_unwind(AssertUnwindSafe(|| registration.run_parts(&input, &parts))) {
+            Ok(Ok(report)) => report,
+            Ok(Err(err)) => return failure(err.with_file(&location).to_string()),
+            Err(payload) => {
+                return failure(format!(
+                    "panicked: {}",
+                    pool::panic_message(payload.as_ref())
+                ))
+            }
+        };
+
+    report
+        .parts
+        .into_iter()
+        .map(|part| {
+            let status = match (&part.answer, expected.verify(&key(part.part), &part.answer)) {
+                (Answer::Unsolved, _) => Status::Unsolved,
+                (_, Verdict::Pass) => Status::Pass,
+                (_, Verdict::Fail { expected }) => {
+                    Status::Fail(format!("expected {expected}, got {}", part.answer))
+                }
+                (_, Verdict::Missing) => unreachable!("only parts with expected answers are run"),
+            };
+            CheckResult {
+                fixture: location.clone(),
+                part: Some(part.part),
+                status,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::examples::{check_examples, find_fixtures, Status, EXAMPLES_DIR};
+
+    #[test]
+    fn test_find_fixtures() {
+        let fixtures = find_fixtures(Path::new(EXAMPLES_DIR)).expect("examples should exist");
+        let day08: Vec<&str> = fixtures
+            .iter()
+            .filter(|fixture| fixture.day == 8)
+            .map(|fixture| fixture.name.as_str())
+            .collect();
+        assert_eq!(vec!["example1", "example2", "example3"], day08);
+    }
+
+    /// Every registered day against every fixture in `examples/`.
+    #[test]
+    fn test_examples() {
+        let registry = crate::registry();
+        let selected: Vec<_> = registry.iter().collect();
+        let results =
+            check_examples(&selected, Path::new(EXAMPLES_DIR)).expect("examples should load");
+        let failures: Vec<String> = results
+            .iter()
+            .filter(|result| matches!(result.status, Status::Fail(_)))
+            .map(|result| result.to_string())
+            .collect();
+        assert!(
+            failures.is_empty(),
+            "failing examples:\n{}",
+            failures.join("\n")
+        );
+        assert!(results.iter().any(|result| result.status == Status::Pass));
+    }
+}
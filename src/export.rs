@@ -0,0 +1,179 @@
+use std::io::{self, Write};
+
+use crate::day::Day;
+use crate::grid::Grid;
+
+/// Side length in pixels that every cell gets in an exported image.
+pub const SCALE: usize = 4;
+pub const DEFAULT_IMAGE_DIR: &str = "images";
+
+/// A day that can turn its input and results into images.
+pub trait Export: Day {
+    fn export(input: &Self::Input) -> Vec<Image>;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(220, 30, 30);
+
+    /// A colour given as `0xRRGGBB`.
+    pub fn from_hex(hex: u32) -> Self {
+        Rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+    }
+
+    /// A shade of grey, from black at 0 to white at 1.
+    pub fn grey(level: f64) -> Self {
+        let value = (level.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Rgb(value, value, value)
+    }
+
+    /// A heat map colour, from black at 0 over red and yellow to white at 1.
+    pub fn heat(level: f64) -> Self {
+        let scaled = level.clamp(0.0, 1.0) * 3.0;
+        let channel = |offset: f64| ((scaled - offset).clamp(0.0, 1.0) * 255.0).round() as u8;
+        Rgb(channel(0.0), channel(1.0), channel(2.0))
+    }
+}
+
+/// A named picture with one colour per cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub name: String,
+    pub pixels: Grid<Rgb>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum ImageFormat {
+    #[default]
+    Svg,
+    /// The plain (ASCII) variant of the netpbm colour format.
+    Ppm,
+}
+
+impl ImageFormat {
+    pub fn from_name(name: &str) -> Option<ImageFormat> {
+        match name {
+            "svg" => Some(ImageFormat::Svg),
+            "ppm" => Some(ImageFormat::Ppm),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Svg => "svg",
+            ImageFormat::Ppm => "ppm",
+        }
+    }
+}
+
+pub fn write_image(out: &mut impl Write, image: &Image, format: ImageFormat) -> io::Result<()> {
+    match format {
+        ImageFormat::Svg => write_svg(out, image),
+        ImageFormat::Ppm => write_ppm(out, image),
+    }
+}
+
+/// One rectangle per run of equally coloured cells in a row, on a viewbox of one unit per cell.
+pub fn write_svg(out: &mut impl Write, image: &Image) -> io::Result<()> {
+    let (width, height) = (image.pixels.width(), image.pixels.height());
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#,
+        width * SCALE,
+        height * SCALE
+    )?;
+    writeln!(out, "<title>{}</title>", image.name)?;
+    for (y, row) in image.pixels.rows().enumerate() {
+        let mut x = 0;
+        for run in row.chunk_by(|a, b| a == b) {
+            let Rgb(red, green, blue) = run[0];
+            writeln!(
+                out,
+                r##"<rect x="{x}" y="{y}" width="{}" height="1" fill="#{red:02x}{green:02x}{blue:02x}"/>"##,
+                run.len()
+            )?;
+            x += run.len();
+        }
+    }
+    writeln!(out, "</svg>")
+}
+
+/// Every cell as a square of [`SCALE`] by [`SCALE`] pixels, one pixel per line.
+pub fn write_ppm(out: &mut impl Write, image: &Image) -> io::Result<()> {
+    let (width, height) = (image.pixels.width(), image.pixels.height());
+    writeln!(
+        out,
+        "P3\n# {}\n{} {}\n255",
+        image.name,
+        width * SCALE,
+        height * SCALE
+    )?;
+    for row in image.pixels.rows() {
+        for _ in 0..SCALE {
+            for Rgb(red, green, blue) in row {
+                for _ in 0..SCALE {
+                    writeln!(out, "{red} {green} {blue}")?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::export::{write_ppm, write_svg, Image, Rgb, SCALE};
+    use crate::grid::Grid;
+
+    fn image() -> Image {
+        Image {
+            name: String::from("test"),
+            pixels: Grid::from_cells(3, 1, vec![Rgb::BLACK, Rgb::BLACK, Rgb::from_hex(0x70c710)]),
+        }
+    }
+
+    #[test]
+    fn test_colours() {
+        assert_eq!(Rgb(0x70, 0xc7, 0x10), Rgb::from_hex(0x70c710));
+        assert_eq!(Rgb::BLACK, Rgb::heat(0.0));
+        assert_eq!(Rgb(255, 255, 0), Rgb::heat(2.0 / 3.0));
+        assert_eq!(Rgb::WHITE, Rgb::heat(1.5));
+        assert_eq!(Rgb(128, 128, 128), Rgb::grey(0.5));
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut out = Vec::new();
+        write_svg(&mut out, &image()).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="12" height="4""#));
+        let rects: Vec<&str> = svg
+            .lines()
+            .filter(|line| line.starts_with("<rect"))
+            .collect();
+        assert_eq!(
+            vec![
+                r##"<rect x="0" y="0" width="2" height="1" fill="#000000"/>"##,
+                r##"<rect x="2" y="0" width="1" height="1" fill="#70c710"/>"##
+            ],
+            rects
+        );
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_ppm() {
+        let mut out = Vec::new();
+        write_ppm(&mut out, &image()).unwrap();
+        let ppm = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = ppm.lines().collect();
+        assert_eq!(vec!["P3", "# test", "12 4", "255"], lines[..4]);
+        assert_eq!(4 + 3 * SCALE * SCALE, lines.len());
+        assert_eq!("112 199 16", lines[4 + 2 * SCALE]);
+    }
+}
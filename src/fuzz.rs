@@ -0,0 +1,320 @@
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::day::{Answer, Day};
+use crate::pool;
+
+/// Upper bound on the inputs tried while shrinking a single failure.
+const SHRINK_ATTEMPTS: usize = 5_000;
+
+/// A day whose fast solver can be checked against a slow reference on random inputs.
+pub trait Fuzz: Day {
+    /// The part whose solver is checked.
+    const PART: u8;
+
+    /// A random input that holds all the guarantees of the puzzle; `size` scales its length.
+    fn generate(rng: &mut Rng, size: usize) -> String;
+
+    /// Slow but straightforward answer of [`Fuzz::PART`], or `None` if the input breaks one of the
+    /// puzzle's guarantees (which happens while shrinking).
+    fn reference(input: &Self::Input) -> Option<Answer>;
+}
+
+/// SplitMix64: tiny, and reproducible from a single seed.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty. The modulo bias does not matter here.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "cannot pick from an empty range");
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len() as u64) as usize]
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FuzzConfig {
+    pub runs: usize,
+    /// Largest `size` passed to the generators; each run picks one between 1 and this.
+    pub size: usize,
+    pub seed: u64,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        FuzzConfig {
+            runs: 100,
+            size: 10,
+            seed: 0,
+        }
+    }
+}
+
+/// How the fast solver did on one input.
+#[derive(Debug, PartialEq)]
+pub enum Check {
+    /// The input does not parse or breaks a guarantee of the puzzle.
+    Invalid,
+    Agree,
+    Mismatch {
+        fast: String,
+        reference: Answer,
+    },
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    pub seed: u64,
+    pub input: String,
+    pub shrunk: String,
+    /// The fast answer on the shrunk input, or why the solver panicked.
+    pub fast: String,
+    pub reference: Answer,
+}
+
+#[derive(Debug)]
+pub struct FuzzReport {
+    pub runs: usize,
+    pub invalid: usize,
+    pub failure: Option<Failure>,
+}
+
+/// The generator and checker of a [`Fuzz`] day, which its registration carries, see
+/// [`crate::day::Registration::with_fuzz`].
+#[derive(Copy, Clone)]
+pub struct Target {
+    pub part: u8,
+    generate: fn(&mut Rng, usize) -> String,
+    check: fn(&str) -> Check,
+}
+
+impl Target {
+    pub fn of<D: Fuzz>() -> Self {
+        Target {
+            part: D::PART,
+            generate: D::generate,
+            check: check::<D>,
+        }
+    }
+
+    pub fn generate(&self, seed: u64, size: usize) -> String {
+        (self.generate)(&mut Rng::new(seed), size)
+    }
+
+    pub fn check(&self, input: &str) -> Check {
+        (self.check)(input)
+    }
+
+    /// Checks `config.runs` random inputs, stopping at the first mismatch and shrinking it.
+    /// Run `i` uses the seed `config.seed + i`, so a failure can be reproduced on its own.
+    pub fn fuzz(&self, config: &FuzzConfig) -> FuzzReport {
+        let mut invalid = 0;
+        for run in 0..config.runs {
+            let seed = config.seed.wrapping_add(run as u64);
+            let size = Rng::new(seed).range(1..config.size.max(1) as u64 + 1) as usize;
+            let input = self.generate(seed, size);
+            match self.check(&input) {
+                Check::Agree => (),
+                Check::Invalid => invalid += 1,
+                Check::Mismatch { .. } => {
+                    let shrunk = shrink(&input, |candidate| {
+                        matches!(self.check(candidate), Check::Mismatch { .. })
+                    });
+                    let Check::Mismatch { fast, reference } = self.check(&shrunk) else {
+                        unreachable!("shrinking keeps the input failing");
+                    };
+                    return FuzzReport {
+                        runs: run + 1,
+                        invalid,
+                        failure: Some(Failure {
+                            seed,
+                            input,
+                            shrunk,
+                            fast,
+                            reference,
+                        }),
+                    };
+                }
+            }
+        }
+        FuzzReport {
+            runs: config.runs,
+            invalid,
+            failure: None,
+        }
+    }
+}
+
+fn check<D: Fuzz>(input: &str) -> Check {
+    let Ok(parsed) = D::parse(input) else {
+        return Check::Invalid;
+    };
+    let reference = match panic::catch_unwind(AssertUnwindSafe(|| D::reference(&parsed))) {
+        Ok(Some(reference)) => reference,
+        _ => return Check::Invalid,
+    };
+    let solve = if D::PART == 1 { D::part1 } else { D::part2 };
+    match panic::catch_unwind(AssertUnwindSafe(|| solve(&parsed))) {
+        Ok(fast) if fast == reference => Check::Agree,
+        Ok(fast) => Check::Mismatch {
+            fast: fast.to_string(),
+            reference,
+        },
+        Err(payload) => Check::Mismatch {
+            fast: format!("panicked: {}", pool::panic_message(payload.as_ref())),
+            reference,
+        },
+    }
+}
+
+/// Greedily drops chunks of lines and lowers numbers for as long as `fails` holds.
+pub fn shrink(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut lines: Vec<String> = input.lines().map(String::from).collect();
+    let mut attempts = 0;
+    let mut fails = |lines: &[String]| {
+        attempts += 1;
+        attempts <= SHRINK_ATTEMPTS && fails(&lines.join("\n"))
+    };
+
+    let mut progress = true;
+    while progress {
+        progress = false;
+
+        let mut chunk = lines.len().div_ceil(2);
+        while chunk > 0 {
+            let mut start = 0;
+            while start < lines.len() {
+                let mut candidate = lines.clone();
+                candidate.drain(start..(start + chunk).min(lines.len()));
+                if fails(&candidate) {
+                    lines = candidate;
+                    progress = true;
+                } else {
+                    start += chunk;
+                }
+            }
+            chunk /= 2;
+        }
+
+        for idx in 0..lines.len() {
+            let mut offset = 0;
+            while let Some((start, end)) = next_number(&lines[idx], offset) {
+                let value: u64 = lines[idx][start..end].parse().unwrap_or(0);
+                let smaller = [0, value / 2, value.saturating_sub(1)]
+                    .into_iter()
+                    .filter(|smaller| *smaller < value)
+                    .find_map(|smaller| {
+                        let mut candidate = lines.clone();
+                        candidate[idx].replace_range(start..end, &smaller.to_string());
+                        fails(&candidate).then_some(candidate)
+                    });
+                match smaller {
+                    Some(candidate) => {
+                        lines = candidate;
+                        progress = true;
+                    }
+                    None => offset = end,
+                }
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// Byte range of the first run of decimal digits at or after `offset`.
+fn next_number(line: &str, offset: usize) -> Option<(usize, usize)> {
+    let start = offset + line[offset..].find(|c: char| c.is_ascii_digit())?;
+    let end = line[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(line.len(), |len| start + len);
+    Some((start, end))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::fuzz::{shrink, Check, FuzzConfig, Rng, Target};
+    use crate::registry;
+
+    fn targets() -> Vec<(u8, Target)> {
+        registry()
+            .iter()
+            .filter_map(|registration| Some((registration.day, registration.fuzz?)))
+            .collect()
+    }
+
+    #[test]
+    fn test_rng() {
+        let mut first = Rng::new(7);
+        let mut second = Rng::new(7);
+        let values: Vec<u64> = (0..100).map(|_| first.range(3..9)).collect();
+        assert!(values.iter().all(|value| (3..9).contains(value)));
+        assert_eq!(
+            values,
+            (0..100).map(|_| second.range(3..9)).collect::<Vec<_>>()
+        );
+        assert_ne!(Rng::new(8).next_u64(), Rng::new(7).next_u64());
+    }
+
+    #[test]
+    fn test_shrink() {
+        // fails whenever a line holds a number of at least 12
+        let input = "a 3\nb 40\nc 17 5\nd 2";
+        let shrunk = shrink(input, |candidate| {
+            candidate
+                .split(|c: char| !c.is_ascii_digit())
+                .any(|number| number.parse::<u32>().is_ok_and(|number| number >= 12))
+        });
+        assert_eq!("c 12 0", shrunk);
+    }
+
+    #[test]
+    fn test_generators() {
+        for (day, target) in targets() {
+            for seed in 0..20 {
+                let input = target.generate(seed, 8);
+                assert_ne!(
+                    Check::Invalid,
+                    target.check(&input),
+                    "day{day:02} generated an invalid input for seed {seed}:\n{input}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fuzz_is_reproducible() {
+        let config = FuzzConfig {
+            runs: 5,
+            size: 4,
+            seed: 42,
+        };
+        for (_, target) in targets() {
+            let first = target.fuzz(&config);
+            let second = target.fuzz(&config);
+            assert_eq!(first.runs, second.runs);
+            assert_eq!(
+                first.failure.map(|failure| failure.shrunk),
+                second.failure.map(|failure| failure.shrunk)
+            );
+        }
+    }
+}
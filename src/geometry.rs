@@ -0,0 +1,337 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Sub};
+
+use num::{CheckedAdd, CheckedSub, PrimInt};
+use parse_display::{Display, FromStr};
+
+/// One of the eight compass directions. `y` grows towards the south, as it does for rows in a
+/// puzzle input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+use Direction::*;
+
+impl Direction {
+    /// The four cardinal directions, clockwise from north.
+    pub const CARDINAL: [Direction; 4] = [North, East, South, West];
+    /// All eight directions, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest,
+    ];
+
+    /// The step `(dx, dy)` one move in this direction takes.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            North => (0, -1),
+            NorthEast => (1, -1),
+            East => (1, 0),
+            SouthEast => (1, 1),
+            South => (0, 1),
+            SouthWest => (-1, 1),
+            West => (-1, 0),
+            NorthWest => (-1, -1),
+        }
+    }
+
+    /// A quarter turn counterclockwise.
+    pub fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    /// A quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn reverse(self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_cardinal(self) -> bool {
+        Self::CARDINAL.contains(&self)
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, North | South)
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, East | West)
+    }
+
+    /// Turns clockwise by `eighths` eighths of a full turn.
+    fn rotate(self, eighths: usize) -> Self {
+        let idx = Self::ALL
+            .iter()
+            .position(|direction| *direction == self)
+            .expect("all directions are listed");
+        Self::ALL[(idx + eighths) % Self::ALL.len()]
+    }
+}
+
+/// A point in the plane, or the vector between two of them.
+#[derive(Display, FromStr, Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[display("{x},{y}")]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T: Copy + CheckedAdd + CheckedSub> Point2<T> {
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Point2::new(
+            self.x.checked_add(&other.x)?,
+            self.y.checked_add(&other.y)?,
+        ))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(Point2::new(
+            self.x.checked_sub(&other.x)?,
+            self.y.checked_sub(&other.y)?,
+        ))
+    }
+
+    /// The point `distance` moves in `direction` away, unless a coordinate over- or underflows.
+    /// Diagonal moves change both coordinates by `distance`.
+    pub fn step(self, direction: Direction, distance: T) -> Option<Self> {
+        let (dx, dy) = direction.delta();
+        Some(Point2::new(
+            shift(self.x, dx, distance)?,
+            shift(self.y, dy, distance)?,
+        ))
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Point2<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        distance(self.x, other.x) + distance(self.y, other.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2::new(x, y)
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(point: Point2<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+/// A point in space, or the vector between two of them.
+#[derive(Display, FromStr, Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[display("{x},{y},{z}")]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Copy + CheckedAdd + CheckedSub> Point3<T> {
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Point3::new(
+            self.x.checked_add(&other.x)?,
+            self.y.checked_add(&other.y)?,
+            self.z.checked_add(&other.z)?,
+        ))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(Point3::new(
+            self.x.checked_sub(&other.x)?,
+            self.y.checked_sub(&other.y)?,
+            self.z.checked_sub(&other.z)?,
+        ))
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Point3<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        distance(self.x, other.x) + distance(self.y, other.y) + distance(self.z, other.z)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+/// The smallest axis-aligned rectangle holding some points; both corners are inside.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BoundingBox<T> {
+    pub min: Point2<T>,
+    pub max: Point2<T>,
+}
+
+impl<T: PrimInt> BoundingBox<T> {
+    /// The box around all `points`, or `None` if there are none.
+    pub fn of(points: impl IntoIterator<Item = Point2<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = BoundingBox {
+            min: first,
+            max: first,
+        };
+        points.for_each(|point| bounds.include(point));
+        Some(bounds)
+    }
+
+    /// Grows the box just enough to hold `point`.
+    pub fn include(&mut self, point: Point2<T>) {
+        self.min = Point2::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point2::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    pub fn contains(&self, point: Point2<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// The box with `margin` more room on every side.
+    pub fn expand(self, margin: T) -> Self {
+        BoundingBox {
+            min: Point2::new(self.min.x - margin, self.min.y - margin),
+            max: Point2::new(self.max.x + margin, self.max.y + margin),
+        }
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::one()
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::one()
+    }
+
+    /// All points in the box, row after row.
+    pub fn points(&self) -> impl Iterator<Item = Point2<T>> {
+        let (min, max) = (self.min, self.max);
+        num::range_inclusive(min.y, max.y)
+            .flat_map(move |y| num::range_inclusive(min.x, max.x).map(move |x| Point2::new(x, y)))
+    }
+}
+
+fn shift<T: CheckedAdd + CheckedSub>(value: T, sign: isize, distance: T) -> Option<T> {
+    match sign.cmp(&0) {
+        Ordering::Less => value.checked_sub(&distance),
+        Ordering::Equal => Some(value),
+        Ordering::Greater => value.checked_add(&distance),
+    }
+}
+
+fn distance<T: Ord + Sub<Output = T>>(a: T, b: T) -> T {
+    if a < b {
+        b - a
+    } else {
+        a - b
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry::Direction::*;
+    use crate::geometry::{BoundingBox, Direction, Point2, Point3};
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(West, North.turn_left());
+        assert_eq!(East, North.turn_right());
+        assert_eq!(SouthWest, NorthEast.reverse());
+        assert_eq!(NorthWest, SouthWest.turn_right());
+        for direction in Direction::ALL {
+            assert_eq!(direction, direction.turn_left().turn_right());
+            let (dx, dy) = direction.delta();
+            assert_eq!((-dx, -dy), direction.reverse().delta());
+        }
+        assert_eq!(4, Direction::ALL.iter().filter(|d| d.is_cardinal()).count());
+    }
+
+    #[test]
+    fn test_points() {
+        let origin = Point2::<usize>::new(0, 0);
+        assert_eq!(None, origin.step(North, 1));
+        assert_eq!(Some(Point2::new(3, 3)), origin.step(SouthEast, 3));
+        assert_eq!(None, origin.checked_sub(Point2::new(1, 0)));
+        assert_eq!(
+            Some(Point2::new(2, 1)),
+            origin.checked_add(Point2::new(2, 1))
+        );
+        assert_eq!(7, Point2::new(4, 1).manhattan(Point2::new(1, 5)));
+        assert_eq!(Point2::new(-3, 4), Point2::new(1, 5) - Point2::new(4, 1));
+        assert_eq!(Point2::new(2, -1), Point2::from((2, -1)));
+
+        let corner: Point3<usize> = "1,0,12".parse().expect("should parse");
+        assert_eq!(Point3::new(1, 0, 12), corner);
+        assert_eq!("1,0,12", corner.to_string());
+        assert_eq!(15, corner.manhattan(Point3::new(0, 2, 0)));
+        assert_eq!(None, corner.checked_sub(Point3::new(0, 1, 0)));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        assert_eq!(None, BoundingBox::<i32>::of([]));
+        let bounds = BoundingBox::of([Point2::new(2, -1), Point2::new(-1, 3), Point2::new(0, 0)])
+            .expect("there are points");
+        assert_eq!(
+            (Point2::new(-1, -1), Point2::new(2, 3)),
+            (bounds.min, bounds.max)
+        );
+        assert_eq!((4, 5), (bounds.width(), bounds.height()));
+        assert!(bounds.contains(Point2::new(2, 3)));
+        assert!(!bounds.contains(Point2::new(3, 0)));
+        let wider = bounds.expand(1);
+        assert_eq!((6, 7), (wider.width(), wider.height()));
+        assert_eq!(20, bounds.points().count());
+        assert_eq!(Some(Point2::new(-1, -1)), bounds.points().next());
+    }
+}
@@ -0,0 +1,285 @@
+use std::ops::{Index, IndexMut};
+
+use crate::parse::ParseError;
+
+/// The four orthogonal steps, clockwise from north, as `(dx, dy)` with `y` growing downwards.
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// A rectangular map of cells, addressed by `(x, y)` with `(0, 0)` in the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its cells, row after row.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(width * height, cells.len(), "cells do not fill the grid");
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses one row per line, turning every character into a cell with `tile`. Lines may end in
+    /// `\r\n`. Characters that `tile` rejects and rows of a different width are reported as parse
+    /// errors; `expected` describes the characters that `tile` accepts.
+    pub fn parse(
+        input: &str,
+        expected: &str,
+        mut tile: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let rows: Vec<&str> = input
+            .lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err(ParseError::at(input, input, "at least one row of tiles"));
+        }
+
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for row in &rows {
+            let mut count = 0;
+            for (idx, char) in row.char_indices() {
+                if count == width {
+                    return Err(ParseError::at(
+                        input,
+                        &row[idx..],
+                        format!("{width} tiles per row"),
+                    ));
+                }
+                let cell =
+                    tile(char).ok_or_else(|| ParseError::at(input, &row[idx..], expected))?;
+                cells.push(cell);
+                count += 1;
+            }
+            if count < width {
+                return Err(ParseError::at(
+                    input,
+                    &row[row.len()..],
+                    format!("{width} tiles per row"),
+                ));
+            }
+        }
+        Ok(Grid::from_cells(width, rows.len(), cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// All cells, row after row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, (x, y): (usize, usize)) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// The position `(dx, dy)` away from `position`, unless that leaves the grid.
+    pub fn offset(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(dx).filter(|x| *x < self.width)?;
+        let y = y.checked_add_signed(dy).filter(|y| *y < self.height)?;
+        Some((x, y))
+    }
+
+    /// The orthogonal neighbours of `position` that lie inside the grid, clockwise from north.
+    pub fn neighbours(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |delta| self.offset(position, *delta))
+    }
+
+    /// All positions, row after row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |idx| (idx % width, idx / width))
+    }
+
+    /// All cells with their positions, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// All cells with their positions, row after row, to change them in place.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut T)> {
+        let width = self.width;
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(idx, cell)| ((idx % width, idx / width), cell))
+    }
+
+    /// The position of the first cell, row after row, that matches `predicate`.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    /// The cells of column `x`, top to bottom.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(x < self.width, "column {x} is outside the grid");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn map<U>(&self, convert: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_cells(
+            self.width,
+            self.height,
+            self.cells.iter().map(convert).collect(),
+        )
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `width` by `height` grid filled with `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid::from_cells(width, height, vec![fill; width * height])
+    }
+
+    /// Mirrors the grid along its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Self {
+        let cells = (0..self.width)
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+        Grid::from_cells(self.height, self.width, cells)
+    }
+
+    /// Rotates the grid a quarter turn clockwise, so the west edge ends up north.
+    pub fn rotate_clockwise(&self) -> Self {
+        let cells = (0..self.width)
+            .flat_map(|x| self.column(x).rev().cloned())
+            .collect();
+        Grid::from_cells(self.height, self.width, cells)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: (usize, usize)) -> &T {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{position:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, position: (usize, usize)) -> &mut T {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{position:?} is outside the grid"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grid::Grid;
+
+    fn parse(input: &str) -> Grid<char> {
+        Grid::parse(input, "a letter", |char| {
+            char.is_ascii_lowercase().then_some(char)
+        })
+        .expect("should parse")
+    }
+
+    fn text(grid: &Grid<char>) -> Vec<String> {
+        grid.rows().map(|row| row.iter().collect()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = parse("abc\r\ndef\r\n");
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(parse("abc\ndef"), grid);
+        assert_eq!(Some(&'f'), grid.get((2, 1)));
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!('d', grid[(0, 1)]);
+
+        let letters = |input| {
+            Grid::parse(input, "a letter", |c: char| {
+                c.is_ascii_lowercase().then_some(c)
+            })
+        };
+        let err = letters("abc\ndXf").unwrap_err();
+        assert_eq!(
+            (2, 2, "a letter"),
+            (err.line, err.column, err.expected.as_str())
+        );
+        let err = letters("abc\nde").unwrap_err();
+        assert_eq!(
+            (2, 3, "3 tiles per row"),
+            (err.line, err.column, err.expected.as_str())
+        );
+        let err = letters("abc\ndefg").unwrap_err();
+        assert_eq!((2, 4), (err.line, err.column));
+        assert!(letters("").is_err());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = parse("abc\ndef\nghi");
+        assert_eq!(
+            vec![(1, 0), (0, 1)],
+            grid.neighbours((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(4, grid.neighbours((1, 1)).count());
+        assert_eq!(Some((2, 0)), grid.offset((0, 2), (2, -2)));
+        assert_eq!(None, grid.offset((2, 2), (1, 0)));
+        assert_eq!(Some((1, 2)), grid.find(|cell| *cell == 'h'));
+    }
+
+    #[test]
+    fn test_views() {
+        let mut grid = parse("abc\ndef");
+        assert_eq!(&['d', 'e', 'f'], grid.row(1));
+        assert_eq!(vec![&'b', &'e'], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(3, grid.columns().count());
+        grid[(1, 0)] = 'x';
+        assert_eq!(vec!["axc", "def"], text(&grid));
+        assert_eq!(
+            Grid::from_cells(3, 2, vec![false, true, false, false, false, false]),
+            grid.map(|cell| *cell == 'x')
+        );
+    }
+
+    #[test]
+    fn test_rotate_and_transpose() {
+        let grid = parse("abc\ndef");
+        assert_eq!(vec!["ad", "be", "cf"], text(&grid.transpose()));
+        assert_eq!(vec!["da", "eb", "fc"], text(&grid.rotate_clockwise()));
+        let full_turn = (0..4).fold(grid.clone(), |grid, _| grid.rotate_clockwise());
+        assert_eq!(grid, full_turn);
+    }
+}
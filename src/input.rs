@@ -0,0 +1,225 @@
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+
+pub const DEFAULT_INPUT_DIR: &str = "input";
+
+/// Where the puzzle input of a day is read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    /// `<dir>/dayNN`, one file per day. A day with several inputs keeps them as
+    /// `<dir>/dayNN/<name>.txt` instead, see [`InputSource::inputs`].
+    Dir(PathBuf),
+    /// A single file, used for whichever day is run.
+    File(PathBuf),
+    Stdin,
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Dir(PathBuf::from(DEFAULT_INPUT_DIR))
+    }
+}
+
+impl InputSource {
+    /// Whether the source only makes sense for a single day.
+    pub fn is_single(&self) -> bool {
+        !matches!(self, InputSource::Dir(_))
+    }
+
+    pub fn read(&self, day_name: &str) -> Result<String, InputError> {
+        let read = match self {
+            InputSource::Dir(dir) if dir.join(day_name).is_dir() => Err(io::Error::other(
+                "it holds several inputs, run `matrix` or pick one with `--input`",
+            )),
+            InputSource::Dir(dir) => fs::read_to_string(dir.join(day_name)),
+            InputSource::File(path) => fs::read_to_string(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).map(|_| input)
+            }
+        };
+        read.map_err(|source| InputError {
+            location: self.describe(day_name),
+            source,
+        })
+    }
+
+    /// Every input of a day, each as a source of its own: the `*.txt` files of `<dir>/dayNN`
+    /// sorted by name if that is a directory, otherwise just this source.
+    pub fn inputs(&self, day_name: &str) -> Result<Vec<InputSource>, InputError> {
+        let InputSource::Dir(dir) = self else {
+            return Ok(vec![self.clone()]);
+        };
+        let day_dir = dir.join(day_name);
+        if !day_dir.is_dir() {
+            return Ok(vec![self.clone()]);
+        }
+        let to_error = |source| InputError {
+            location: day_dir.display().to_string(),
+            source,
+        };
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(&day_dir).map_err(to_error)? {
+            let path = entry.map_err(to_error)?.path();
+            if path.extension().is_some_and(|extension| extension == "txt") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths.into_iter().map(InputSource::File).collect())
+    }
+
+    /// Short name of the input, used to tell apart the inputs of a day in `matrix` and `watch`.
+    pub fn name(&self) -> String {
+        let name = match self {
+            InputSource::Dir(dir) => dir.file_name(),
+            InputSource::File(path) => path.file_stem(),
+            InputSource::Stdin => None,
+        };
+        name.map_or(String::from("stdin"), |name| {
+            name.to_string_lossy().into_owned()
+        })
+    }
+
+    /// Names the file the input of the day is read from the same way however it was given, so
+    /// answers recorded for a file are found again: its path relative to the working directory
+    /// (or absolute outside of it), `stdin` for stdin.
+    pub fn answer_key(&self, day_name: &str) -> String {
+        let Some(path) = self.path(day_name) else {
+            return String::from("stdin");
+        };
+        let resolved = fs::canonicalize(&path).ok().map(|full| {
+            env::current_dir()
+                .and_then(fs::canonicalize)
+                .ok()
+                .and_then(|cwd| full.strip_prefix(cwd).ok().map(Path::to_path_buf))
+                .unwrap_or(full)
+        });
+        resolved.unwrap_or(path).display().to_string()
+    }
+
+    /// The file the input of the day is read from, `None` for stdin.
+    pub fn path(&self, day_name: &str) -> Option<PathBuf> {
+        match self {
+            InputSource::Dir(dir) => Some(dir.join(day_name)),
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+        }
+    }
+
+    pub fn describe(&self, day_name: &str) -> String {
+        self.path(day_name)
+            .map_or(String::from("<stdin>"), |path| path.display().to_string())
+    }
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    location: String,
+    source: io::Error,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not read {}: {}", self.location, self.source)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::input::InputSource;
+
+    #[test]
+    fn test_describe() {
+        let dir = InputSource::Dir(PathBuf::from("inputs"));
+        assert_eq!("inputs/day05", dir.describe("day05"));
+        assert!(!dir.is_single());
+        let file = InputSource::File(PathBuf::from("example.txt"));
+        assert_eq!("example.txt", file.describe("day05"));
+        assert!(file.is_single());
+        assert_eq!("<stdin>", InputSource::Stdin.describe("day05"));
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!("input", InputSource::default().name());
+        assert_eq!(
+            "example",
+            InputSource::File(PathBuf::from("day05/example.txt")).name()
+        );
+        assert_eq!("stdin", InputSource::Stdin.name());
+    }
+
+    #[test]
+    fn test_answer_key() {
+        let dir = InputSource::Dir(PathBuf::from("examples"));
+        assert_eq!("examples/day08", dir.answer_key("day08"));
+        for path in [
+            "examples/day08/example1.txt",
+            "./examples/day08/../day08/example1.txt",
+        ] {
+            assert_eq!(
+                "examples/day08/example1.txt",
+                InputSource::File(PathBuf::from(path)).answer_key("day08")
+            );
+        }
+        assert_eq!(
+            "does/not/exist",
+            InputSource::File(PathBuf::from("does/not/exist")).answer_key("day08")
+        );
+        assert_eq!("stdin", InputSource::Stdin.answer_key("day08"));
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let dir = InputSource::Dir(PathBuf::from("does/not/exist"));
+        let err = dir.read("day05").expect_err("should not exist");
+        assert!(err
+            .to_string()
+            .starts_with("Could not read does/not/exist/day05"));
+    }
+
+    #[test]
+    fn test_inputs() {
+        let dir = std::env::temp_dir().join(format!("aoc_2023-inputs-{}", std::process::id()));
+        fs::create_dir_all(dir.join("day05")).expect("can create the input dir");
+        for name in ["second.txt", "first.txt", "notes.md"] {
+            fs::write(dir.join("day05").join(name), "seeds: 1").expect("can write an input");
+        }
+        fs::write(dir.join("day06"), "Time: 7").expect("can write an input");
+        let source = InputSource::Dir(dir.clone());
+
+        let inputs = source.inputs("day05").expect("should list");
+        assert_eq!(
+            vec!["first", "second"],
+            inputs.iter().map(InputSource::name).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Ok(String::from("seeds: 1")),
+            inputs[0].read("day05").map_err(|_| ())
+        );
+        let err = source
+            .read("day05")
+            .expect_err("a directory is not an input");
+        assert!(err.to_string().contains("holds several inputs"));
+
+        assert_eq!(
+            vec![source.clone()],
+            source.inputs("day06").expect("should list")
+        );
+        assert_eq!(
+            vec![source.clone()],
+            source.inputs("day07").expect("should list")
+        );
+        assert_eq!(
+            vec![InputSource::Stdin],
+            InputSource::Stdin.inputs("day05").expect("should list")
+        );
+        fs::remove_dir_all(dir).expect("can clean up");
+    }
+}
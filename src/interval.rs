@@ -0,0 +1,331 @@
+use num::PrimInt;
+
+/// The integers from `start` up to, but not including, `end`. Empty when `start >= end`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { start, end }
+    }
+
+    /// The `len` integers from `start` on.
+    pub fn with_len(start: T, len: T) -> Self {
+        Interval::new(start, start + len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// The integers in both intervals, or `None` if they have none in common.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let common = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        (!common.is_empty()).then_some(common)
+    }
+
+    /// Splits the interval into the integers below `at` and the ones from `at` on; either side
+    /// is `None` if it is empty.
+    pub fn split_at(&self, at: T) -> (Option<Self>, Option<Self>) {
+        let below = Interval::new(self.start, self.end.min(at));
+        let above = Interval::new(self.start.max(at), self.end);
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+
+    /// Moves the interval so that `from` ends up on `to`.
+    pub fn translate(&self, from: T, to: T) -> Self {
+        Interval::new(self.start - from + to, self.end - from + to)
+    }
+}
+
+/// A set of integers, kept as sorted intervals that neither overlap nor touch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// The normalized intervals, lowest first.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// How many integers the set holds.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |total, interval| total + interval.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.intervals
+            .binary_search_by(|interval| {
+                if interval.end <= value {
+                    std::cmp::Ordering::Less
+                } else if interval.start > value {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        self.intervals.push(interval);
+        self.intervals = normalize(std::mem::take(&mut self.intervals));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut common = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            common.extend(a.intersection(b));
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        // the pieces of two normalized sets are separated by the gaps of one of them
+        IntervalSet { intervals: common }
+    }
+
+    /// The integers of `self` that are not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut remaining = Vec::new();
+        for interval in &self.intervals {
+            let mut rest = *interval;
+            for cut in other
+                .intervals
+                .iter()
+                .filter(|cut| cut.end > interval.start && cut.start < interval.end)
+            {
+                if cut.start > rest.start {
+                    remaining.push(Interval::new(rest.start, cut.start));
+                }
+                rest.start = rest.start.max(cut.end);
+            }
+            if !rest.is_empty() {
+                remaining.push(rest);
+            }
+        }
+        IntervalSet {
+            intervals: remaining,
+        }
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        IntervalSet::from_iter([interval])
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> Self {
+        IntervalSet {
+            intervals: normalize(intervals.into_iter().collect()),
+        }
+    }
+}
+
+/// Sorts the intervals, drops the empty ones and merges the ones that overlap or touch.
+fn normalize<T: PrimInt>(mut intervals: Vec<Interval<T>>) -> Vec<Interval<T>> {
+    intervals.retain(|interval| !interval.is_empty());
+    intervals.sort_unstable();
+    let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+    for interval in intervals {
+        match merged.last_mut() {
+            Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+            _ => merged.push(interval),
+        }
+    }
+    merged
+}
+
+/// An axis-aligned box in `N` dimensions: one interval per axis.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalBox<T, const N: usize> {
+    pub axes: [Interval<T>; N],
+}
+
+impl<T: PrimInt, const N: usize> IntervalBox<T, N> {
+    pub fn new(axes: [Interval<T>; N]) -> Self {
+        IntervalBox { axes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(Interval::is_empty)
+    }
+
+    /// How many integer points the box holds.
+    pub fn volume(&self) -> T {
+        self.axes
+            .iter()
+            .fold(T::one(), |volume, interval| volume * interval.len())
+    }
+
+    /// Whether the boxes share at least one point.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.axes
+            .iter()
+            .zip(&other.axes)
+            .all(|(a, b)| a.intersection(b).is_some())
+    }
+
+    /// Splits the box where `axis` reaches `threshold`: the part below it and the part from it on.
+    /// Either side is `None` if it is empty.
+    pub fn split(&self, axis: usize, threshold: T) -> (Option<Self>, Option<Self>) {
+        let (below, above) = self.axes[axis].split_at(threshold);
+        let with_axis = |interval: Interval<T>| {
+            let mut axes = self.axes;
+            axes[axis] = interval;
+            IntervalBox { axes }
+        };
+        (below.map(with_axis), above.map(with_axis))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::fuzz::Rng;
+    use crate::interval::{Interval, IntervalBox, IntervalSet};
+
+    fn set(intervals: &[(u32, u32)]) -> IntervalSet<u32> {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    fn members(set: &IntervalSet<u32>) -> BTreeSet<u32> {
+        set.intervals()
+            .iter()
+            .flat_map(|interval| interval.start..interval.end)
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let interval = Interval::with_len(5, 3);
+        assert_eq!(Interval::new(5, 8), interval);
+        assert_eq!(3, interval.len());
+        assert!(interval.contains(7) && !interval.contains(8));
+        assert_eq!(0, Interval::new(8, 5).len());
+        assert_eq!(
+            Some(Interval::new(6, 8)),
+            interval.intersection(&Interval::new(6, 20))
+        );
+        assert_eq!(None, interval.intersection(&Interval::new(8, 20)));
+        assert_eq!(
+            (Some(Interval::new(5, 6)), Some(Interval::new(6, 8))),
+            interval.split_at(6)
+        );
+        assert_eq!((None, Some(interval)), interval.split_at(5));
+        assert_eq!((Some(interval), None), interval.split_at(8));
+        assert_eq!(Interval::new(52, 55), interval.translate(3, 50));
+    }
+
+    #[test]
+    fn test_set() {
+        let numbers = set(&[(10, 12), (1, 3), (3, 5), (8, 9), (7, 7), (11, 15)]);
+        assert_eq!(
+            &[
+                Interval::new(1, 5),
+                Interval::new(8, 9),
+                Interval::new(10, 15)
+            ],
+            numbers.intervals()
+        );
+        assert_eq!((10, Some(1)), (numbers.len(), numbers.min()));
+        assert!(numbers.contains(4) && numbers.contains(14) && !numbers.contains(9));
+        let mut numbers = numbers;
+        numbers.insert(Interval::new(5, 8));
+        assert_eq!(set(&[(1, 9), (10, 15)]), numbers);
+        assert!(IntervalSet::<u32>::new().is_empty());
+    }
+
+    /// Compares the set operations with the same operations on plain sets of numbers.
+    #[test]
+    fn test_set_operations() {
+        let mut rng = Rng::new(17);
+        let random_set = |rng: &mut Rng| -> Vec<(u32, u32)> {
+            (0..rng.range(0..5))
+                .map(|_| {
+                    let start = rng.range(0..30) as u32;
+                    (start, start + rng.range(0..8) as u32)
+                })
+                .collect()
+        };
+        for _ in 0..500 {
+            let (a, b) = (set(&random_set(&mut rng)), set(&random_set(&mut rng)));
+            let (a_members, b_members) = (members(&a), members(&b));
+            for result in [a.union(&b), a.intersection(&b), a.difference(&b)] {
+                assert_eq!(result, result.intervals().iter().copied().collect());
+            }
+            assert_eq!(&a_members | &b_members, members(&a.union(&b)));
+            assert_eq!(&a_members & &b_members, members(&a.intersection(&b)));
+            assert_eq!(&a_members - &b_members, members(&a.difference(&b)));
+        }
+    }
+
+    #[test]
+    fn test_box() {
+        let cube = IntervalBox::new([Interval::new(1u64, 4001); 4]);
+        assert_eq!(4000u64.pow(4), cube.volume());
+        let (below, above) = cube.split(2, 2006);
+        let (below, above) = (below.expect("not empty"), above.expect("not empty"));
+        assert_eq!(Interval::new(1, 2006), below.axes[2]);
+        assert_eq!(Interval::new(2006, 4001), above.axes[2]);
+        assert_eq!(cube.volume(), below.volume() + above.volume());
+        assert!(!below.intersects(&above) && cube.intersects(&below));
+        assert_eq!((None, Some(cube)), cube.split(0, 1));
+
+        let flat = IntervalBox::new([Interval::new(0, 3), Interval::new(2, 2)]);
+        assert!(flat.is_empty());
+        assert_eq!(0, flat.volume());
+    }
+}
@@ -0,0 +1,72 @@
+//! Solutions for Advent of Code 2023 and the tooling around them.
+//!
+//! Every day lives in its own `dayNN` module. It exposes its parsed model, the functions that solve
+//! it, and a `Solution` that implements [`day::Day`]. [`registry`] collects all days for the runner.
+
+pub mod answers;
+pub mod bench;
+pub mod cache;
+pub mod cancel;
+pub mod day;
+pub mod debugger;
+pub mod dot;
+pub mod examples;
+pub mod export;
+pub mod fuzz;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod log;
+pub mod matrix;
+pub mod mem;
+pub mod parse;
+pub mod pool;
+pub mod render;
+pub mod report;
+pub mod scaffold;
+pub mod search;
+pub mod watch;
+
+use crate::day::Registration;
+
+/// Declares the day modules and collects them into the registry; a new day only needs a line here.
+/// The traits after a day name the tools it supports besides solving, e.g. `day05: Fuzz`.
+macro_rules! days {
+    (@with $registration:expr, $solution:ty;) => {
+        $registration
+    };
+    (@with $registration:expr, $solution:ty; Fuzz $($rest:ident)*) => {
+        days!(@with $registration.with_fuzz::<$solution>(), $solution; $($rest)*)
+    };
+    (@with $registration:expr, $solution:ty; Render $($rest:ident)*) => {
+        days!(@with $registration.with_render::<$solution>(), $solution; $($rest)*)
+    };
+    (@with $registration:expr, $solution:ty; Export $($rest:ident)*) => {
+        days!(@with $registration.with_export::<$solution>(), $solution; $($rest)*)
+    };
+    (@with $registration:expr, $solution:ty; Dot $($rest:ident)*) => {
+        days!(@with $registration.with_dot::<$solution>(), $solution; $($rest)*)
+    };
+    (@with $registration:expr, $solution:ty; Simulate $($rest:ident)*) => {
+        days!(@with $registration.with_simulate::<$solution>(), $solution; $($rest)*)
+    };
+    ($($module:ident $(: $capability:ident $(+ $capabilities:ident)*)?),* $(,)?) => {
+        $(pub mod $module;)*
+
+        /// All days, ordered by day, including stubs whose parts are still unsolved.
+        pub fn registry() -> Vec<Registration> {
+            vec![$(days!(
+                @with Registration::of::<$module::Solution>(), $module::Solution;
+                $($capability $($capabilities)*)?
+            )),*]
+        }
+    };
+}
+
+days! {
+    day01, day02, day03, day04, day05: Fuzz, day06, day07, day08: Dot, day09, day10: Render,
+    day11: Render, day12, day13: Fuzz, day14: Render + Simulate, day15,
+    day16: Render + Export + Simulate, day17: Render + Export, day18: Fuzz + Export,
+    day19: Fuzz + Dot, day20: Dot + Simulate, day22,
+}
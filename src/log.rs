@@ -0,0 +1,187 @@
+//! Leveled logging to stderr, filtered per module, so diagnostics of a solver never mix with the
+//! answers on stdout. Log with [`crate::warn!`], [`crate::info!`], [`crate::debug!`] or
+//! [`crate::trace!`]; the message is only formatted if its level is enabled.
+
+use std::fmt::{self, Display, Formatter};
+use std::sync::RwLock;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    const ALL: [Level; 5] = [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Level> {
+        Level::ALL
+            .into_iter()
+            .find(|level| level.to_string().eq_ignore_ascii_case(name))
+    }
+
+    /// The level `steps` above `self`, capped at [`Level::Trace`].
+    pub fn raised(self, steps: usize) -> Level {
+        Level::ALL[(self as usize + steps).min(Level::ALL.len() - 1)]
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The most detailed level that is logged, overall and for single modules like `day20`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    pub default: Level,
+    pub targets: Vec<(String, Level)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::new(Level::Warn)
+    }
+}
+
+impl Filter {
+    pub const fn new(default: Level) -> Self {
+        Filter {
+            default,
+            targets: Vec::new(),
+        }
+    }
+
+    /// Applies a comma-separated spec like `day20=debug,info`: `<module>=<level>` sets the level
+    /// of one module, a bare level the default. Returns the part that is not understood.
+    pub fn apply(&mut self, spec: &str) -> Result<(), String> {
+        for directive in spec.split(',') {
+            let level = |name: &str| Level::from_name(name).ok_or_else(|| directive.to_string());
+            match directive.split_once('=') {
+                Some((target, name)) if !target.is_empty() => {
+                    let level = level(name)?;
+                    self.targets.retain(|(known, _)| known != target);
+                    self.targets.push((target.to_string(), level));
+                }
+                Some(_) => return Err(directive.to_string()),
+                None => self.default = level(directive)?,
+            }
+        }
+        Ok(())
+    }
+
+    pub fn level(&self, target: &str) -> Level {
+        self.targets
+            .iter()
+            .find(|(known, _)| known == target)
+            .map_or(self.default, |(_, level)| *level)
+    }
+}
+
+static FILTER: RwLock<Filter> = RwLock::new(Filter::new(Level::Warn));
+
+pub fn set_filter(filter: Filter) {
+    *FILTER.write().expect("the filter is never poisoned") = filter;
+}
+
+/// The module a log call belongs to: `day20` for `aoc_2023::day20::test`.
+pub fn target(module_path: &str) -> &str {
+    module_path.split("::").nth(1).unwrap_or(module_path)
+}
+
+pub fn enabled(level: Level, module_path: &str) -> bool {
+    level
+        <= FILTER
+            .read()
+            .expect("the filter is never poisoned")
+            .level(target(module_path))
+}
+
+pub fn write(level: Level, module_path: &str, message: fmt::Arguments) {
+    eprintln!("[{level} {}] {message}", target(module_path));
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($level, module_path!()) {
+            $crate::log::write($level, module_path!(), format_args!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::log::{target, Filter, Level};
+
+    #[test]
+    fn test_level() {
+        assert_eq!(Some(Level::Debug), Level::from_name("debug"));
+        assert_eq!(Some(Level::Warn), Level::from_name("WARN"));
+        assert_eq!(None, Level::from_name("loud"));
+        assert_eq!(Level::Info, Level::Warn.raised(1));
+        assert_eq!(Level::Trace, Level::Warn.raised(7));
+        assert!(Level::Error < Level::Trace);
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut filter = Filter::default();
+        filter
+            .apply("day20=debug,day12=trace")
+            .expect("should apply");
+        assert_eq!(Level::Debug, filter.level("day20"));
+        assert_eq!(Level::Trace, filter.level("day12"));
+        assert_eq!(Level::Warn, filter.level("day18"));
+        filter.apply("info,day20=error").expect("should apply");
+        assert_eq!(Level::Info, filter.level("day18"));
+        assert_eq!(Level::Error, filter.level("day20"));
+        assert_eq!(2, filter.targets.len());
+        assert_eq!(Err(String::from("day20=loud")), filter.apply("day20=loud"));
+        assert_eq!(Err(String::from("=debug")), filter.apply("=debug"));
+    }
+
+    #[test]
+    fn test_target() {
+        assert_eq!("day20", target("aoc_2023::day20"));
+        assert_eq!("day20", target("aoc_2023::day20::test"));
+        assert_eq!("aoc_2023", target("aoc_2023"));
+    }
+}
@@ -1,61 +1,522 @@
-#![feature(slice_group_by)]
-
-use std::env;
-use std::time::Instant;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day22;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use std::{env, io, process, thread};
+
+use aoc_2023::answers::{AnswerKey, AnswerStore, Verdict};
+use aoc_2023::cache::{AnswerCache, DEFAULT_CACHE_FILE};
+use aoc_2023::day::{Answer, Registration};
+use aoc_2023::debugger;
+use aoc_2023::dot;
+use aoc_2023::examples::{self, Status};
+use aoc_2023::export::{self, ImageFormat};
+use aoc_2023::input::InputSource;
+use aoc_2023::matrix;
+use aoc_2023::mem;
+use aoc_2023::parse::ParseError;
+use aoc_2023::pool::{self, DayOutcome};
+use aoc_2023::render::{self, Mode};
+use aoc_2023::report::{Format, Reporter};
+use aoc_2023::scaffold;
+use aoc_2023::watch::{self, Snapshot};
+use aoc_2023::{bench, registry};
+
+use crate::cli::{Args, Command, Highlight, Selection};
+
+mod cli;
+
+/// Only built with the `mem` feature, see [`mem::CountingAllocator`].
+#[cfg(feature = "mem")]
+#[global_allocator]
+static ALLOCATOR: mem::CountingAllocator = mem::CountingAllocator;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let now = Instant::now();
-    let to_match = if args.len() > 1 {
-        args[1].as_str()
-    } else {
-        "today"
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Args {
+        command,
+        input,
+        format,
+        answers,
+        jobs,
+        sort,
+        render,
+        dot,
+        mem: count_allocations,
+        log,
+        timeout,
+        cache: use_cache,
+    } = cli::parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(2);
+    });
+    aoc_2023::log::set_filter(log);
+
+    let registry = registry();
+    match command {
+        Command::List => {
+            for registration in &registry {
+                println!("{}", registration.name());
+            }
+        }
+        Command::Run(selection) => {
+            let selected = select_runnable(&registry, &selection, &input);
+            if let Some(highlight) = dot {
+                for registration in selected {
+                    graph(
+                        registration,
+                        &read_input(registration, &input),
+                        &input,
+                        &highlight,
+                    );
+                }
+                return;
+            }
+            if count_allocations {
+                if !cfg!(feature = "mem") {
+                    eprintln!(
+                        "--mem needs a build with the `mem` feature: cargo run --features mem"
+                    );
+                    process::exit(2);
+                }
+                println!("{}", mem::HEADER);
+                for registration in selected {
+                    let memory = registration.profile(&read_input(registration, &input));
+                    for row in or_exit(memory, registration, &input).rows() {
+                        println!("{row}");
+                    }
+                }
+                return;
+            }
+            let cache = use_cache.then(|| Mutex::new(load_cache()));
+            let now = Instant::now();
+            if let Some(jobs) = jobs {
+                let mut outcomes = pool::map_parallel(&selected, jobs, |registration| {
+                    pool::run_day(registration, &input, timeout, cache.as_ref())
+                });
+                pool::sort_outcomes(&mut outcomes, sort);
+                let succeeded = report_outcomes(&outcomes, format, now.elapsed());
+                save_cache(cache);
+                if !succeeded {
+                    process::exit(1);
+                }
+                return;
+            }
+            let mut reporter = Reporter::new(format, io::stdout());
+            for registration in selected {
+                let text = read_input(registration, &input);
+                let report = pool::solve_day(registration, &text, timeout, cache.as_ref());
+                reporter.day(&or_exit(report, registration, &input));
+                if render {
+                    draw(registration, &text, &input);
+                }
+            }
+            reporter.finish(now.elapsed());
+            save_cache(cache);
+        }
+        Command::Matrix(selection) => {
+            let selected = select_runnable(&registry, &selection, &input);
+            let mut failed = false;
+            // panics are flagged in the matrix, the default hook would only clutter it
+            panic::set_hook(Box::new(|_| {}));
+            for registration in selected {
+                let inputs = input.inputs(&registration.name()).unwrap_or_else(|err| {
+                    eprintln!("{err}");
+                    process::exit(1);
+                });
+                let rows = matrix::run_inputs(
+                    registration,
+                    &inputs,
+                    timeout.unwrap_or(matrix::DEFAULT_TIMEOUT),
+                );
+                matrix::write_matrix(&mut io::stdout(), &registration.name(), &rows)
+                    .expect("could not write matrix");
+                failed |= rows
+                    .iter()
+                    .any(|row| row.panicked() || row.timed_out() || row.cells.is_err());
+            }
+            if failed {
+                process::exit(1);
+            }
+        }
+        Command::Examples(selection) => {
+            let selected = select_runnable(&registry, &selection, &InputSource::default());
+            let results = examples::check_examples(&selected, Path::new(examples::EXAMPLES_DIR))
+                .unwrap_or_else(|err| {
+                    eprintln!("{err}");
+                    process::exit(1);
+                });
+            for result in &results {
+                println!("{result}");
+            }
+            let failed = results
+                .iter()
+                .filter(|result| matches!(result.status, Status::Fail(_)))
+                .count();
+            println!("{} checks, {failed} failed", results.len());
+            if failed > 0 {
+                process::exit(1);
+            }
+        }
+        Command::Fuzz { selection, config } => {
+            let targets: Vec<_> = select(&registry, &selection)
+                .into_iter()
+                .filter_map(|reg| Some((reg, reg.fuzz?)))
+                .collect();
+            if targets.is_empty() {
+                eprintln!("No fuzz target matches {:?}", selection);
+                process::exit(1);
+            }
+            // the solvers are expected to panic on some inputs, which are reported below instead
+            panic::set_hook(Box::new(|_| {}));
+            println!("seed {}", config.seed);
+            let mut failed = false;
+            for (registration, target) in targets {
+                let report = target.fuzz(&config);
+                let name = format!("{} part {}", registration.name(), target.part);
+                let Some(failure) = report.failure else {
+                    println!(
+                        "{name}: {} runs, {} invalid, ok",
+                        report.runs, report.invalid
+                    );
+                    continue;
+                };
+                failed = true;
+                println!(
+                    "{name}: MISMATCH in run {} (seed {}): got {}, reference {}",
+                    report.runs, failure.seed, failure.fast, failure.reference
+                );
+                println!(
+                    "shrunk from {} to {} lines:\n{}",
+                    failure.input.lines().count(),
+                    failure.shrunk.lines().count(),
+                    failure.shrunk
+                );
+            }
+            if failed {
+                process::exit(1);
+            }
+        }
+        Command::Debug(day) => {
+            let selected = select_runnable(&registry, &Selection::Days(day..=day), &input);
+            let registration = selected[0];
+            let Some(simulate) = registration.simulate else {
+                eprintln!("{} has no simulation to debug", registration.name());
+                process::exit(1);
+            };
+            let text = read_input(registration, &input);
+            let mut session = or_exit(simulate(&text), registration, &input);
+            println!("{}", debugger::HELP);
+            let prompt = format!("({}) ", registration.name());
+            debugger::run_session(
+                &mut *session,
+                &prompt,
+                io::stdin().lock(),
+                &mut io::stdout(),
+            )
+            .expect("could not run the debugger");
+        }
+        Command::Watch(day) => {
+            let selected = select_runnable(&registry, &Selection::Days(day..=day), &input);
+            let registration = selected[0];
+            let name = registration.name();
+            // panics show up in the answers, the default hook would only clutter them
+            panic::set_hook(Box::new(|_| {}));
+            println!("watching {name}, stop with Ctrl-C");
+            let mut previous = None;
+            loop {
+                let sources =
+                    watch::watched_sources(registration, &input, Path::new(examples::EXAMPLES_DIR))
+                        .unwrap_or_else(|err| {
+                            eprintln!("{err}");
+                            process::exit(1);
+                        });
+                // taken before running, so changes made during the run trigger another one
+                let snapshot =
+                    Snapshot::take(sources.iter().filter_map(|source| source.path(&name)));
+                if previous.as_ref().is_none_or(|(seen, _)| *seen != snapshot) {
+                    let answers = watch::answers(&matrix::run_inputs(
+                        registration,
+                        &sources,
+                        timeout.unwrap_or(matrix::DEFAULT_TIMEOUT),
+                    ));
+                    let lines = match &previous {
+                        None => watch::listing(&answers),
+                        Some((_, before)) => {
+                            println!("change detected, re-ran {name}");
+                            watch::diff(before, &answers)
+                        }
+                    };
+                    if lines.is_empty() {
+                        println!("answers unchanged");
+                    }
+                    for line in lines {
+                        println!("  {line}");
+                    }
+                    previous = Some((snapshot, answers));
+                }
+                thread::sleep(watch::POLL_INTERVAL);
+            }
+        }
+        Command::Export {
+            selection,
+            out,
+            format,
+        } => {
+            let selected: Vec<_> = select(&registry, &selection)
+                .into_iter()
+                .filter_map(|reg| Some((reg, reg.export?)))
+                .collect();
+            if selected.is_empty() {
+                eprintln!("No exporter matches {:?}", selection);
+                process::exit(1);
+            }
+            if input.is_single() && selected.len() > 1 {
+                eprintln!("--input can only be used when running a single day");
+                process::exit(2);
+            }
+            for (registration, export) in selected {
+                let text = read_input(registration, &input);
+                for image in or_exit(export(&text), registration, &input) {
+                    let path = write_image(&out, &image, format).unwrap_or_else(|err| {
+                        eprintln!("could not write {}: {err}", image.name);
+                        process::exit(1);
+                    });
+                    println!("{}", path.display());
+                }
+            }
+        }
+        Command::NewDay(day) => {
+            let files = scaffold::new_day(Path::new("."), day).unwrap_or_else(|err| {
+                eprintln!("{err}");
+                process::exit(1);
+            });
+            for path in files {
+                println!("wrote {}", path.display());
+            }
+        }
+        Command::Bench { selection, config } => {
+            let selected = select_runnable(&registry, &selection, &input);
+            println!("{}", bench::HEADER);
+            for registration in selected {
+                let bench = registration.bench(&read_input(registration, &input), &config);
+                for row in or_exit(bench, registration, &input).rows() {
+                    println!("{row}");
+                }
+            }
+        }
+        Command::Verify { selection, record } => {
+            let selected = select_runnable(&registry, &selection, &input);
+            let mut store = AnswerStore::load(&answers).unwrap_or_else(|err| {
+                eprintln!("{err}");
+                process::exit(1);
+            });
+            let failed = verify(&selected, &input, timeout, &mut store, record);
+            if record {
+                store.save(&answers).unwrap_or_else(|err| {
+                    eprintln!("{err}");
+                    process::exit(1);
+                });
+            } else if failed {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Loads the answer cache; one that cannot be read is started afresh.
+fn load_cache() -> AnswerCache {
+    AnswerCache::load(Path::new(DEFAULT_CACHE_FILE)).unwrap_or_else(|err| {
+        eprintln!("could not read {DEFAULT_CACHE_FILE}, ignoring it: {err}");
+        AnswerCache::default()
+    })
+}
+
+fn save_cache(cache: Option<Mutex<AnswerCache>>) {
+    let Some(cache) = cache else {
+        return;
+    };
+    let cache = cache
+        .into_inner()
+        .expect("no thread panics holding the cache");
+    if let Err(err) = cache.save(Path::new(DEFAULT_CACHE_FILE)) {
+        eprintln!("could not write {DEFAULT_CACHE_FILE}: {err}");
+    }
+}
+
+/// Selects the days to run, exiting if nothing matches or a single input is given for several days.
+fn select_runnable<'a>(
+    registry: &'a [Registration],
+    selection: &Selection,
+    input: &InputSource,
+) -> Vec<&'a Registration> {
+    let selected = select(registry, selection);
+    if selected.is_empty() {
+        eprintln!("No registered day matches {:?}", selection);
+        process::exit(1);
+    }
+    if input.is_single() && selected.len() > 1 {
+        eprintln!("--input can only be used when running a single day");
+        process::exit(2);
+    }
+    selected
+}
+
+fn select<'a>(registry: &'a [Registration], selection: &Selection) -> Vec<&'a Registration> {
+    match selection {
+        Selection::All => registry.iter().collect(),
+        Selection::Today => registry
+            .iter()
+            .filter(|reg| reg.solved)
+            .max_by_key(|reg| reg.day)
+            .into_iter()
+            .collect(),
+        Selection::Days(days) => registry
+            .iter()
+            .filter(|reg| days.contains(&reg.day))
+            .collect(),
+    }
+}
+
+/// Prints the pictures of a day that has a renderer, and a note on stderr for one that has none.
+fn draw(registration: &Registration, text: &str, source: &InputSource) {
+    let Some(render) = registration.render else {
+        eprintln!("{} has no renderer", registration.name());
+        return;
+    };
+    let mode = Mode::detect();
+    for picture in or_exit(render(text), registration, source) {
+        render::write_picture(&mut io::stdout(), &picture, mode).expect("could not write picture");
+    }
+}
+
+/// Writes `image` into `dir`, creating it if needed, and returns the path of the new file.
+fn write_image(dir: &Path, image: &export::Image, format: ImageFormat) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.{}", image.name, format.extension()));
+    let mut out = BufWriter::new(File::create(&path)?);
+    export::write_image(&mut out, image, format)?;
+    out.flush()?;
+    Ok(path)
+}
+
+/// Prints the DOT graph of a day that has one, and a note on stderr for one that has none.
+fn graph(registration: &Registration, text: &str, source: &InputSource, highlight: &Highlight) {
+    let Some(dot) = registration.dot else {
+        eprintln!("{} has no graph", registration.name());
+        return;
     };
-    match to_match {
-        "day01" => day01::run(),
-        "day02" => day02::run(),
-        "day03" => day03::run(),
-        "day04" => day04::run(),
-        "day05" => day05::run(),
-        "day06" => day06::run(),
-        "day07" => day07::run(),
-        "day08" => day08::run(),
-        "day09" => day09::run(),
-        "day10" => day10::run(),
-        "day11" => day11::run(),
-        "day12" => day12::run(),
-        "day13" => day13::run(),
-        "day14" => day14::run(),
-        "day15" => day15::run(),
-        "day16" => day16::run(),
-        "day17" => day17::run(),
-        "day18" => day18::run(),
-        "day19" => day19::run(),
-        "day20" => day20::run(),
-        "day20" | "today" => day20::run(),
-        _ => unreachable!("Someone forgot to add the day."),
-    }
-    println!("Total took {} µs", now.elapsed().as_micros());
+    let mut graph = or_exit(dot(text), registration, source);
+    if let Highlight::Nodes(nodes) = highlight {
+        graph.highlighted = nodes.iter().cloned().collect();
+    }
+    dot::write_dot(&mut io::stdout(), &graph).expect("could not write graph");
+}
+
+fn read_input(registration: &Registration, source: &InputSource) -> String {
+    source.read(&registration.name()).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(1);
+    })
+}
+
+/// Prints the outcomes of a parallel run: a summary table as text, otherwise the usual records
+/// with failures on stderr. Returns whether every day succeeded.
+fn report_outcomes(outcomes: &[DayOutcome], format: Format, wall_time: Duration) -> bool {
+    if format == Format::Text {
+        pool::write_summary(&mut io::stdout(), outcomes, wall_time)
+            .expect("could not write report");
+    } else {
+        let mut reporter = Reporter::new(format, io::stdout());
+        for outcome in outcomes {
+            match &outcome.result {
+                Ok(report) => reporter.day(report),
+                Err(reason) => eprintln!("day{:02}: {reason}", outcome.day),
+            }
+        }
+        reporter.finish(wall_time);
+    }
+    outcomes.iter().all(|outcome| outcome.result.is_ok())
+}
+
+/// Unwraps the result of running a day, or prints where its input is malformed and exits.
+fn or_exit<T>(
+    result: Result<T, ParseError>,
+    registration: &Registration,
+    source: &InputSource,
+) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err.with_file(source.describe(&registration.name())));
+        process::exit(1);
+    })
+}
+
+/// Checks every part of the selected days on each of their inputs against the store and prints
+/// a verdict per part. With `record` the current answers replace the stored ones; a part that
+/// timed out is a failure and never recorded. Returns whether any part failed.
+fn verify(
+    selected: &[&Registration],
+    source: &InputSource,
+    timeout: Option<Duration>,
+    store: &mut AnswerStore,
+    record: bool,
+) -> bool {
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for registration in selected {
+        let name = registration.name();
+        let inputs = source.inputs(&name).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            process::exit(1);
+        });
+        for input_source in &inputs {
+            let input = read_input(registration, input_source);
+            let report = match timeout {
+                Some(timeout) => registration.run_with_timeout(&input, timeout),
+                None => registration.run(&input),
+            };
+            let report = or_exit(report, registration, input_source);
+            // the input is only worth naming if the day has several
+            let label = if inputs.len() > 1 {
+                format!("{name} {}", input_source.name())
+            } else {
+                name.clone()
+            };
+            for part in report.parts {
+                let key = AnswerKey {
+                    day: report.day,
+                    part: part.part,
+                    input: input_source.answer_key(&name),
+                };
+                let verdict = store.verify(&key, &part.answer);
+                let outcome = match &verdict {
+                    Verdict::Pass => {
+                        passed += 1;
+                        String::from("pass")
+                    }
+                    Verdict::Fail { expected } => {
+                        failed += 1;
+                        format!("FAIL expected {expected}, got {}", part.answer)
+                    }
+                    Verdict::Missing => {
+                        missing += 1;
+                        format!("missing, got {}", part.answer)
+                    }
+                };
+                let recorded =
+                    record && verdict != Verdict::Pass && matches!(part.answer, Answer::Solved(_));
+                if recorded {
+                    store.record(key, &part.answer);
+                }
+                println!(
+                    "{label} part {}: {outcome}{}",
+                    part.part,
+                    if recorded { " (recorded)" } else { "" }
+                );
+            }
+        }
+    }
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    failed > 0
 }
@@ -0,0 +1,300 @@
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use crate::day::{Answer, Registration};
+use crate::input::InputSource;
+use crate::parse::ParseError;
+use crate::pool::panic_message;
+
+/// What one part produced on one input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Solved { answer: Answer, time: Duration },
+    Panicked(String),
+}
+
+/// The parts of a day run on one of its inputs; inputs that cannot be read or parsed carry the
+/// reason instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub input: String,
+    pub cells: Result<Vec<Cell>, String>,
+}
+
+/// How long a part may run in `matrix` and `watch` before it is reported as timed out.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl Row {
+    pub fn panicked(&self) -> bool {
+        self.cells
+            .as_ref()
+            .is_ok_and(|cells| cells.iter().any(|cell| matches!(cell, Cell::Panicked(_))))
+    }
+
+    pub fn timed_out(&self) -> bool {
+        self.cells.as_ref().is_ok_and(|cells| {
+            cells.iter().any(|cell| {
+                matches!(
+                    cell,
+                    Cell::Solved {
+                        answer: Answer::TimedOut(_),
+                        ..
+                    }
+                )
+            })
+        })
+    }
+}
+
+/// Runs both parts of the day on each input. Every part runs on its own, so a panic in part 2
+/// still leaves the answer of part 1, and a part still running after `timeout` is given up on.
+pub fn run_inputs(
+    registration: &Registration,
+    inputs: &[InputSource],
+    timeout: Duration,
+) -> Vec<Row> {
+    let name = registration.name();
+    inputs
+        .iter()
+        .map(|source| {
+            let cells = source
+                .read(&name)
+                .map_err(|err| err.to_string())
+                .and_then(|text| {
+                    [1, 2]
+                        .into_iter()
+                        .map(|part| {
+                            run_part(registration, &text, part, timeout)
+                                .map_err(|err| err.with_file(source.describe(&name)).to_string())
+                        })
+                        .collect()
+                });
+            Row {
+                input: source.name(),
+                cells,
+            }
+        })
+        .collect()
+}
+
+fn run_part(
+    registration: &Registration,
+    text: &str,
+    part: u8,
+    timeout: Duration,
+) -> Result<Cell, ParseError> {
+    match panic::catch_unwind(AssertUnwindSafe(|| {
+        registration.run_parts_with_timeout(text, &[part], timeout)
+    })) {
+        Ok(report) => report.map(|report| {
+            let part = &report.parts[0];
+            Cell::Solved {
+                answer: part.answer.clone(),
+                time: part.solve_time,
+            }
+        }),
+        Err(payload) => Ok(Cell::Panicked(String::from(panic_message(
+            payload.as_ref(),
+        )))),
+    }
+}
+
+/// Prints one row per input with the answer and solve time of each part, flagging the inputs on
+/// which a part panicked or timed out.
+pub fn write_matrix(out: &mut impl Write, day_name: &str, rows: &[Row]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:<16} {:<28} {:<28} status",
+        day_name, "part 1", "part 2"
+    )?;
+    for row in rows {
+        let (part1, part2, status) = match &row.cells {
+            Ok(cells) => {
+                let flagged: Vec<String> = (1..)
+                    .zip(cells)
+                    .filter_map(|(part, cell)| match cell {
+                        Cell::Panicked(message) => Some(format!("part {part} panicked: {message}")),
+                        Cell::Solved {
+                            answer: Answer::TimedOut(_),
+                            ..
+                        } => Some(format!("part {part} timed out")),
+                        Cell::Solved { .. } => None,
+                    })
+                    .collect();
+                let status = if flagged.is_empty() {
+                    String::from("ok")
+                } else if row.panicked() {
+                    format!("PANIC {}", flagged.join("; "))
+                } else {
+                    format!("TIMEOUT {}", flagged.join("; "))
+                };
+                (cell_text(&cells[0]), cell_text(&cells[1]), status)
+            }
+            Err(reason) => (String::from("-"), String::from("-"), reason.clone()),
+        };
+        writeln!(
+            out,
+            "{:<16} {:<28} {:<28} {}",
+            row.input, part1, part2, status
+        )?;
+    }
+    let panicked = rows.iter().filter(|row| row.panicked()).count();
+    let timed_out = rows.iter().filter(|row| row.timed_out()).count();
+    let failed = rows.iter().filter(|row| row.cells.is_err()).count();
+    writeln!(
+        out,
+        "{} inputs, {panicked} panicked, {timed_out} timed out, {failed} failed",
+        rows.len()
+    )
+}
+
+fn cell_text(cell: &Cell) -> String {
+    match cell {
+        Cell::Solved {
+            answer: answer @ Answer::TimedOut(_),
+            ..
+        } => answer.to_string(),
+        Cell::Solved { answer, time } => format!("{answer} ({} µs)", time.as_micros()),
+        Cell::Panicked(_) => String::from("PANIC"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use crate::cancel::CancellationToken;
+    use crate::day::{Answer, Day, Registration};
+    use crate::input::InputSource;
+    use crate::matrix::{run_inputs, write_matrix, Cell, Row};
+    use crate::parse::{self, ParseError};
+
+    /// Sums numbers in part 1 and divides the first by the second in part 2. Part 1 does not end
+    /// on a leading zero until it is cancelled.
+    struct Division;
+
+    impl Day for Division {
+        const DAY: u8 = 25;
+        type Input = Vec<u32>;
+
+        fn parse(input: &str) -> Result<Self::Input, ParseError> {
+            input
+                .split_whitespace()
+                .map(|number| parse::number(input, number))
+                .collect()
+        }
+
+        fn part1(input: &Self::Input) -> Answer {
+            input.iter().sum::<u32>().into()
+        }
+
+        fn part2(input: &Self::Input) -> Answer {
+            (input[0] / input[1]).into()
+        }
+
+        fn part1_cancellable(input: &Self::Input, token: &CancellationToken) -> Answer {
+            while input[0] == 0 && token.check().is_ok() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            Self::part1(input)
+        }
+    }
+
+    #[test]
+    fn test_run_inputs() {
+        let dir = std::env::temp_dir().join(format!("aoc_2023-matrix-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("can create the input dir");
+        let inputs: Vec<InputSource> = [
+            ("even", "6 2"),
+            ("zero", "6 0"),
+            ("words", "six"),
+            ("stuck", "0 2"),
+        ]
+        .into_iter()
+        .map(|(name, text)| {
+            let path = dir.join(format!("{name}.txt"));
+            std::fs::write(&path, text).expect("can write an input");
+            InputSource::File(path)
+        })
+        .chain([InputSource::File(PathBuf::from("does/not/exist.txt"))])
+        .collect();
+        let timeout = Duration::from_millis(50);
+        let rows = run_inputs(&Registration::of::<Division>(), &inputs, timeout);
+        std::fs::remove_dir_all(dir).expect("can clean up");
+
+        assert_eq!(5, rows.len());
+        let Ok(cells) = &rows[0].cells else {
+            panic!("even should run");
+        };
+        assert!(matches!(&cells[1], Cell::Solved { answer, .. } if *answer == Answer::from(3)));
+        assert!(!rows[0].panicked());
+        assert!(rows[1].panicked());
+        let Ok(cells) = &rows[1].cells else {
+            panic!("zero should run");
+        };
+        assert!(matches!(&cells[0], Cell::Solved { answer, .. } if *answer == Answer::from(6)));
+        assert!(matches!(&cells[1], Cell::Panicked(message) if message.contains("divide by zero")));
+        assert!(rows[2]
+            .cells
+            .as_ref()
+            .is_err_and(|err| err.contains("words.txt")));
+        assert!(rows[3].timed_out() && !rows[3].panicked());
+        let Ok(cells) = &rows[3].cells else {
+            panic!("stuck should run");
+        };
+        assert!(
+            matches!(&cells[0], Cell::Solved { answer, .. } if *answer == Answer::TimedOut(timeout))
+        );
+        assert!(matches!(&cells[1], Cell::Solved { answer, .. } if *answer == Answer::from(0)));
+        assert_eq!("exist", rows[4].input);
+        assert!(rows[4].cells.is_err());
+    }
+
+    #[test]
+    fn test_write_matrix() {
+        let solved = |answer: u32| Cell::Solved {
+            answer: Answer::from(answer),
+            time: Duration::from_micros(12),
+        };
+        let rows = [
+            Row {
+                input: String::from("input"),
+                cells: Ok(vec![solved(35), solved(46)]),
+            },
+            Row {
+                input: String::from("edge"),
+                cells: Ok(vec![solved(0), Cell::Panicked(String::from("no seeds"))]),
+            },
+            Row {
+                input: String::from("stuck"),
+                cells: Ok(vec![
+                    Cell::Solved {
+                        answer: Answer::TimedOut(Duration::from_secs(10)),
+                        time: Duration::from_secs(10),
+                    },
+                    solved(0),
+                ]),
+            },
+            Row {
+                input: String::from("broken"),
+                cells: Err(String::from("expected a number")),
+            },
+        ];
+        let mut out = Vec::new();
+        write_matrix(&mut out, "day05", &rows).expect("writes to memory");
+        let text = String::from_utf8(out).expect("matrix is utf-8");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(6, lines.len());
+        assert!(lines[1].starts_with("input ") && lines[1].ends_with(" ok"));
+        assert!(lines[1].contains("35 (12 µs)") && lines[1].contains("46 (12 µs)"));
+        assert!(lines[2].contains("PANIC part 2 panicked: no seeds"));
+        assert!(
+            lines[3].contains("timed out after 10s ")
+                && lines[3].ends_with("TIMEOUT part 1 timed out")
+        );
+        assert!(lines[4].ends_with("expected a number"));
+        assert_eq!("4 inputs, 1 panicked, 1 timed out, 1 failed", lines[5]);
+    }
+}
@@ -0,0 +1,180 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// The system allocator, counting what each thread allocates. It only takes effect where a binary
+/// installs it with `#[global_allocator]`; elsewhere every [`MemUsage`] stays zero. The counting
+/// adds to every allocation, so the `aoc_2023` binary only installs it with the `mem` feature.
+pub struct CountingAllocator;
+
+#[derive(Copy, Clone)]
+struct Counters {
+    allocations: u64,
+    bytes: u64,
+    /// Signed, since a thread may free memory that another thread allocated.
+    live: i64,
+    peak: i64,
+}
+
+thread_local! {
+    // const-initialized without a destructor, so using it never allocates
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            allocations: 0,
+            bytes: 0,
+            live: 0,
+            peak: 0,
+        })
+    };
+}
+
+fn count(allocated: usize, freed: usize) {
+    // the counters are gone while the thread shuts down, its last frees are not counted then
+    let _ = COUNTERS.try_with(|counters| {
+        let mut current = counters.get();
+        if allocated > 0 {
+            current.allocations += 1;
+            current.bytes += allocated as u64;
+        }
+        current.live += allocated as i64 - freed as i64;
+        current.peak = current.peak.max(current.live);
+        counters.set(current);
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            count(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            count(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        count(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            count(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// What a phase allocated on the current thread. A reallocation counts as a new allocation of
+/// the new size.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct MemUsage {
+    pub allocations: u64,
+    pub bytes: u64,
+    /// The most bytes the phase held at once, on top of what was live when it started.
+    pub peak: u64,
+}
+
+/// Runs `phase` and counts its allocations. Measurements do not nest: an inner one resets the
+/// peak of the outer one.
+pub fn measure<T>(phase: impl FnOnce() -> T) -> (T, MemUsage) {
+    let start = COUNTERS.with(|counters| {
+        let mut start = counters.get();
+        start.peak = start.live;
+        counters.set(start);
+        start
+    });
+    let result = phase();
+    let end = COUNTERS.with(Cell::get);
+    let usage = MemUsage {
+        allocations: end.allocations - start.allocations,
+        bytes: end.bytes - start.bytes,
+        peak: (end.peak - start.live).max(0) as u64,
+    };
+    (result, usage)
+}
+
+#[derive(Debug, Clone)]
+pub struct DayMemory {
+    pub day: u8,
+    pub phases: Vec<(&'static str, MemUsage)>,
+}
+
+pub const HEADER: &str = "day   phase     allocations          bytes     peak bytes";
+
+impl DayMemory {
+    pub fn rows(&self) -> Vec<String> {
+        self.phases
+            .iter()
+            .map(|(phase, usage)| {
+                format!(
+                    "day{:02} {:<6} {:>14} {:>14} {:>14}",
+                    self.day, phase, usage.allocations, usage.bytes, usage.peak
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(test)]
+mod test {
+    use std::hint::black_box;
+
+    use crate::mem::{measure, DayMemory, MemUsage};
+
+    #[test]
+    fn test_measure() {
+        let (_, usage) = measure(|| black_box(1 + 2));
+        assert_eq!(MemUsage::default(), usage);
+
+        let (total, usage) = measure(|| {
+            let numbers: Vec<u64> = black_box((0..1000).collect());
+            drop(black_box(Vec::<u8>::with_capacity(100)));
+            numbers.iter().sum::<u64>()
+        });
+        assert_eq!(499_500, total);
+        assert_eq!(2, usage.allocations);
+        assert_eq!(8100, usage.bytes);
+        assert_eq!(8100, usage.peak, "both vectors are live at the same time");
+
+        let (_, usage) = measure(|| {
+            for _ in 0..10 {
+                drop(black_box(vec![0u8; 100]));
+            }
+        });
+        assert_eq!(
+            (10, 1000, 100),
+            (usage.allocations, usage.bytes, usage.peak)
+        );
+    }
+
+    #[test]
+    fn test_rows() {
+        let memory = DayMemory {
+            day: 5,
+            phases: vec![(
+                "part1",
+                MemUsage {
+                    allocations: 3,
+                    bytes: 4096,
+                    peak: 1024,
+                },
+            )],
+        };
+        assert_eq!(
+            vec!["day05 part1               3           4096           1024"],
+            memory.rows()
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A problem in a puzzle input, located by line and column (both starting at 1).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Builds an error pointing at `fragment`, which has to be a slice of `input`.
+    /// Fragments from elsewhere are reported at the start of the input.
+    pub fn at(input: &str, fragment: &str, expected: impl Into<String>) -> Self {
+        let start = input.as_ptr() as usize;
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|offset| *offset <= input.len())
+            .unwrap_or(0);
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        ParseError {
+            file: None,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected: expected.into(),
+        }
+    }
+
+    /// Builds an error pointing right behind the last character of `input`.
+    pub fn at_end(input: &str, expected: impl Into<String>) -> Self {
+        Self::at(input, &input[input.len()..], expected)
+    }
+
+    pub fn with_file(self, file: impl Into<String>) -> Self {
+        ParseError {
+            file: Some(file.into()),
+            ..self
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+        write!(
+            f,
+            "{}:{}: expected {}",
+            self.line, self.column, self.expected
+        )
+    }
+}
+
+/// Parses `fragment` (a slice of `input`) as a number.
+pub fn number<T: FromStr>(input: &str, fragment: &str) -> Result<T, ParseError> {
+    fragment
+        .parse()
+        .map_err(|_| ParseError::at(input, fragment, format!("a number, found `{fragment}`")))
+}
+
+/// Splits `fragment` (a slice of `input`) at the first `delimiter`.
+pub fn split_once<'a>(
+    input: &str,
+    fragment: &'a str,
+    delimiter: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    fragment
+        .split_once(delimiter)
+        .ok_or_else(|| ParseError::at(input, &fragment[fragment.len()..], format!("`{delimiter}`")))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parse::{number, split_once, ParseError};
+
+    #[test]
+    fn test_location() {
+        let input = "Card 1: 41 48\nCard 2: 13 x2\n";
+        let fragment = &input[25..27];
+        assert_eq!("x2", fragment);
+        let err = ParseError::at(input, fragment, "a number");
+        assert_eq!((2, 12), (err.line, err.column));
+        assert_eq!("2:12: expected a number", err.to_string());
+        assert_eq!(
+            "input/day04:2:12: expected a number",
+            err.with_file("input/day04").to_string()
+        );
+        let err = ParseError::at_end(input, "another card");
+        assert_eq!((3, 1), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_helpers() {
+        let input = "41 x8 | 13";
+        assert_eq!(Ok(41), number::<u32>(input, &input[..2]));
+        let err = number::<u32>(input, &input[3..5]).unwrap_err();
+        assert_eq!(
+            (1, 4, "a number, found `x8`"),
+            (err.line, err.column, err.expected.as_str())
+        );
+        assert_eq!(Ok(("41 x8 ", " 13")), split_once(input, input, "|"));
+        let err = split_once(input, &input[..5], ":").unwrap_err();
+        assert_eq!((1, 6), (err.line, err.column));
+    }
+}
@@ -0,0 +1,295 @@
+use std::any::Any;
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cache::{self, AnswerCache};
+use crate::day::{Answer, Registration};
+use crate::input::InputSource;
+use crate::parse::ParseError;
+use crate::report::DayReport;
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum SortBy {
+    #[default]
+    Day,
+    /// Slowest day first.
+    Runtime,
+}
+
+impl SortBy {
+    pub fn from_name(name: &str) -> Option<SortBy> {
+        match name {
+            "day" => Some(SortBy::Day),
+            "runtime" => Some(SortBy::Runtime),
+            _ => None,
+        }
+    }
+}
+
+/// What running a single day produced; failures carry a printable reason.
+#[derive(Debug)]
+pub struct DayOutcome {
+    pub day: u8,
+    /// Wall time of the whole day, including reading the input.
+    pub elapsed: Duration,
+    pub result: Result<DayReport, String>,
+}
+
+/// Applies `task` to every item on up to `jobs` worker threads. Results come back in completion order.
+pub fn map_parallel<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    task: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(item) = items.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let result = task(item);
+                    results.lock().expect("no worker panics").push(result);
+                }
+            });
+        }
+    });
+    results.into_inner().expect("no worker panics")
+}
+
+/// Runs the day on `input`. With a `timeout`, parts that take longer are reported as timed out;
+/// with a `cache`, only the parts it has no answer for are solved.
+pub fn solve_day(
+    registration: &Registration,
+    input: &str,
+    timeout: Option<Duration>,
+    cache: Option<&Mutex<AnswerCache>>,
+) -> Result<DayReport, ParseError> {
+    let solve = |parts: &[u8]| match timeout {
+        Some(timeout) => registration.run_parts_with_timeout(input, parts, timeout),
+        None => registration.run_parts(input, parts),
+    };
+    match cache {
+        Some(cache) => cache::run_cached(cache, registration, input, solve),
+        None => solve(&[1, 2]),
+    }
+}
+
+/// Reads the input and runs the day with [`solve_day`], turning input errors, parse errors and
+/// panics into failures.
+pub fn run_day(
+    registration: &Registration,
+    source: &InputSource,
+    timeout: Option<Duration>,
+    cache: Option<&Mutex<AnswerCache>>,
+) -> DayOutcome {
+    let name = registration.name();
+    let now = Instant::now();
+    let run = |input: &str| solve_day(registration, input, timeout, cache);
+    let result =
+        source.read(&name).map_err(|err| err.to_string()).and_then(
+            |input| match panic::catch_unwind(AssertUnwindSafe(|| run(&input))) {
+                Ok(Ok(report)) => Ok(report),
+                Ok(Err(err)) => Err(err.with_file(source.describe(&name)).to_string()),
+                Err(payload) => Err(format!("panicked: {}", panic_message(payload.as_ref()))),
+            },
+        );
+    DayOutcome {
+        day: registration.day,
+        elapsed: now.elapsed(),
+        result,
+    }
+}
+
+pub fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause")
+}
+
+pub fn sort_outcomes(outcomes: &mut [DayOutcome], sort: SortBy) {
+    match sort {
+        SortBy::Day => outcomes.sort_by_key(|outcome| outcome.day),
+        SortBy::Runtime => outcomes.sort_by_key(|outcome| std::cmp::Reverse(outcome.elapsed)),
+    }
+}
+
+/// Prints one row per day and a closing line comparing wall time with the summed runtimes.
+pub fn write_summary(
+    out: &mut impl Write,
+    outcomes: &[DayOutcome],
+    wall_time: Duration,
+) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "{:<6} {:<18} {:<18} {:>12}  status",
+        "day", "part 1", "part 2", "runtime"
+    )?;
+    for outcome in outcomes {
+        let (part1, part2, status) = match &outcome.result {
+            Ok(report) => {
+                let answer = |idx: usize| {
+                    report.parts.get(idx).map_or(String::from("-"), |part| {
+                        if part.cached {
+                            format!("{} (cached)", part.answer)
+                        } else {
+                            part.answer.to_string()
+                        }
+                    })
+                };
+                let timed_out = report
+                    .parts
+                    .iter()
+                    .any(|part| matches!(part.answer, Answer::TimedOut(_)));
+                let status = if timed_out { "timed out" } else { "ok" };
+                (answer(0), answer(1), status)
+            }
+            Err(reason) => (String::from("-"), String::from("-"), reason.as_str()),
+        };
+        writeln!(
+            out,
+            "day{:02}  {:<18} {:<18} {:>9} µs  {}",
+            outcome.day,
+            part1,
+            part2,
+            outcome.elapsed.as_micros(),
+            status
+        )?;
+    }
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| outcome.result.is_err())
+        .count();
+    let summed: Duration = outcomes.iter().map(|outcome| outcome.elapsed).sum();
+    writeln!(
+        out,
+        "{} days, {} failed; wall time {} µs, summed runtime {} µs",
+        outcomes.len(),
+        failed,
+        wall_time.as_micros(),
+        summed.as_micros()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic;
+    use std::time::Duration;
+
+    use crate::day::Answer;
+    use crate::pool::{
+        map_parallel, panic_message, sort_outcomes, write_summary, DayOutcome, SortBy,
+    };
+    use crate::report::{DayReport, PartReport};
+
+    fn outcome(day: u8, micros: u64, result: Result<DayReport, String>) -> DayOutcome {
+        DayOutcome {
+            day,
+            elapsed: Duration::from_micros(micros),
+            result,
+        }
+    }
+
+    fn report(day: u8) -> DayReport {
+        DayReport {
+            day,
+            parse_time: Duration::ZERO,
+            parts: vec![
+                PartReport {
+                    part: 1,
+                    answer: Answer::from(35),
+                    solve_time: Duration::ZERO,
+                    cached: true,
+                },
+                PartReport {
+                    part: 2,
+                    answer: Answer::Unsolved,
+                    solve_time: Duration::ZERO,
+                    cached: false,
+                },
+            ],
+        }
+    }
+
+    fn timed_out_report() -> DayReport {
+        DayReport {
+            day: 20,
+            parse_time: Duration::ZERO,
+            parts: vec![
+                PartReport {
+                    part: 1,
+                    answer: Answer::from(899848294),
+                    solve_time: Duration::ZERO,
+                    cached: false,
+                },
+                PartReport {
+                    part: 2,
+                    answer: Answer::TimedOut(Duration::from_millis(1)),
+                    solve_time: Duration::from_millis(1),
+                    cached: false,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_map_parallel() {
+        let items: Vec<u32> = (0..20).collect();
+        let mut squares = map_parallel(&items, 4, |item| item * item);
+        squares.sort();
+        assert_eq!(
+            items.iter().map(|item| item * item).collect::<Vec<_>>(),
+            squares
+        );
+        assert!(map_parallel(&[] as &[u32], 4, |item| *item).is_empty());
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let payload = panic::catch_unwind(|| panic!("no result found")).unwrap_err();
+        assert_eq!("no result found", panic_message(payload.as_ref()));
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut outcomes = vec![
+            outcome(3, 20, Ok(report(3))),
+            outcome(1, 10, Ok(report(1))),
+            outcome(2, 30, Err(String::from("panicked"))),
+        ];
+        let days = |outcomes: &[DayOutcome]| outcomes.iter().map(|o| o.day).collect::<Vec<_>>();
+        sort_outcomes(&mut outcomes, SortBy::Day);
+        assert_eq!(vec![1, 2, 3], days(&outcomes));
+        sort_outcomes(&mut outcomes, SortBy::Runtime);
+        assert_eq!(vec![2, 3, 1], days(&outcomes));
+    }
+
+    #[test]
+    fn test_summary() {
+        let outcomes = vec![
+            outcome(5, 12, Ok(report(5))),
+            outcome(
+                19,
+                3,
+                Err(String::from("input/day19:3:1: expected a rating")),
+            ),
+            outcome(20, 1000, Ok(timed_out_report())),
+        ];
+        let mut out = Vec::new();
+        write_summary(&mut out, &outcomes, Duration::from_micros(1013)).unwrap();
+        assert_eq!(
+            "day    part 1             part 2                  runtime  status
+day05  35 (cached)        unsolved                  12 µs  ok
+day19  -                  -                          3 µs  input/day19:3:1: expected a rating
+day20  899848294          timed out after 0.001s      1000 µs  timed out
+3 days, 1 failed; wall time 1013 µs, summed runtime 1015 µs
+",
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::io::{self, IsTerminal, Write};
+
+use crate::day::Day;
+use crate::grid::Grid;
+
+/// A grid day that can draw its input and what its solvers found.
+pub trait Render: Day {
+    fn render(input: &Self::Input) -> Vec<Picture>;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Style {
+    #[default]
+    Plain,
+    Dim,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+}
+
+impl Style {
+    /// The SGR parameters that switch to this style.
+    fn code(self) -> &'static str {
+        match self {
+            Style::Plain => "0",
+            Style::Dim => "2",
+            Style::Red => "1;31",
+            Style::Green => "1;32",
+            Style::Yellow => "1;33",
+            Style::Blue => "34",
+        }
+    }
+}
+
+/// One tile of a picture: `glyph` in `style` on a terminal, `plain` everywhere else. Overlays
+/// that only show as colour need a `plain` glyph of their own to stay visible.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Cell {
+    pub glyph: char,
+    pub plain: char,
+    pub style: Style,
+}
+
+impl Cell {
+    pub fn new(glyph: char, style: Style) -> Self {
+        Cell {
+            glyph,
+            plain: glyph,
+            style,
+        }
+    }
+
+    pub fn or_plain(self, plain: char) -> Self {
+        Cell { plain, ..self }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Picture {
+    pub title: String,
+    pub cells: Grid<Cell>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Mode {
+    /// Coloured glyphs through ANSI escape codes.
+    Ansi,
+    /// The plain ASCII glyphs without any escape codes.
+    Plain,
+}
+
+impl Mode {
+    /// ANSI when stdout is a terminal, plain otherwise.
+    pub fn detect() -> Self {
+        if io::stdout().is_terminal() {
+            Mode::Ansi
+        } else {
+            Mode::Plain
+        }
+    }
+}
+
+/// Writes the title and the rows of `picture`, switching styles only where they change.
+pub fn write_picture(out: &mut impl Write, picture: &Picture, mode: Mode) -> io::Result<()> {
+    writeln!(out, "{}", picture.title)?;
+    for row in picture.cells.rows() {
+        let mut current = Style::Plain;
+        for cell in row {
+            match mode {
+                Mode::Plain => write!(out, "{}", cell.plain)?,
+                Mode::Ansi => {
+                    if cell.style != current {
+                        write!(out, "\x1b[0;{}m", cell.style.code())?;
+                        current = cell.style;
+                    }
+                    write!(out, "{}", cell.glyph)?;
+                }
+            }
+        }
+        if current != Style::Plain {
+            write!(out, "\x1b[0m")?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::grid::Grid;
+    use crate::registry;
+    use crate::render::{write_picture, Cell, Mode, Picture, Style};
+
+    fn draw(picture: &Picture, mode: Mode) -> String {
+        let mut out = Vec::new();
+        write_picture(&mut out, picture, mode).expect("writes to memory");
+        String::from_utf8(out).expect("glyphs are utf-8")
+    }
+
+    #[test]
+    fn test_write_picture() {
+        let cells = vec![
+            Cell::new('.', Style::Dim),
+            Cell::new('┌', Style::Yellow).or_plain('F'),
+            Cell::new('┐', Style::Yellow).or_plain('7'),
+            Cell::new('a', Style::Plain),
+        ];
+        let picture = Picture {
+            title: String::from("test"),
+            cells: Grid::from_cells(2, 2, cells),
+        };
+        assert_eq!("test\n.F\n7a\n", draw(&picture, Mode::Plain));
+        assert_eq!(
+            "test\n\x1b[0;2m.\x1b[0;1;33m┌\x1b[0m\n\x1b[0;1;33m┐\x1b[0;0ma\n",
+            draw(&picture, Mode::Ansi)
+        );
+    }
+
+    /// Every renderer draws its first example, and the plain pictures are plain ASCII.
+    #[test]
+    fn test_renderers() {
+        for registration in registry() {
+            let Some(render) = registration.render else {
+                continue;
+            };
+            let path = format!("examples/{}/example1.txt", registration.name());
+            let input = fs::read_to_string(&path).expect("fixture should exist");
+            let pictures = render(&input).expect("should parse");
+            assert!(!pictures.is_empty(), "{} drew nothing", registration.name());
+            for picture in &pictures {
+                assert!(draw(picture, Mode::Plain).is_ascii());
+            }
+        }
+    }
+
+    #[test]
+    fn test_day16_energized() {
+        let input = fs::read_to_string("examples/day16/example1.txt").expect("fixture exists");
+        let render = registry()
+            .into_iter()
+            .find(|registration| registration.day == 16)
+            .and_then(|registration| registration.render)
+            .expect("day16 renders");
+        let pictures = render(&input).expect("should parse");
+        assert_eq!(
+            "######....
+.#...#....
+.#...#####
+.#...##...
+.#...##...
+.#...##...
+.#..####..
+########..
+.#######..
+.#...#.#..
+",
+            draw(&pictures[0], Mode::Plain)
+                .split_once('\n')
+                .expect("starts with a title")
+                .1
+        );
+    }
+}
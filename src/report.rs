@@ -0,0 +1,268 @@
+use std::io::Write;
+use std::time::Duration;
+
+use crate::day::Answer;
+
+#[derive(Debug, Clone)]
+pub struct PartReport {
+    pub part: u8,
+    pub answer: Answer,
+    pub solve_time: Duration,
+    /// The answer was taken from the answer cache instead of being solved.
+    pub cached: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    pub day: u8,
+    pub parse_time: Duration,
+    pub parts: Vec<PartReport>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Streams day reports in the chosen format; json and csv emit one record per day and part.
+pub struct Reporter<W: Write> {
+    format: Format,
+    out: W,
+    records: usize,
+}
+
+impl<W: Write> Reporter<W> {
+    pub fn new(format: Format, mut out: W) -> Self {
+        match format {
+            Format::Text => (),
+            Format::Json => write!(out, "[").expect("could not write report"),
+            Format::Csv => writeln!(out, "day,part,answer,status,parse_us,solve_us,cached")
+                .expect("could not write report"),
+        }
+        Reporter {
+            format,
+            out,
+            records: 0,
+        }
+    }
+
+    pub fn day(&mut self, report: &DayReport) {
+        match self.format {
+            Format::Text => {
+                writeln!(
+                    self.out,
+                    "Day {:02} parsing took {} µs",
+                    report.day,
+                    report.parse_time.as_micros()
+                )
+                .expect("could not write report");
+                for part in &report.parts {
+                    if part.cached {
+                        writeln!(
+                            self.out,
+                            "Day {:02} Solution Part {}: {} (cached)",
+                            report.day, part.part, part.answer
+                        )
+                    } else {
+                        writeln!(
+                            self.out,
+                            "Day {:02} Solution Part {}: {} ({} µs)",
+                            report.day,
+                            part.part,
+                            part.answer,
+                            part.solve_time.as_micros()
+                        )
+                    }
+                    .expect("could not write report");
+                }
+            }
+            Format::Json => {
+                for part in &report.parts {
+                    let separator = if self.records == 0 { "" } else { "," };
+                    let answer = match &part.answer {
+                        Answer::Solved(value) => json_string(value),
+                        Answer::Unsolved | Answer::TimedOut(_) => String::from("null"),
+                    };
+                    write!(
+                        self.out,
+                        "{separator}\n  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"status\": \"{}\", \"parse_us\": {}, \"solve_us\": {}, \"cached\": {}}}",
+                        report.day,
+                        part.part,
+                        answer,
+                        part.answer.status(),
+                        report.parse_time.as_micros(),
+                        part.solve_time.as_micros(),
+                        part.cached
+                    )
+                    .expect("could not write report");
+                    self.records += 1;
+                }
+            }
+            Format::Csv => {
+                for part in &report.parts {
+                    let answer = match &part.answer {
+                        Answer::Solved(value) => csv_field(value),
+                        Answer::Unsolved | Answer::TimedOut(_) => String::new(),
+                    };
+                    writeln!(
+                        self.out,
+                        "{},{},{},{},{},{},{}",
+                        report.day,
+                        part.part,
+                        answer,
+                        part.answer.status(),
+                        report.parse_time.as_micros(),
+                        part.solve_time.as_micros(),
+                        part.cached
+                    )
+                    .expect("could not write report");
+                    self.records += 1;
+                }
+            }
+        }
+    }
+
+    pub fn finish(mut self, total: Duration) -> W {
+        match self.format {
+            Format::Text => writeln!(self.out, "Total took {} µs", total.as_micros()),
+            Format::Json if self.records == 0 => writeln!(self.out, "]"),
+            Format::Json => writeln!(self.out, "\n]"),
+            Format::Csv => Ok(()),
+        }
+        .expect("could not write report");
+        self.out
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for char in value.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            control if control.is_control() => {
+                escaped.push_str(&format!("\\u{:04x}", control as u32))
+            }
+            other => escaped.push(other),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        String::from(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::day::Answer;
+    use crate::report::{DayReport, Format, PartReport, Reporter};
+
+    fn example_report() -> DayReport {
+        DayReport {
+            day: 5,
+            parse_time: Duration::from_micros(12),
+            parts: vec![
+                PartReport {
+                    part: 1,
+                    answer: Answer::from(35),
+                    solve_time: Duration::from_micros(3),
+                    cached: false,
+                },
+                PartReport {
+                    part: 2,
+                    answer: Answer::Unsolved,
+                    solve_time: Duration::from_micros(0),
+                    cached: false,
+                },
+            ],
+        }
+    }
+
+    fn cached_report() -> DayReport {
+        DayReport {
+            day: 6,
+            parse_time: Duration::ZERO,
+            parts: vec![PartReport {
+                part: 1,
+                answer: Answer::from(288),
+                solve_time: Duration::ZERO,
+                cached: true,
+            }],
+        }
+    }
+
+    fn timed_out_report() -> DayReport {
+        DayReport {
+            day: 8,
+            parse_time: Duration::from_micros(7),
+            parts: vec![PartReport {
+                part: 2,
+                answer: Answer::TimedOut(Duration::from_secs(1)),
+                solve_time: Duration::from_secs(1),
+                cached: false,
+            }],
+        }
+    }
+
+    fn render(format: Format) -> String {
+        let mut reporter = Reporter::new(format, Vec::new());
+        reporter.day(&example_report());
+        reporter.day(&cached_report());
+        reporter.day(&timed_out_report());
+        let out = reporter.finish(Duration::from_micros(20));
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            "[\n  {\"day\": 5, \"part\": 1, \"answer\": \"35\", \"status\": \"solved\", \"parse_us\": 12, \"solve_us\": 3, \"cached\": false},\n  {\"day\": 5, \"part\": 2, \"answer\": null, \"status\": \"unsolved\", \"parse_us\": 12, \"solve_us\": 0, \"cached\": false},\n  {\"day\": 6, \"part\": 1, \"answer\": \"288\", \"status\": \"solved\", \"parse_us\": 0, \"solve_us\": 0, \"cached\": true},\n  {\"day\": 8, \"part\": 2, \"answer\": null, \"status\": \"timed out\", \"parse_us\": 7, \"solve_us\": 1000000, \"cached\": false}\n]\n",
+            render(Format::Json)
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            "day,part,answer,status,parse_us,solve_us,cached\n5,1,35,solved,12,3,false\n5,2,,unsolved,12,0,false\n6,1,288,solved,0,0,true\n8,2,,timed out,7,1000000,false\n",
+            render(Format::Csv)
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            "Day 05 parsing took 12 µs\nDay 05 Solution Part 1: 35 (3 µs)\nDay 05 Solution Part 2: unsolved (0 µs)\nDay 06 parsing took 0 µs\nDay 06 Solution Part 1: 288 (cached)\nDay 08 parsing took 7 µs\nDay 08 Solution Part 2: timed out after 1s (1000000 µs)\nTotal took 20 µs\n",
+            render(Format::Text)
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!("\"a\\\"b\"", super::json_string("a\"b"));
+        assert_eq!("\"a,b\"", super::csv_field("a,b"));
+    }
+}
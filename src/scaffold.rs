@@ -0,0 +1,232 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use crate::input::DEFAULT_INPUT_DIR;
+
+/// The file whose `days!` invocation lists the registered day modules.
+const LIB_FILE: &str = "src/lib.rs";
+const DAYS_MACRO: &str = "days! {";
+/// Widest line of the `days!` list, as rustfmt would wrap it.
+const MAX_WIDTH: usize = 100;
+
+const TEMPLATE: &str = r#"use crate::day::{Answer, Day};
+use crate::parse::ParseError;
+
+pub struct Solution;
+
+impl Day for Solution {
+    const DAY: u8 = {day};
+    const SOLVED: bool = false;
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(input.lines().map(String::from).collect())
+    }
+
+    fn part1(_input: &Self::Input) -> Answer {
+        Answer::Unsolved
+    }
+
+    fn part2(_input: &Self::Input) -> Answer {
+        Answer::Unsolved
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::day::{Answer, Day};
+    use crate::{module}::Solution;
+
+    /// The example of the puzzle text; once it is solved, it belongs in `examples/` as well.
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn test_example() {
+        let input = Solution::parse(EXAMPLE).expect("should parse");
+        assert_eq!(Answer::Unsolved, Solution::part1(&input));
+        assert_eq!(Answer::Unsolved, Solution::part2(&input));
+    }
+}
+"#;
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// The day already has a module or is already registered.
+    Exists(String),
+    /// The `days!` list could not be found in the lib file.
+    NoRegistry(String),
+    Io {
+        location: String,
+        source: io::Error,
+    },
+}
+
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScaffoldError::Exists(what) => write!(f, "{what} already exists"),
+            ScaffoldError::NoRegistry(location) => {
+                write!(f, "Could not find the `days!` list in {location}")
+            }
+            ScaffoldError::Io { location, source } => {
+                write!(f, "Could not write {location}: {source}")
+            }
+        }
+    }
+}
+
+/// The source of a new day module: a parse stub, unsolved parts and a test of the first example.
+pub fn day_module(day: u8) -> String {
+    TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{module}", &format!("day{day:02}"))
+}
+
+/// Adds `module` to the `days!` list of the lib file `source`, keeping the list sorted.
+pub fn register(source: &str, module: &str) -> Result<String, ScaffoldError> {
+    let no_registry = || ScaffoldError::NoRegistry(String::from(LIB_FILE));
+    let start = source.find(DAYS_MACRO).ok_or_else(no_registry)? + DAYS_MACRO.len();
+    let end = start + source[start..].find('}').ok_or_else(no_registry)?;
+    let mut modules: Vec<&str> = source[start..end]
+        .split(',')
+        .map(str::trim)
+        .filter(|module| !module.is_empty())
+        .collect();
+    // an entry may list the tools of the day after its name, like `day05: Fuzz`
+    if modules
+        .iter()
+        .any(|entry| entry.split(':').next() == Some(module))
+    {
+        return Err(ScaffoldError::Exists(format!("{module} in {LIB_FILE}")));
+    }
+    modules.push(module);
+    modules.sort_unstable();
+
+    let mut lines = vec![String::from("   ")];
+    for module in modules {
+        let line = lines.last_mut().expect("starts with a line");
+        if line.len() + module.len() + 2 > MAX_WIDTH {
+            lines.push(format!("    {module},"));
+        } else {
+            line.push_str(&format!(" {module},"));
+        }
+    }
+    Ok(format!(
+        "{}\n{}\n{}",
+        &source[..start],
+        lines.join("\n"),
+        &source[end..]
+    ))
+}
+
+/// Creates the module of `day` below `root`, registers it and adds an empty input unless there is
+/// one already. Returns the files it wrote; nothing is written if the day exists already.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let module = format!("day{day:02}");
+    let module_path = root.join("src").join(format!("{module}.rs"));
+    if module_path.exists() {
+        return Err(ScaffoldError::Exists(module_path.display().to_string()));
+    }
+    let lib_path = root.join(LIB_FILE);
+    let lib = fs::read_to_string(&lib_path).map_err(|source| ScaffoldError::Io {
+        location: lib_path.display().to_string(),
+        source,
+    })?;
+    let lib = register(&lib, &module)?;
+
+    let input_path = root.join(DEFAULT_INPUT_DIR).join(&module);
+    let mut files = vec![(module_path, day_module(day)), (lib_path, lib)];
+    if !input_path.exists() {
+        files.push((input_path, String::new()));
+    }
+    for (path, content) in &files {
+        let parent = path.parent().expect("files are below the root");
+        fs::create_dir_all(parent)
+            .and_then(|_| fs::write(path, content))
+            .map_err(|source| ScaffoldError::Io {
+                location: path.display().to_string(),
+                source,
+            })?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::scaffold::{day_module, new_day, register, ScaffoldError};
+
+    const LIB: &str = "pub mod input;
+
+days! {
+    day01, day02, day03, day04, day05: Fuzz, day06, day07, day08, day09, day10, day11, day12,
+    day13, day14, day15,
+}
+";
+
+    #[test]
+    fn test_register() {
+        assert_eq!(
+            "pub mod input;
+
+days! {
+    day01, day02, day03, day04, day05: Fuzz, day06, day07, day08, day09, day10, day11, day12, day13,
+    day14, day15, day21,
+}
+",
+            register(LIB, "day21").expect("should register")
+        );
+        let wrapped = register(LIB, "day00").expect("should register");
+        assert!(wrapped.contains("    day00, day01,"));
+        assert!(wrapped.contains("day12,\n    day13, day14, day15,\n}"));
+        for module in ["day05", "day14"] {
+            assert!(matches!(
+                register(LIB, module),
+                Err(ScaffoldError::Exists(_))
+            ));
+        }
+        assert!(matches!(
+            register("pub mod input;", "day14"),
+            Err(ScaffoldError::NoRegistry(_))
+        ));
+    }
+
+    #[test]
+    fn test_day_module() {
+        let module = day_module(21);
+        assert!(module.contains("const DAY: u8 = 21;"));
+        assert!(module.contains("use crate::day21::Solution;"));
+        assert!(!module.contains("{day}") && !module.contains("{module}"));
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = std::env::temp_dir().join(format!("aoc_2023-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).expect("can create the source dir");
+        fs::write(root.join("src/lib.rs"), LIB).expect("can write the lib");
+
+        let files = new_day(&root, 21).expect("should scaffold");
+        assert_eq!(3, files.len());
+        assert_eq!(
+            day_module(21),
+            fs::read_to_string(root.join("src/day21.rs")).expect("module is written")
+        );
+        assert_eq!(
+            "",
+            fs::read_to_string(root.join("input/day21")).expect("input is created")
+        );
+        assert!(matches!(new_day(&root, 21), Err(ScaffoldError::Exists(_))));
+
+        fs::write(root.join("input/day23"), "puzzle").expect("can write the input");
+        assert_eq!(2, new_day(&root, 23).expect("should scaffold").len());
+        assert_eq!(
+            "puzzle",
+            fs::read_to_string(root.join("input/day23")).expect("input is kept")
+        );
+        let lib = fs::read_to_string(root.join("src/lib.rs")).expect("lib is kept");
+        assert!(lib.contains("day15, day21, day23,"));
+        fs::remove_dir_all(root).expect("can clean up");
+    }
+}
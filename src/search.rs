@@ -0,0 +1,255 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// A cheapest route: its total cost and every state on it, from a start to the goal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchPath<S> {
+    pub cost: usize,
+    pub states: Vec<S>,
+}
+
+/// What a search reached: the cheapest known cost of every state it saw, and where it came from.
+#[derive(Debug, Clone)]
+pub struct Explored<S> {
+    pub distances: HashMap<S, usize>,
+    previous: HashMap<S, S>,
+}
+
+impl<S: Clone + Eq + Hash> Explored<S> {
+    fn new() -> Self {
+        Explored {
+            distances: HashMap::new(),
+            previous: HashMap::new(),
+        }
+    }
+
+    /// The cheapest known route to `state`, or `None` if the search never saw it.
+    pub fn path_to(&self, state: &S) -> Option<SearchPath<S>> {
+        let cost = *self.distances.get(state)?;
+        let mut states = vec![state.clone()];
+        while let Some(previous) = self.previous.get(states.last().expect("starts with state")) {
+            states.push(previous.clone());
+        }
+        states.reverse();
+        Some(SearchPath { cost, states })
+    }
+
+    /// Records `state` as reachable for `cost` through `previous`, unless it is known to be cheaper.
+    fn relax(&mut self, state: &S, cost: usize, previous: Option<&S>) -> bool {
+        if self
+            .distances
+            .get(state)
+            .is_some_and(|known| *known <= cost)
+        {
+            return false;
+        }
+        self.distances.insert(state.clone(), cost);
+        match previous {
+            Some(previous) => self.previous.insert(state.clone(), previous.clone()),
+            None => self.previous.remove(state),
+        };
+        true
+    }
+}
+
+/// Breadth-first search where every move costs 1. Returns the shortest route from any of
+/// `starts` to a state that satisfies `is_goal`.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchPath<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut explored = Explored::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if explored.relax(&start, 0, None) {
+            queue.push_back(start);
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            return explored.path_to(&state);
+        }
+        let cost = explored.distances[&state] + 1;
+        for next in successors(&state) {
+            if !explored.distances.contains_key(&next) {
+                explored.relax(&next, cost, Some(&state));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Dijkstra's algorithm: the cheapest route from any of `starts` to a state that satisfies
+/// `is_goal`. `successors` lists the next states together with the cost of moving there.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchPath<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    astar(starts, successors, |_| 0, is_goal)
+}
+
+/// A*: like [`dijkstra`], but visits states in order of their cost plus `heuristic`, which has to
+/// be a lower bound of the remaining cost and must not drop by more than the cost of any move.
+pub fn astar<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchPath<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let (explored, goal) = run(starts, successors, heuristic, |state| is_goal(state));
+    explored.path_to(&goal?)
+}
+
+/// Dijkstra's algorithm without a goal: every state reachable from `starts` with its cheapest cost.
+pub fn explore<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+) -> Explored<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    run(starts, successors, |_| 0, |_| false).0
+}
+
+/// The best-first search behind [`astar`], [`dijkstra`] and [`explore`]. Stops at the first goal
+/// it takes from the queue and returns it along with everything explored so far.
+fn run<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> (Explored<S>, Option<S>)
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut explored = Explored::new();
+    let mut done = HashSet::new();
+    // the heap holds indices into `queued`, so states need no ordering of their own
+    let mut queued = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if explored.relax(&start, 0, None) {
+            heap.push(Reverse((heuristic(&start), 0, queued.len())));
+            queued.push(start);
+        }
+    }
+
+    while let Some(Reverse((_, cost, idx))) = heap.pop() {
+        let state = queued[idx].clone();
+        if explored.distances[&state] < cost || !done.insert(state.clone()) {
+            continue;
+        }
+        if is_goal(&state) {
+            return (explored, Some(state));
+        }
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            if !done.contains(&next) && explored.relax(&next, next_cost, Some(&state)) {
+                heap.push(Reverse((
+                    next_cost + heuristic(&next),
+                    next_cost,
+                    queued.len(),
+                )));
+                queued.push(next);
+            }
+        }
+    }
+    (explored, None)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry::Point2;
+    use crate::grid::Grid;
+    use crate::search::{astar, bfs, dijkstra, explore, SearchPath};
+
+    fn maze() -> Grid<bool> {
+        let input = "..#....\n.##.##.\n....#..\n##.##.#\n.......";
+        Grid::parse(input, "`.` or `#`", |char| match char {
+            '.' => Some(true),
+            '#' => Some(false),
+            _ => None,
+        })
+        .expect("should parse")
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = maze();
+        let open = |pos: &(usize, usize)| maze.neighbours(*pos).filter(|next| maze[*next]);
+        let path = bfs([(0, 0)], open, |pos| *pos == (6, 0)).expect("the maze is solvable");
+        assert_eq!(10, path.cost);
+        assert_eq!(11, path.states.len());
+        assert_eq!(Some(&(0, 0)), path.states.first());
+        assert!(path
+            .states
+            .windows(2)
+            .all(|w| maze.neighbours(w[0]).any(|n| n == w[1])));
+        assert_eq!(None, bfs([(0, 0)], open, |pos| *pos == (2, 0)));
+    }
+
+    #[test]
+    fn test_weighted() {
+        // 0 -> 1 -> 3 costs 2 + 5, 0 -> 2 -> 3 costs 4 + 1, 0 -> 3 directly costs 10
+        let edges = |node: &u32| match node {
+            0 => vec![(1, 2), (2, 4), (3, 10)],
+            1 => vec![(3, 5)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        };
+        let expected = SearchPath {
+            cost: 5,
+            states: vec![0, 2, 3],
+        };
+        assert_eq!(
+            Some(expected.clone()),
+            dijkstra([0], edges, |node| *node == 3)
+        );
+        let heuristic = |node: &u32| if *node == 3 { 0 } else { 1 };
+        assert_eq!(
+            Some(expected),
+            astar([0], edges, heuristic, |node| *node == 3)
+        );
+        assert_eq!(None, dijkstra([1], edges, |node| *node == 2));
+
+        let explored = explore([0], edges);
+        assert_eq!(4, explored.distances.len());
+        assert_eq!(Some(&4), explored.distances.get(&2));
+        assert_eq!(vec![0, 1], explored.path_to(&1).expect("reached").states);
+    }
+
+    #[test]
+    fn test_astar_on_grid() {
+        let maze = maze();
+        let goal: (usize, usize) = (6, 4);
+        let moves = |pos: &(usize, usize)| {
+            maze.neighbours(*pos)
+                .filter(|next| maze[*next])
+                .map(|next| (next, 1))
+                .collect::<Vec<_>>()
+        };
+        let manhattan = |pos: &(usize, usize)| Point2::from(goal).manhattan(Point2::from(*pos));
+        let found = astar([(0, 0)], moves, manhattan, |pos| *pos == goal);
+        let reference = dijkstra([(0, 0)], moves, |pos| *pos == goal);
+        assert_eq!(Some(10), found.as_ref().map(|path| path.cost));
+        assert_eq!(reference.map(|path| path.cost), found.map(|path| path.cost));
+    }
+}
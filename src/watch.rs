@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::day::Registration;
+use crate::examples::find_fixtures;
+use crate::input::{InputError, InputSource};
+use crate::matrix::{Cell, Row};
+
+/// How often the watched files are checked for changes.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The inputs of a day followed by its example fixtures, each as a source of its own. Listed anew
+/// on every poll, so inputs and fixtures added while watching are picked up.
+pub fn watched_sources(
+    registration: &Registration,
+    input: &InputSource,
+    examples_dir: &Path,
+) -> Result<Vec<InputSource>, InputError> {
+    let mut sources = input.inputs(&registration.name())?;
+    // a day without fixtures is still worth watching
+    let fixtures = find_fixtures(examples_dir).unwrap_or_default();
+    sources.extend(
+        fixtures
+            .into_iter()
+            .filter(|fixture| fixture.day == registration.day)
+            .map(|fixture| InputSource::File(fixture.path)),
+    );
+    Ok(sources)
+}
+
+/// The modification times of the watched files; a file that cannot be read has none.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Snapshot(BTreeMap<PathBuf, Option<SystemTime>>);
+
+impl Snapshot {
+    pub fn take(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        Snapshot(
+            paths
+                .into_iter()
+                .map(|path| {
+                    let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+                    (path, modified)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// The answers of a run by input and part; an input that could not be run has a single entry
+/// without a part.
+pub type Answers = BTreeMap<(String, Option<u8>), String>;
+
+pub fn answers(rows: &[Row]) -> Answers {
+    let mut answers = Answers::new();
+    for row in rows {
+        match &row.cells {
+            Ok(cells) => {
+                for (part, cell) in (1..).zip(cells) {
+                    let answer = match cell {
+                        Cell::Solved { answer, .. } => answer.to_string(),
+                        Cell::Panicked(message) => format!("panicked: {message}"),
+                    };
+                    answers.insert((row.input.clone(), Some(part)), answer);
+                }
+            }
+            Err(reason) => {
+                answers.insert((row.input.clone(), None), reason.clone());
+            }
+        }
+    }
+    answers
+}
+
+/// One line per answer, in input and part order.
+pub fn listing(answers: &Answers) -> Vec<String> {
+    answers
+        .iter()
+        .map(|(key, answer)| format!("{}: {answer}", label(key)))
+        .collect()
+}
+
+/// One line per answer that differs between the runs, in input and part order.
+pub fn diff(previous: &Answers, current: &Answers) -> Vec<String> {
+    let mut keys: Vec<_> = previous.keys().chain(current.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter_map(|key| match (previous.get(key), current.get(key)) {
+            (Some(before), Some(after)) if before == after => None,
+            (Some(before), Some(after)) => Some(format!("{}: {before} -> {after}", label(key))),
+            (None, Some(after)) => Some(format!("{}: {after} (new)", label(key))),
+            (Some(before), None) => Some(format!("{}: {before} (gone)", label(key))),
+            (None, None) => unreachable!("keys come from either run"),
+        })
+        .collect()
+}
+
+fn label((input, part): &(String, Option<u8>)) -> String {
+    match part {
+        Some(part) => format!("{input} part {part}"),
+        None => input.clone(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use crate::day::Answer;
+    use crate::input::InputSource;
+    use crate::matrix::{Cell, Row};
+    use crate::watch::{answers, diff, listing, watched_sources, Snapshot};
+
+    fn row(input: &str, cells: Result<Vec<Cell>, String>) -> Row {
+        Row {
+            input: String::from(input),
+            cells,
+        }
+    }
+
+    fn solved(answer: u32) -> Cell {
+        Cell::Solved {
+            answer: Answer::from(answer),
+            time: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = answers(&[
+            row("input", Ok(vec![solved(35), solved(46)])),
+            row("example1", Ok(vec![solved(1), solved(2)])),
+            row("old", Err(String::from("could not read"))),
+        ]);
+        let after = answers(&[
+            row(
+                "input",
+                Ok(vec![solved(35), Cell::Panicked(String::from("oops"))]),
+            ),
+            row("example1", Ok(vec![solved(1), solved(3)])),
+            row("extra", Ok(vec![solved(7), solved(8)])),
+        ]);
+        assert_eq!(
+            vec![
+                "example1 part 2: 2 -> 3",
+                "extra part 1: 7 (new)",
+                "extra part 2: 8 (new)",
+                "input part 2: 46 -> panicked: oops",
+                "old: could not read (gone)",
+            ],
+            diff(&before, &after)
+        );
+        assert!(diff(&after, &after).is_empty());
+        assert_eq!(
+            vec![
+                "example1 part 1: 1",
+                "example1 part 2: 2",
+                "input part 1: 35",
+                "input part 2: 46",
+                "old: could not read"
+            ],
+            listing(&before)
+        );
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let dir = std::env::temp_dir().join(format!("aoc_2023-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("can create the dir");
+        let path = dir.join("day05");
+        let paths = || [path.clone(), dir.join("missing")];
+
+        let missing = Snapshot::take(paths());
+        fs::write(&path, "seeds: 1").expect("can write the input");
+        let written = Snapshot::take(paths());
+        assert_ne!(missing, written);
+        assert_eq!(written, Snapshot::take(paths()));
+        let file = fs::File::options()
+            .append(true)
+            .open(&path)
+            .expect("can open");
+        file.set_modified(std::time::SystemTime::UNIX_EPOCH)
+            .expect("can touch");
+        assert_ne!(written, Snapshot::take(paths()));
+        fs::remove_dir_all(dir).expect("can clean up");
+    }
+
+    #[test]
+    fn test_watched_sources() {
+        let registry = crate::registry();
+        let day08 = registry
+            .iter()
+            .find(|reg| reg.day == 8)
+            .expect("day08 is registered");
+        let sources = watched_sources(day08, &InputSource::default(), &PathBuf::from("examples"))
+            .expect("should list");
+        assert_eq!(
+            vec!["input", "example1", "example2", "example3"],
+            sources.iter().map(InputSource::name).collect::<Vec<_>>()
+        );
+    }
+}
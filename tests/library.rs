@@ -0,0 +1,85 @@
+use std::fs;
+use std::time::Duration;
+
+use aoc_2023::day::{Answer, Day};
+use aoc_2023::day05::{self, Range};
+use aoc_2023::interval::{Interval, IntervalSet};
+use aoc_2023::{day04, day17, registry};
+
+fn example(day: &str, name: &str) -> String {
+    fs::read_to_string(format!("examples/{day}/{name}.txt")).expect("fixture should exist")
+}
+
+#[test]
+fn test_solver_functions() {
+    let map = day17::Solution::parse(&example("day17", "example1")).expect("should parse");
+    assert_eq!(Some(102), day17::solve_p1(&map, 1, 3));
+    assert_eq!(Some(94), day17::solve_p1(&map, 4, 10));
+
+    let cards = day04::Solution::parse(&example("day04", "example1")).expect("should parse");
+    assert_eq!(30, day04::count_scratchcards(&cards));
+    // the first card wins copies of four cards, but the table ends after the second one
+    assert_eq!(3, day04::count_scratchcards(&cards[..2]));
+    assert_eq!(Answer::from(13), day04::Solution::part1(&cards));
+}
+
+#[test]
+fn test_range_mapping() {
+    let map = [Range {
+        destination_range_start: 52,
+        source_range_start: 50,
+        range_length: 48,
+    }];
+    assert_eq!(
+        vec![10, 52, 99],
+        day05::get_destinations(&[10, 50, 97], &map)
+    );
+    assert_eq!(
+        IntervalSet::from_iter([Interval::new(40, 50), Interval::new(52, 62)]),
+        day05::extend_ranges(&Interval::new(40, 60).into(), &map)
+    );
+}
+
+#[test]
+fn test_registry() {
+    let days: Vec<u8> = registry()
+        .iter()
+        .map(|registration| registration.day)
+        .collect();
+    assert_eq!(1, days[0]);
+    assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+    let latest_solved = registry()
+        .iter()
+        .filter(|registration| registration.solved)
+        .map(|registration| registration.day)
+        .max();
+    assert_eq!(Some(20), latest_solved);
+}
+
+/// Going left from `AAA` never leaves it, so both parts of day08 walk until they are cancelled.
+#[test]
+fn test_timeout() {
+    let registry = registry();
+    let day08 = registry
+        .iter()
+        .find(|registration| registration.day == 8)
+        .expect("day08 is registered");
+    let timeout = Duration::from_millis(200);
+    let report = day08
+        .run_with_timeout("L\n\nAAA = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)", timeout)
+        .expect("should parse");
+    assert_eq!(Answer::TimedOut(timeout), report.parts[0].answer);
+    assert_eq!(Answer::TimedOut(timeout), report.parts[1].answer);
+    assert_eq!("timed out after 0.2s", report.parts[1].answer.to_string());
+
+    let day20 = registry
+        .iter()
+        .find(|registration| registration.day == 20)
+        .expect("day20 is registered");
+    // the example has none of the conjunctions part 2 combines
+    let report = day20
+        .run_with_timeout(&example("day20", "example1"), timeout)
+        .expect("should parse");
+    assert_eq!(Answer::from(32000000), report.parts[0].answer);
+    assert_eq!(Answer::Unsolved, report.parts[1].answer);
+}